@@ -11,15 +11,11 @@
 //! QRZ_USERNAME=your_username QRZ_PASSWORD=your_password cargo run --example basic_lookup -- AA7BQ
 //! ```
 
-use qrz_xml::{ApiVersion, QrzXmlClient, QrzXmlError};
+use qrz_xml::{QrzXmlClient, QrzXmlError};
 use std::env;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Get credentials from environment variables
-    let username = env::var("QRZ_USERNAME").expect("QRZ_USERNAME environment variable must be set");
-    let password = env::var("QRZ_PASSWORD").expect("QRZ_PASSWORD environment variable must be set");
-
     // Get callsign from command line arguments
     let args: Vec<String> = env::args().collect();
     if args.len() != 2 {
@@ -29,9 +25,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     let callsign = &args[1];
 
-    // Create client using the current API version
+    // Create client from QRZ_USERNAME and QRZ_PASSWORD
     println!("Creating QRZ client...");
-    let client = QrzXmlClient::new(&username, &password, ApiVersion::Current)?;
+    let client = QrzXmlClient::from_env()?;
 
     // Authenticate (this happens automatically on first request, but we can do it explicitly)
     println!("Authenticating with QRZ.com...");
@@ -41,7 +37,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("Authentication failed: {}", reason);
             std::process::exit(1);
         }
-        Err(QrzXmlError::ConnectionRefused) => {
+        Err(QrzXmlError::ConnectionRefused { .. }) => {
             eprintln!("QRZ is refusing connections. Try again in 24 hours.");
             std::process::exit(1);
         }
@@ -103,14 +99,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             // QSL information
+            let qsl = info.qsl_capabilities();
             let mut qsl_methods = Vec::new();
-            if info.accepts_eqsl() == Some(true) {
+            if qsl.eqsl.is_yes() {
                 qsl_methods.push("eQSL");
             }
-            if info.returns_paper_qsl() == Some(true) {
+            if qsl.paper.is_yes() {
                 qsl_methods.push("Paper QSL");
             }
-            if info.accepts_lotw() == Some(true) {
+            if qsl.lotw.is_yes() {
                 qsl_methods.push("LoTW");
             }
             if !qsl_methods.is_empty() {
@@ -0,0 +1,23 @@
+//! Parser and cache benchmarks against the bundled fixtures.
+//!
+//! Usage:
+//! ```
+//! cargo run --release --example bench --features bench-internal -- [iterations]
+//! ```
+
+use std::env;
+
+fn main() {
+    let iterations = match env::args().nth(1) {
+        Some(arg) => match arg.parse() {
+            Ok(iterations) => iterations,
+            Err(_) => {
+                eprintln!("Usage: bench [iterations]");
+                std::process::exit(1);
+            }
+        },
+        None => 10_000,
+    };
+
+    print!("{}", qrz_xml::bench::run(iterations));
+}
@@ -9,7 +9,7 @@
 //!
 //! Usage:
 //! ```
-//! QRZ_USERNAME=your_username QRZ_PASSWORD=your_password cargo run --example bulk_lookup -- callsigns.txt output.csv
+//! QRZ_USERNAME=your_username QRZ_PASSWORD=your_password cargo run --example bulk_lookup --features csv -- callsigns.txt output.csv
 //! ```
 //!
 //! Input file format (one callsign per line):
@@ -20,10 +20,10 @@
 //! JA1ABC
 //! ```
 
-use qrz_xml::{ApiVersion, CallsignInfo, QrzXmlClient, QrzXmlError};
+use qrz_xml::{CallsignInfo, QrzXmlClient, QrzXmlError};
 use std::env;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader};
 use std::path::Path;
 use std::time::{Duration, Instant};
 use tokio::time::sleep;
@@ -147,7 +147,7 @@ async fn lookup_with_retry(
                     QrzXmlError::CallsignNotFound { .. }
                     | QrzXmlError::SubscriptionRequired
                     | QrzXmlError::AuthenticationFailed { .. }
-                    | QrzXmlError::ConnectionRefused => break,
+                    | QrzXmlError::ConnectionRefused { .. } => break,
                     _ => {}
                 }
 
@@ -173,41 +173,9 @@ fn write_csv_output<P: AsRef<Path>>(
     filename: P,
     results: &[LookupResult],
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut file = File::create(filename)?;
-
-    // Write CSV header
-    writeln!(
-        file,
-        "callsign,success,name,country,grid,lat,lon,email,class,dxcc,error"
-    )?;
-
-    for result in results {
-        if let Some(info) = &result.info {
-            writeln!(
-                file,
-                "{},{},{},{},{},{},{},{},{},{},",
-                result.callsign,
-                result.success,
-                info.full_name().unwrap_or_default().replace(',', ";"),
-                info.country.as_deref().unwrap_or("").replace(',', ";"),
-                info.grid.as_deref().unwrap_or(""),
-                info.lat.map(|l| l.to_string()).unwrap_or_default(),
-                info.lon.map(|l| l.to_string()).unwrap_or_default(),
-                info.email.as_deref().unwrap_or(""),
-                info.class.as_deref().unwrap_or(""),
-                info.dxcc.map(|d| d.to_string()).unwrap_or_default(),
-            )?;
-        } else {
-            writeln!(
-                file,
-                "{},{},,,,,,,,,{}",
-                result.callsign,
-                result.success,
-                result.error.as_deref().unwrap_or("").replace(',', ";"),
-            )?;
-        }
-    }
-
+    // Failed lookups are reported on the console; the CSV holds the records found
+    let records = results.iter().filter_map(|result| result.info.as_ref());
+    qrz_xml::csv::write_callsigns(File::create(filename)?, records)?;
     Ok(())
 }
 
@@ -223,10 +191,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let input_file = &args[1];
     let output_file = &args[2];
 
-    // Get credentials
-    let username = env::var("QRZ_USERNAME").expect("QRZ_USERNAME environment variable must be set");
-    let password = env::var("QRZ_PASSWORD").expect("QRZ_PASSWORD environment variable must be set");
-
     // Read callsigns from file
     println!("Reading callsigns from: {}", input_file);
     let callsigns = read_callsigns_from_file(input_file)?;
@@ -237,9 +201,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
 
-    // Create client
+    // Create client from QRZ_USERNAME and QRZ_PASSWORD
     println!("Creating QRZ client...");
-    let client = QrzXmlClient::new(&username, &password, ApiVersion::Current)?;
+    let client = QrzXmlClient::from_env()?;
 
     // Authenticate
     println!("Authenticating with QRZ.com...");
@@ -321,6 +285,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nWriting results to: {}", output_file);
     write_csv_output(output_file, &results)?;
 
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|result| !result.success)
+        .map(|result| result.callsign.as_str())
+        .collect();
+    if !failed.is_empty() {
+        println!("Not in the output (lookup failed): {}", failed.join(", "));
+    }
+
     println!("Bulk lookup completed successfully!");
 
     // Provide guidance on results
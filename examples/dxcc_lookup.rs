@@ -14,7 +14,7 @@
 //! QRZ_USERNAME=your_username QRZ_PASSWORD=your_password cargo run --example dxcc_lookup -- --callsign JA1ABC
 //! ```
 
-use qrz_xml::{ApiVersion, QrzXmlClient, QrzXmlError};
+use qrz_xml::{QrzXmlClient, QrzXmlError};
 use std::env;
 
 #[derive(Debug)]
@@ -59,13 +59,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    // Get credentials from environment variables
-    let username = env::var("QRZ_USERNAME").expect("QRZ_USERNAME environment variable must be set");
-    let password = env::var("QRZ_PASSWORD").expect("QRZ_PASSWORD environment variable must be set");
-
-    // Create client
+    // Create client from QRZ_USERNAME and QRZ_PASSWORD
     println!("Creating QRZ client...");
-    let client = QrzXmlClient::new(&username, &password, ApiVersion::Current)?;
+    let client = QrzXmlClient::from_env()?;
 
     // Authenticate
     println!("Authenticating with QRZ.com...");
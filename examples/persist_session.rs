@@ -1,207 +1,50 @@
 //! Example demonstrating XDG-compliant session token storage
 //!
 //! This example shows how to persist QRZ session tokens using XDG Base Directory
-//! specification for cross-platform cache storage.
+//! specification for cross-platform cache storage. The client loads a previous
+//! session from the store when it first needs one, saves every new session and
+//! clears the stored one when QRZ reports it expired.
 //!
 //! Usage:
 //! ```
 //! QRZ_USERNAME=your_username QRZ_PASSWORD=your_password cargo run --example persist_session
 //! ```
 
-use qrz_xml::{ApiVersion, QrzXmlClient, QrzXmlError};
-use serde::{Deserialize, Serialize};
+use qrz_xml::{ApiVersion, FileSessionStore, QrzXmlClient, SessionStore};
 use std::env;
-use std::fs;
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct CachedSession {
-    session_key: String,
-    username: String,
-    created_at: u64,
-    expires_at: Option<String>,
-    lookup_count: Option<u32>,
-}
-
-impl CachedSession {
-    fn new(
-        session_key: String,
-        username: String,
-        expires_at: Option<String>,
-        lookup_count: Option<u32>,
-    ) -> Self {
-        let created_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
-        Self {
-            session_key,
-            username,
-            created_at,
-            expires_at,
-            lookup_count,
-        }
-    }
-
-    fn is_expired(&self) -> bool {
-        // Consider sessions older than 23 hours as potentially expired
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let username = env::var("QRZ_USERNAME")?;
+    let password = env::var("QRZ_PASSWORD")?;
 
-        now - self.created_at > 23 * 3600
-    }
-}
+    let store = Arc::new(FileSessionStore::xdg()?);
 
-struct XdgSessionStore {
-    cache_dir: PathBuf,
-}
+    println!("QRZ XML client with XDG session storage");
+    println!("Cache directory: {:?}", store.dir());
 
-impl XdgSessionStore {
-    fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let cache_dir = if let Ok(xdg_cache) = env::var("XDG_CACHE_HOME") {
-            PathBuf::from(xdg_cache)
-        } else if let Ok(home) = env::var("HOME") {
-            PathBuf::from(home).join(".cache")
+    if let Some(cached) = store.load(&username)? {
+        if cached.is_expired() {
+            println!("Cached session is too old and will be replaced");
         } else {
-            return Err("Cannot determine cache directory".into());
-        };
-
-        let app_cache_dir = cache_dir.join("qrz-xml");
-        fs::create_dir_all(&app_cache_dir)?;
-
-        Ok(Self {
-            cache_dir: app_cache_dir,
-        })
-    }
-
-    fn session_file_path(&self, username: &str) -> PathBuf {
-        self.cache_dir.join(format!("session_{}.json", username))
-    }
-
-    fn load_session(&self, username: &str) -> Option<CachedSession> {
-        let path = self.session_file_path(username);
-
-        let content = fs::read_to_string(path).ok()?;
-        let session: CachedSession = serde_json::from_str(&content).ok()?;
-
-        if session.is_expired() {
-            self.clear_session(username);
-            return None;
-        }
-
-        Some(session)
-    }
-
-    fn save_session(&self, session: &CachedSession) -> Result<(), Box<dyn std::error::Error>> {
-        let path = self.session_file_path(&session.username);
-        let content = serde_json::to_string_pretty(session)?;
-        fs::write(path, content)?;
-        Ok(())
-    }
-
-    fn clear_session(&self, username: &str) {
-        let path = self.session_file_path(username);
-        let _ = fs::remove_file(path);
-    }
-}
-
-/// Custom QRZ client with XDG session persistence
-struct PersistentQrzXmlClient {
-    client: QrzXmlClient,
-    store: XdgSessionStore,
-    username: String,
-}
-
-impl PersistentQrzXmlClient {
-    fn new(
-        username: impl Into<String>,
-        password: impl Into<String>,
-        api_version: ApiVersion,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
-        let username_str = username.into();
-        let client = QrzXmlClient::new(&username_str, password, api_version)?;
-        let store = XdgSessionStore::new()?;
-
-        Ok(Self {
-            client,
-            store,
-            username: username_str,
-        })
-    }
-
-    async fn ensure_authenticated(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Try to load cached session
-        if let Some(cached) = self.store.load_session(&self.username) {
             println!(
                 "Found cached session from {}",
-                chrono::DateTime::from_timestamp(cached.created_at as i64, 0)
+                chrono::DateTime::from_timestamp(cached.saved_at as i64, 0)
                     .unwrap()
                     .format("%Y-%m-%d %H:%M:%S")
             );
-
-            // Test if the cached session still works
-            match self.client.lookup_callsign("AA7BQ").await {
-                Ok(_) => {
-                    println!("Cached session is still valid");
-                    return Ok(());
-                }
-                Err(
-                    QrzXmlError::SessionExpired
-                    | QrzXmlError::NoSessionKey
-                    | QrzXmlError::ApiError { .. },
-                ) => {
-                    println!("Cached session expired, re-authenticating...");
-                    self.store.clear_session(&self.username);
-                }
-                Err(e) => return Err(e.into()),
-            }
-        }
-
-        // Authenticate and cache the new session
-        self.client.authenticate().await?;
-
-        if let Some((count, sub_exp)) = self.client.session_info().await {
-            // Note: In a real implementation, you'd need to extract the actual session key
-            // from the client. This would require exposing it or creating a custom client.
-            let dummy_session = CachedSession::new(
-                "session_key_placeholder".to_string(),
-                self.username.clone(),
-                sub_exp.clone(),
-                count,
-            );
-
-            self.store.save_session(&dummy_session)?;
-            println!("New session cached, expires at: {:?}", sub_exp.as_deref());
         }
-
-        Ok(())
-    }
-
-    async fn lookup_callsign(
-        &mut self,
-        callsign: &str,
-    ) -> Result<qrz_xml::CallsignInfo, Box<dyn std::error::Error>> {
-        self.ensure_authenticated().await?;
-        Ok(self.client.lookup_callsign(callsign).await?)
     }
-}
-
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let username = env::var("QRZ_USERNAME")?;
-    let password = env::var("QRZ_PASSWORD")?;
 
-    let mut client = PersistentQrzXmlClient::new(&username, &password, ApiVersion::Current)?;
+    let client = QrzXmlClient::new(&username, &password, ApiVersion::Current)?
+        .with_session_store(store.clone());
 
-    println!("QRZ XML client with XDG session storage");
-    println!("Cache directory: {:?}", client.store.cache_dir);
+    // Reuses the cached session, or logs in and caches a new one
+    client.ensure_authenticated().await?;
 
-    // First lookup - will authenticate and cache session
-    println!("\nFirst lookup (will cache session):");
+    // First lookup - uses the session established above
+    println!("\nFirst lookup:");
     let info = client.lookup_callsign("AA7BQ").await?;
     println!(
         "Found: {} - {}",
@@ -209,7 +52,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         info.full_name().unwrap_or_default()
     );
 
-    // Second lookup - should use cached session
+    // Second lookup - uses the same session
     println!("\nSecond lookup (should use cached session):");
     let info = client.lookup_callsign("W1AW").await?;
     println!(
@@ -219,18 +62,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Show cache file location
-    let session_file = client.store.session_file_path(&username);
-    if session_file.exists() {
+    let session_file = store.session_file_path(&username);
+    if let Some(cached) = store.load(&username)? {
         println!("\nSession cached at: {}", session_file.display());
-        let content = fs::read_to_string(&session_file)?;
-        let cached: CachedSession = serde_json::from_str(&content)?;
         println!(
-            "Session created: {}",
-            chrono::DateTime::from_timestamp(cached.created_at as i64, 0)
+            "Session saved: {}",
+            chrono::DateTime::from_timestamp(cached.saved_at as i64, 0)
                 .unwrap()
                 .format("%Y-%m-%d %H:%M:%S")
         );
-        if let Some(expires) = cached.expires_at {
+        if let Some(expires) = cached.sub_exp {
             println!("Subscription expires: {}", expires);
         }
     }
@@ -240,19 +81,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use qrz_xml::{FileSessionStore, SessionStore, StoredSession};
     use tempfile::TempDir;
 
     #[test]
     fn test_cached_session_expiry() {
-        let session =
-            CachedSession::new("test_key".to_string(), "test_user".to_string(), None, None);
+        let session = StoredSession::new("test_user", "test_key");
 
         assert!(!session.is_expired());
 
         // Create an old session
-        let old_session = CachedSession {
-            created_at: 0, // Unix epoch
+        let old_session = StoredSession {
+            saved_at: 0, // Unix epoch
             ..session
         };
 
@@ -262,28 +102,25 @@ mod tests {
     #[tokio::test]
     async fn test_xdg_store() -> Result<(), Box<dyn std::error::Error>> {
         let temp_dir = TempDir::new()?;
-        let store = XdgSessionStore {
-            cache_dir: temp_dir.path().to_path_buf(),
-        };
+        let store = FileSessionStore::new(temp_dir.path())?;
 
-        let session = CachedSession::new(
-            "test_key".to_string(),
-            "testuser".to_string(),
-            Some("2025-12-31".to_string()),
-            Some(42),
-        );
+        let session = StoredSession {
+            sub_exp: Some("2025-12-31".to_string()),
+            count: Some(42),
+            ..StoredSession::new("testuser", "test_key")
+        };
 
         // Save and load
-        store.save_session(&session)?;
-        let loaded = store.load_session("testuser").unwrap();
+        store.save(&session)?;
+        let loaded = store.load("testuser")?.unwrap();
 
-        assert_eq!(loaded.session_key, "test_key");
+        assert_eq!(loaded.key, "test_key");
         assert_eq!(loaded.username, "testuser");
-        assert_eq!(loaded.lookup_count, Some(42));
+        assert_eq!(loaded.count, Some(42));
 
         // Clear session
-        store.clear_session("testuser");
-        assert!(store.load_session("testuser").is_none());
+        store.clear("testuser")?;
+        assert!(store.load("testuser")?.is_none());
 
         Ok(())
     }
@@ -0,0 +1,211 @@
+//! Deterministic benchmarks of the parsing and caching layers.
+//!
+//! Enabled with the `bench-internal` feature. The benchmarks run against
+//! fixtures bundled with the crate and never touch the network, so results
+//! from two versions of the crate on the same machine are directly comparable.
+//! Run them with `cargo run --release --example bench --features bench-internal`
+//! before upgrading to check for regressions in bulk lookup paths.
+
+use crate::cache::TtlCache;
+use crate::prefix::DxccPrefixTable;
+use crate::types::{BiographyOutcome, CallsignInfo, QrzXmlResponse};
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// A full callsign lookup response
+pub const CALLSIGN_FIXTURE: &str = include_str!("../fixtures/callsign.xml");
+/// A DXCC entity lookup response
+pub const DXCC_FIXTURE: &str = include_str!("../fixtures/dxcc.xml");
+/// A large biography page
+pub const BIOGRAPHY_FIXTURE: &str = include_str!("../fixtures/biography.html");
+
+/// Distinct keys used by the cache benchmarks, below the cache's entry limit
+const CACHE_KEYS: usize = 5_000;
+
+/// Timing of one benchmark
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    /// Benchmark name
+    pub name: &'static str,
+    /// Operations performed
+    pub operations: u64,
+    /// Input bytes processed, for benchmarks that consume fixtures
+    pub bytes: u64,
+    /// Total time taken
+    pub elapsed: Duration,
+}
+
+impl BenchResult {
+    /// Operations per second
+    pub fn ops_per_sec(&self) -> f64 {
+        self.operations as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Input throughput in MiB per second, if the benchmark consumes input
+    pub fn mib_per_sec(&self) -> Option<f64> {
+        if self.bytes == 0 {
+            return None;
+        }
+        Some(self.bytes as f64 / (1024.0 * 1024.0) / self.elapsed.as_secs_f64().max(f64::EPSILON))
+    }
+}
+
+impl fmt::Display for BenchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<20} {:>10} ops {:>14.0} ops/s",
+            self.name,
+            self.operations,
+            self.ops_per_sec()
+        )?;
+        if let Some(mib) = self.mib_per_sec() {
+            write!(f, " {:>10.2} MiB/s", mib)?;
+        }
+        Ok(())
+    }
+}
+
+/// Results of a benchmark run
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    /// Iterations each benchmark was run with
+    pub iterations: u32,
+    /// One result per benchmark, in a fixed order
+    pub results: Vec<BenchResult>,
+}
+
+impl BenchReport {
+    /// Result of the named benchmark
+    pub fn get(&self, name: &str) -> Option<&BenchResult> {
+        self.results.iter().find(|r| r.name == name)
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "qrz-xml {} benchmarks, {} iterations",
+            env!("CARGO_PKG_VERSION"),
+            self.iterations
+        )?;
+        for result in &self.results {
+            writeln!(f, "{}", result)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run all benchmarks with the given number of iterations each
+pub fn run(iterations: u32) -> BenchReport {
+    let iterations = iterations.max(1);
+    let calls: Vec<String> = (0..CACHE_KEYS).map(|i| format!("K{}AB", i)).collect();
+    let record = parse(CALLSIGN_FIXTURE)
+        .callsign
+        .expect("bundled callsign fixture");
+
+    let mut prefixes = DxccPrefixTable::new();
+    for (prefix, dxcc) in [("K", 291), ("W", 291), ("N", 291), ("VE", 1), ("JA", 339)] {
+        prefixes.insert_prefix(prefix, dxcc);
+    }
+
+    let results = vec![
+        measure("parse_callsign", iterations, CALLSIGN_FIXTURE.len(), || {
+            black_box(parse(black_box(CALLSIGN_FIXTURE)));
+        }),
+        measure("parse_dxcc", iterations, DXCC_FIXTURE.len(), || {
+            black_box(parse(black_box(DXCC_FIXTURE)));
+        }),
+        measure(
+            "classify_biography",
+            iterations,
+            BIOGRAPHY_FIXTURE.len(),
+            || {
+                black_box(BiographyOutcome::classify(black_box(BIOGRAPHY_FIXTURE)));
+            },
+        ),
+        bench_cache(iterations, &calls, &record),
+        measure("prefix_resolve", iterations, 0, {
+            let mut next = calls.iter().cycle();
+            move || {
+                black_box(prefixes.resolve(black_box(next.next().unwrap())));
+            }
+        }),
+    ];
+
+    BenchReport {
+        iterations,
+        results,
+    }
+}
+
+/// Insert and read back callsign records, cycling through a fixed key set
+fn bench_cache(iterations: u32, calls: &[String], record: &CallsignInfo) -> BenchResult {
+    let cache = TtlCache::new(Some(Duration::from_secs(3600)));
+    let started = Instant::now();
+    for i in 0..iterations as usize {
+        let call = &calls[i % calls.len()];
+        cache.insert(call.clone(), record.clone(), Instant::now(), None);
+        black_box(cache.get(call, Instant::now()));
+    }
+    BenchResult {
+        name: "cache_insert_get",
+        operations: u64::from(iterations) * 2,
+        bytes: 0,
+        elapsed: started.elapsed(),
+    }
+}
+
+fn measure(
+    name: &'static str,
+    iterations: u32,
+    input_len: usize,
+    mut operation: impl FnMut(),
+) -> BenchResult {
+    let started = Instant::now();
+    for _ in 0..iterations {
+        operation();
+    }
+    BenchResult {
+        name,
+        operations: u64::from(iterations),
+        bytes: u64::from(iterations) * input_len as u64,
+        elapsed: started.elapsed(),
+    }
+}
+
+fn parse(xml: &str) -> QrzXmlResponse {
+    quick_xml::de::from_str(xml).expect("bundled fixtures parse")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_parse() {
+        let callsign = parse(CALLSIGN_FIXTURE).callsign.unwrap();
+        assert_eq!(callsign.call, "AA7BQ");
+        assert_eq!(parse(DXCC_FIXTURE).dxcc.unwrap().dxcc, 291);
+        assert!(matches!(
+            BiographyOutcome::classify(BIOGRAPHY_FIXTURE),
+            BiographyOutcome::Html(_)
+        ));
+    }
+
+    #[test]
+    fn test_run_reports_every_benchmark() {
+        let report = run(3);
+        assert_eq!(report.results.len(), 5);
+        assert_eq!(report.get("parse_callsign").unwrap().operations, 3);
+        assert_eq!(report.get("cache_insert_get").unwrap().operations, 6);
+        assert!(report
+            .get("prefix_resolve")
+            .unwrap()
+            .mib_per_sec()
+            .is_none());
+        assert!(report.to_string().contains("classify_biography"));
+    }
+}
@@ -0,0 +1,156 @@
+//! Builders for response types.
+//!
+//! [`CallsignInfo`] and [`DxccInfo`] are `#[non_exhaustive]`, so code outside
+//! this crate cannot use struct literals for them. Tests and mock backends
+//! build values with [`CallsignInfo::builder`] and [`DxccInfo::builder`]
+//! instead, setting only the fields they care about:
+//!
+//! ```rust
+//! use qrz_xml::CallsignInfo;
+//!
+//! let info = CallsignInfo::builder().call("AA7BQ").grid("DM32af").dxcc(291).build();
+//! assert_eq!(info.grid.as_deref(), Some("DM32af"));
+//! ```
+
+use crate::types::{CallsignInfo, DxccInfo};
+
+/// Generate a setter for each optional text field
+macro_rules! text_setters {
+    ($target:ident; $($field:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Set `", stringify!($field), "`")]
+            pub fn $field(mut self, value: impl Into<String>) -> Self {
+                self.$target.$field = Some(value.into());
+                self
+            }
+        )*
+    };
+}
+
+/// Generate a setter for each optional numeric field
+macro_rules! value_setters {
+    ($target:ident; $($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Set `", stringify!($field), "`")]
+            pub fn $field(mut self, value: $ty) -> Self {
+                self.$target.$field = Some(value);
+                self
+            }
+        )*
+    };
+}
+
+/// Builder for [`CallsignInfo`], started with [`CallsignInfo::builder`]
+///
+/// Fields that are not set stay `None`.
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct CallsignInfoBuilder {
+    info: CallsignInfo,
+}
+
+impl CallsignInfoBuilder {
+    /// Set the primary callsign
+    pub fn call(mut self, call: impl Into<String>) -> Self {
+        self.info.call = call.into();
+        self
+    }
+
+    /// Add an element this crate does not model
+    pub fn extra(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.info.extra.insert(name.into(), value.into());
+        self
+    }
+
+    text_setters!(info;
+        xref, aliases, fname, name, addr1, addr2, state, zip, country, grid, county, fips,
+        land, efdate, expdate, p_call, class, codes, qslmgr, email, url, biodate, image,
+        imageinfo, moddate, msa, area_code, time_zone, gmt_offset, dst, eqsl, mqsl, user, lotw,
+        iota, geoloc, attn, nickname, name_fmt, queried_callsign
+    );
+
+    value_setters!(info;
+        dxcc: u32, ccode: u32, lat: f64, lon: f64, u_views: u32, bio: u64, serial: u32,
+        cqzone: u32, ituzone: u32, born: u32
+    );
+
+    /// Finish the record
+    pub fn build(self) -> CallsignInfo {
+        self.info
+    }
+}
+
+/// Builder for [`DxccInfo`], started with [`DxccInfo::builder`]
+///
+/// Fields that are not set stay `None`.
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct DxccInfoBuilder {
+    dxcc: DxccInfo,
+}
+
+impl DxccInfoBuilder {
+    /// Set the DXCC entity number
+    pub fn dxcc(mut self, dxcc: u32) -> Self {
+        self.dxcc.dxcc = dxcc;
+        self
+    }
+
+    /// Set the entity name
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.dxcc.name = name.into();
+        self
+    }
+
+    /// Add an element this crate does not model
+    pub fn extra(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.dxcc.extra.insert(name.into(), value.into());
+        self
+    }
+
+    text_setters!(dxcc;
+        cc, ccc, continent, timezone, notes
+    );
+
+    value_setters!(dxcc;
+        ituzone: u32, cqzone: u32, lat: f64, lon: f64
+    );
+
+    /// Finish the entity
+    pub fn build(self) -> DxccInfo {
+        self.dxcc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builders_set_fields() {
+        let info = CallsignInfo::builder()
+            .call("AA7BQ")
+            .grid("DM32af")
+            .dxcc(291)
+            .lat(34.23)
+            .extra("newfield", "x")
+            .build();
+        assert_eq!(info.call, "AA7BQ");
+        assert_eq!(info.grid.as_deref(), Some("DM32af"));
+        assert_eq!(
+            (info.dxcc, info.lat, info.lon),
+            (Some(291), Some(34.23), None)
+        );
+        assert_eq!(info.extra["newfield"], "x");
+
+        let dxcc = DxccInfo::builder()
+            .dxcc(291)
+            .name("United States")
+            .cc("US")
+            .build();
+        assert_eq!(dxcc.dxcc, 291);
+        assert_eq!(dxcc.name, "United States");
+        assert_eq!(dxcc.cc.as_deref(), Some("US"));
+        assert_eq!(dxcc.continent, None);
+    }
+}
@@ -0,0 +1,180 @@
+//! Time-bounded lookup caches.
+//!
+//! DXCC entity data changes a few times a year at most, while callsign records
+//! change whenever an operator edits their page. The client therefore keeps an
+//! independent cache per kind of data, each with its own time-to-live.
+//!
+//! Callsign records can additionally get adaptive time-to-lives: a record whose
+//! `moddate` is unchanged each time it is refreshed is evidently stable, so its
+//! time-to-live doubles with every such refresh, while an edited record starts
+//! over with the base time-to-live.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Entries kept before expired ones are purged and new ones are refused
+const MAX_ENTRIES: usize = 10_000;
+
+/// Thread-safe map whose entries expire after a time-to-live
+#[derive(Debug)]
+pub(crate) struct TtlCache<K, V> {
+    ttl: Option<Duration>,
+    /// Values with their expiry time
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    /// Create a cache with the given time-to-live; `None` disables caching
+    pub(crate) fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Remove all entries
+    pub(crate) fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Get a cached value if it has not expired at `now`
+    pub(crate) fn get(&self, key: &K, now: Instant) -> Option<V> {
+        self.ttl?;
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(expires_at, _)| now < *expires_at)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Get a cached value whether or not it has expired
+    pub(crate) fn get_stale(&self, key: &K) -> Option<V> {
+        self.ttl?;
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.get(key).map(|(_, value)| value.clone())
+    }
+
+    /// Whether the cache is enabled, the number of entries held and how many of them expired at `now`
+    pub(crate) fn stats(&self, now: Instant) -> (bool, usize, usize) {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let expired = entries
+            .values()
+            .filter(|(expires_at, _)| now >= *expires_at)
+            .count();
+        (self.ttl.is_some(), entries.len(), expired)
+    }
+
+    /// Cache a value at `now`, with its own time-to-live or the cache's default
+    ///
+    /// Ignored if the cache is disabled.
+    pub(crate) fn insert(&self, key: K, value: V, now: Instant, ttl: Option<Duration>) {
+        let Some(default_ttl) = self.ttl else {
+            return;
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(&key) {
+            entries.retain(|_, (expires_at, _)| now < *expires_at);
+            if entries.len() >= MAX_ENTRIES {
+                return;
+            }
+        }
+        entries.insert(key, (now + ttl.unwrap_or(default_ttl), value));
+    }
+}
+
+/// Per-record time-to-lives that grow while a record's `moddate` stays the same
+#[derive(Debug)]
+pub(crate) struct AdaptiveTtl {
+    base: Duration,
+    max: Duration,
+    /// Last seen modification date and number of refreshes without a change
+    history: Mutex<HashMap<String, (Option<String>, u32)>>,
+}
+
+impl AdaptiveTtl {
+    pub(crate) fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max: max.max(base),
+            history: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a refreshed record's modification date and get its time-to-live
+    pub(crate) fn ttl_for(&self, key: &str, moddate: Option<&str>) -> Duration {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let unchanged = match history.get(key) {
+            Some((previous, unchanged)) if moddate.is_some() && previous.as_deref() == moddate => {
+                unchanged + 1
+            }
+            _ => 0,
+        };
+
+        if history.len() < MAX_ENTRIES || history.contains_key(key) {
+            history.insert(key.to_string(), (moddate.map(str::to_string), unchanged));
+        }
+
+        self.base
+            .saturating_mul(1 << unchanged.min(16))
+            .min(self.max)
+    }
+
+    pub(crate) fn clear(&self) {
+        self.history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_cache() {
+        let cache = TtlCache::new(Some(Duration::from_secs(60)));
+        let start = Instant::now();
+
+        cache.insert("291", 1, start, None);
+        assert_eq!(cache.get(&"291", start + Duration::from_secs(59)), Some(1));
+        assert_eq!(cache.get(&"291", start + Duration::from_secs(60)), None);
+        assert_eq!(cache.get_stale(&"291"), Some(1));
+        assert_eq!(cache.stats(start), (true, 1, 0));
+        assert_eq!(cache.stats(start + Duration::from_secs(60)), (true, 1, 1));
+
+        cache.clear();
+        assert_eq!(cache.get(&"291", start), None);
+
+        cache.insert("1", 1, start, Some(Duration::from_secs(120)));
+        assert_eq!(cache.get(&"1", start + Duration::from_secs(90)), Some(1));
+
+        let disabled = TtlCache::new(None);
+        disabled.insert("291", 1, start, None);
+        assert_eq!(disabled.get(&"291", start), None);
+    }
+
+    #[test]
+    fn test_adaptive_ttl() {
+        let hour = Duration::from_secs(3600);
+        let adaptive = AdaptiveTtl::new(hour, hour * 6);
+
+        assert_eq!(adaptive.ttl_for("AA7BQ", Some("2003-11-04")), hour);
+        assert_eq!(adaptive.ttl_for("AA7BQ", Some("2003-11-04")), hour * 2);
+        assert_eq!(adaptive.ttl_for("AA7BQ", Some("2003-11-04")), hour * 4);
+        assert_eq!(adaptive.ttl_for("AA7BQ", Some("2003-11-04")), hour * 6);
+
+        // An edit starts over
+        assert_eq!(adaptive.ttl_for("AA7BQ", Some("2024-08-16")), hour);
+
+        // Records without a modification date never grow
+        assert_eq!(adaptive.ttl_for("W1AW", None), hour);
+        assert_eq!(adaptive.ttl_for("W1AW", None), hour);
+    }
+}
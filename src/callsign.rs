@@ -0,0 +1,270 @@
+//! Structured callsigns.
+//!
+//! Operators sign with more than their base callsign: `EA8/DL1ABC/P` is DL1ABC
+//! operating portable from the Canary Islands. [`Callsign::parse`] validates a
+//! callsign the way [`validate::check_callsign`](crate::validate::check_callsign)
+//! does and splits it into its prefix, base callsign and suffix. The lookup
+//! methods of [`QrzXmlClient`](crate::QrzXmlClient) accept a `Callsign` as well
+//! as a plain string.
+//!
+//! QRZ often has no record for `W1AW/P` or `DL/N0CALL` as written. A
+//! [`CallsignNormalization`] rewrites such callsigns before the lookup.
+
+use crate::error::{QrzXmlError, Result};
+use crate::validate::{check_callsign, is_base_callsign};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Operating suffix after the base callsign
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CallsignSuffix {
+    /// `/P`
+    Portable,
+    /// `/M`
+    Mobile,
+    /// `/MM`
+    MaritimeMobile,
+    /// `/AM`
+    AeronauticalMobile,
+    /// `/QRP`
+    Qrp,
+    /// Any other suffix, such as a call area digit
+    Other(String),
+}
+
+impl CallsignSuffix {
+    fn from_part(part: &str) -> Self {
+        match part {
+            "P" => CallsignSuffix::Portable,
+            "M" => CallsignSuffix::Mobile,
+            "MM" => CallsignSuffix::MaritimeMobile,
+            "AM" => CallsignSuffix::AeronauticalMobile,
+            "QRP" => CallsignSuffix::Qrp,
+            other => CallsignSuffix::Other(other.to_string()),
+        }
+    }
+}
+
+/// How a callsign is rewritten before it is looked up
+///
+/// Callsigns that do not parse are looked up as given.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CallsignNormalization {
+    /// Look up the callsign as given
+    #[default]
+    None,
+    /// Drop operating suffixes (`/P`, `/M`, `/MM`, `/AM`, `/QRP`), keeping
+    /// location prefixes and other suffixes
+    StripOperatingSuffix,
+    /// Look up the base callsign only, e.g. `N0CALL` for `DL/N0CALL/P`
+    BaseCall,
+}
+
+impl CallsignNormalization {
+    /// The callsign to look up in place of `callsign`
+    pub fn apply(self, callsign: &str) -> String {
+        if self == CallsignNormalization::None {
+            return callsign.to_string();
+        }
+        let Ok(parsed) = Callsign::parse(callsign) else {
+            return callsign.to_string();
+        };
+
+        match (self, parsed.suffix) {
+            (CallsignNormalization::BaseCall, _) => parsed.base,
+            (_, Some(CallsignSuffix::Other(_)) | None) => parsed.text,
+            (_, Some(_)) => match parsed.prefix {
+                Some(prefix) => format!("{}/{}", prefix, parsed.base),
+                None => parsed.base,
+            },
+        }
+    }
+}
+
+/// A validated callsign, split into prefix, base callsign and suffix
+///
+/// Kept in upper case. Serializes as its string form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Callsign {
+    text: String,
+    prefix: Option<String>,
+    base: String,
+    suffix: Option<CallsignSuffix>,
+}
+
+impl Callsign {
+    /// Validate and decompose a callsign such as `EA8/DL1ABC/P`
+    pub fn parse(callsign: &str) -> Result<Self> {
+        check_callsign(callsign)?;
+        let text = callsign.trim().to_ascii_uppercase();
+        let parts: Vec<&str> = text.split('/').collect();
+
+        let (prefix, base, suffix) = match parts[..] {
+            [base] => (None, base, None),
+            [first, second] => {
+                if is_base_callsign(first)
+                    && (!is_base_callsign(second) || base_rank(first) >= base_rank(second))
+                {
+                    (None, first, Some(second))
+                } else {
+                    (Some(first), second, None)
+                }
+            }
+            [prefix, base, suffix] if is_base_callsign(base) => (Some(prefix), base, Some(suffix)),
+            _ => {
+                return Err(QrzXmlError::invalid_input(format!(
+                    "{} is not a plausible callsign",
+                    text
+                )))
+            }
+        };
+
+        Ok(Self {
+            prefix: prefix.map(str::to_string),
+            base: base.to_string(),
+            suffix: suffix.map(CallsignSuffix::from_part),
+            text,
+        })
+    }
+
+    /// The full callsign, including prefix and suffix
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The base callsign, e.g. `DL1ABC` for `EA8/DL1ABC/P`
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// The location prefix, e.g. `EA8` for `EA8/DL1ABC/P`
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    /// The operating suffix, e.g. portable for `EA8/DL1ABC/P`
+    pub fn suffix(&self) -> Option<&CallsignSuffix> {
+        self.suffix.as_ref()
+    }
+
+    /// The station is operating portable (`/P`)
+    pub fn is_portable(&self) -> bool {
+        self.suffix == Some(CallsignSuffix::Portable)
+    }
+}
+
+/// How much a part looks like the base callsign rather than a location prefix
+///
+/// Location prefixes such as `VP2E` rarely end in more than one letter, so the part with
+/// more letters after its last digit is taken as the base, then the longer part.
+fn base_rank(part: &str) -> (usize, usize) {
+    let suffix_letters = part
+        .bytes()
+        .rev()
+        .take_while(u8::is_ascii_alphabetic)
+        .count();
+    (suffix_letters, part.len())
+}
+
+impl FromStr for Callsign {
+    type Err = QrzXmlError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Callsign {
+    type Error = QrzXmlError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Callsign> for String {
+    fn from(callsign: Callsign) -> Self {
+        callsign.text
+    }
+}
+
+impl AsRef<str> for Callsign {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for Callsign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_decomposes() {
+        let call = Callsign::parse("ea8/dl1abc/p").unwrap();
+        assert_eq!(call.as_str(), "EA8/DL1ABC/P");
+        assert_eq!(call.prefix(), Some("EA8"));
+        assert_eq!(call.base(), "DL1ABC");
+        assert!(call.is_portable());
+
+        let call = Callsign::parse("VE3/AA7BQ").unwrap();
+        assert_eq!((call.prefix(), call.base()), (Some("VE3"), "AA7BQ"));
+        assert_eq!(call.suffix(), None);
+
+        let call = Callsign::parse("W1AW/MM").unwrap();
+        assert_eq!((call.prefix(), call.base()), (None, "W1AW"));
+        assert_eq!(call.suffix(), Some(&CallsignSuffix::MaritimeMobile));
+
+        let call = Callsign::parse("K1ABC/4").unwrap();
+        assert_eq!(call.suffix(), Some(&CallsignSuffix::Other("4".to_string())));
+
+        let call = Callsign::parse("KH6/K1ABC").unwrap();
+        assert_eq!((call.prefix(), call.base()), (Some("KH6"), "K1ABC"));
+
+        let call = Callsign::parse("VP2E/W1AW").unwrap();
+        assert_eq!((call.prefix(), call.base()), (Some("VP2E"), "W1AW"));
+        assert_eq!(call.suffix(), None);
+
+        let call = Callsign::parse("W1AW/VP2E").unwrap();
+        assert_eq!((call.prefix(), call.base()), (None, "W1AW"));
+    }
+
+    #[test]
+    fn test_parse_rejects_implausible() {
+        for call in ["", "HELLO", "AA7BQ//P", "EA8/P/DL1ABC", "A/B/C/D1AB"] {
+            assert!(
+                matches!(Callsign::parse(call), Err(QrzXmlError::InvalidInput { .. })),
+                "{}",
+                call
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalization() {
+        use CallsignNormalization::*;
+
+        assert_eq!(None.apply("w1aw/p"), "w1aw/p");
+        assert_eq!(StripOperatingSuffix.apply("w1aw/p"), "W1AW");
+        assert_eq!(StripOperatingSuffix.apply("EA8/DL1ABC/QRP"), "EA8/DL1ABC");
+        assert_eq!(StripOperatingSuffix.apply("K1ABC/4"), "K1ABC/4");
+        assert_eq!(StripOperatingSuffix.apply("DL/N0CALL"), "DL/N0CALL");
+        assert_eq!(BaseCall.apply("DL/N0CALL"), "N0CALL");
+        assert_eq!(BaseCall.apply("EA8/DL1ABC/P"), "DL1ABC");
+        assert_eq!(BaseCall.apply("HELLO"), "HELLO");
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        let call: Callsign = serde_json::from_str("\"dl1abc/p\"").unwrap();
+        assert_eq!(call.base(), "DL1ABC");
+        assert_eq!(serde_json::to_string(&call).unwrap(), "\"DL1ABC/P\"");
+    }
+}
@@ -0,0 +1,128 @@
+//! Decoding of response bodies.
+//!
+//! QRZ usually answers in UTF-8, but some records and biographies come back
+//! in ISO-8859-1, with the encoding given only in the XML declaration or not
+//! at all. Decoding those as UTF-8 turns every accented letter into U+FFFD.
+//! [`decode_body`] picks the encoding from the `Content-Type` charset, then
+//! the XML declaration, and falls back to Windows-1252 (the superset of
+//! ISO-8859-1 browsers use for that label) when the body is not valid UTF-8.
+
+/// Characters of Windows-1252 bytes 0x80 to 0x9F; the rest match ISO-8859-1
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Labels decoded as Windows-1252
+const LATIN1_LABELS: [&str; 8] = [
+    "iso-8859-1",
+    "iso8859-1",
+    "iso_8859-1",
+    "latin1",
+    "l1",
+    "windows-1252",
+    "cp1252",
+    "us-ascii",
+];
+
+/// Decode a response body using the charset of its `Content-Type` header or
+/// XML declaration
+pub(crate) fn decode_body(bytes: &[u8], content_type: Option<&str>) -> String {
+    decode(bytes, declares_latin1(bytes, content_type))
+}
+
+/// Check if the `Content-Type` header, or else the XML declaration at the
+/// start of `head`, declares ISO-8859-1 or a related encoding
+pub(crate) fn declares_latin1(head: &[u8], content_type: Option<&str>) -> bool {
+    let declared = content_type
+        .and_then(header_charset)
+        .or_else(|| xml_encoding(head));
+    declared.is_some_and(|label| {
+        LATIN1_LABELS
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(label))
+    })
+}
+
+/// Decode part of a body, as Windows-1252 if `latin1` is set or the bytes
+/// are not valid UTF-8
+pub(crate) fn decode(bytes: &[u8], latin1: bool) -> String {
+    if !latin1 {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            return text.to_string();
+        }
+    }
+    decode_windows_1252(bytes)
+}
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => WINDOWS_1252_HIGH[usize::from(b - 0x80)],
+            _ => char::from(b),
+        })
+        .collect()
+}
+
+/// The `charset` parameter of a `Content-Type` header value
+fn header_charset(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Check if `head` is long enough to find the XML declaration in: the
+/// declaration is complete, or the body starts with something else
+pub(crate) fn declaration_complete(head: &[u8]) -> bool {
+    let head = head.trim_ascii_start();
+    if head.len() < b"<?xml".len() && b"<?xml".starts_with(head) {
+        return false;
+    }
+    !head.starts_with(b"<?xml") || head.windows(2).any(|pair| pair == b"?>")
+}
+
+/// The `encoding` attribute of an XML declaration at the start of the body
+fn xml_encoding(bytes: &[u8]) -> Option<&str> {
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace())?;
+    let declaration = bytes[start..].strip_prefix(b"<?xml")?;
+    let end = declaration.windows(2).position(|pair| pair == b"?>")?;
+    let declaration = std::str::from_utf8(&declaration[..end]).ok()?;
+
+    let value = declaration.split("encoding").nth(1)?.trim_start();
+    let value = value.strip_prefix('=')?.trim_start();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    value[1..].split(quote).next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin1_declared_in_xml() {
+        let body = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\" ?>\n<fname>Jos\xe9</fname>";
+        assert!(decode_body(body, Some("text/xml")).ends_with("<fname>José</fname>"));
+    }
+
+    #[test]
+    fn test_header_charset_takes_precedence() {
+        let body = "<?xml version=\"1.0\" encoding=\"utf-8\"?><name>M\u{fc}ller</name>";
+        let latin1: Vec<u8> = body.chars().map(|c| c as u8).collect();
+        let decoded = decode_body(&latin1, Some("text/xml; charset=\"iso-8859-1\""));
+        assert!(decoded.ends_with("<name>Müller</name>"));
+    }
+
+    #[test]
+    fn test_utf8_and_undeclared_fallback() {
+        let utf8 = "<name>Øystein \u{2013} LA1ABC</name>";
+        assert_eq!(decode_body(utf8.as_bytes(), None), utf8);
+        assert_eq!(
+            decode_body(b"<name>Fran\xe7ois \x96 F1ABC</name>", None),
+            "<name>François – F1ABC</name>"
+        );
+    }
+}
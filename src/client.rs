@@ -1,27 +1,177 @@
 //! QRZ.com XML API client implementation.
 
+use crate::cache::{AdaptiveTtl, TtlCache};
+use crate::callsign::CallsignNormalization;
+use crate::charset;
+use crate::clock::{Clock, TokioClock};
+#[cfg(feature = "keyring")]
+use crate::credentials;
+use crate::diagnostics::{self, CacheStats, Diagnostics, ErrorLog, SessionDiagnostics};
 use crate::error::{QrzXmlError, Result};
+use crate::events::{ClientEvent, EVENT_CHANNEL_CAPACITY};
+use crate::exchange::{self, ExchangeValidation};
+use crate::governor::{Governor, GovernorConfig, GovernorPermit, GovernorPhase};
+use crate::latency::{LatencyHistogram, LatencyTracker};
+use crate::merge::CacheMergePolicy;
+use crate::pacing::{Pacer, PacingConfig};
+use crate::parse::{self, ParseMode};
+#[cfg(feature = "offline-dxcc")]
+use crate::prefix::PrefixMatch;
+#[cfg(feature = "strict")]
+use crate::probe::{self, ApiProbeReport, PROBE_CALLSIGN};
+use crate::query::{QueryParams, QueryResult};
+use crate::quota::{Quota, QuotaWatcher};
+use crate::retry::RetryBudget;
+use crate::session_store::{self, SessionLock, SessionSnapshot, SessionStore, StoredSession};
+#[cfg(feature = "request-signing")]
+use crate::signing::RequestSigner;
+use crate::station::Station;
+use crate::stream::DxccEntityStream;
+use crate::telemetry::{self, SessionEvent};
 use crate::types::{
-    ApiVersion, BiographyData, CallsignInfo, DxccInfo, QrzXmlResponse, SessionInfo,
+    ApiVersion, BiographyData, BiographyOutcome, CallsignInfo, DxccInfo, DxccListResponse,
+    QrzXmlResponse, SessionInfo, SubscriptionStatus,
 };
+use crate::user_agent::UserAgent;
+use crate::validate;
+use crate::worker::{self, LookupResult, LookupWorker};
 use crate::{DEFAULT_BASE_URL, DEFAULT_USER_AGENT};
-use reqwest::Client;
+#[cfg(feature = "iso3166")]
+use isocountry::CountryCode;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, StatusCode};
+use std::collections::BTreeMap;
+use std::env::{self, VarError};
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, info, warn};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use url::Url;
 
+/// Environment variable holding the QRZ username, read by [`QrzXmlClient::from_env`]
+pub const ENV_USERNAME: &str = "QRZ_USERNAME";
+/// Environment variable holding the QRZ password
+pub const ENV_PASSWORD: &str = "QRZ_PASSWORD";
+/// Optional environment variable overriding the API base URL
+pub const ENV_BASE_URL: &str = "QRZ_BASE_URL";
+/// Optional environment variable selecting the API version (`current`, `legacy` or e.g. `1.34`)
+pub const ENV_API_VERSION: &str = "QRZ_API_VERSION";
+
+/// Query parameters the client sets itself, which extra parameters may not replace
+const RESERVED_QUERY_PARAMS: [&str; 7] = [
+    "s", "username", "password", "agent", "callsign", "dxcc", "html",
+];
+
+/// Query parameters left out of events, as they carry the session key or login data
+const PRIVATE_QUERY_PARAMS: [&str; 4] = ["s", "username", "password", "agent"];
+
+/// How long QRZ asks clients to stay away after refusing connections
+const CONNECTION_REFUSED_LOCKOUT: Duration = Duration::from_secs(24 * 3600);
+
 /// Configuration for the QRZ client
 #[derive(Debug, Clone)]
 pub struct QrzXmlClientConfig {
     /// Base URL for the QRZ XML API
     pub base_url: String,
-    /// User agent string for HTTP requests
+    /// Agent string sent as the HTTP `User-Agent` header and the `agent` login parameter
     pub user_agent: String,
+    /// Extra HTTP headers sent with every request, e.g. `X-Forwarded-For` behind a proxy
+    pub extra_headers: Vec<(String, String)>,
+    /// Extra query parameters appended to every request, for proxies that route on them
+    ///
+    /// The parameters the client sets itself (`s`, `username`, `password`,
+    /// `agent`, `callsign`, `dxcc` and `html`) are rejected when the client is
+    /// built, in any case.
+    pub extra_query_params: Vec<(String, String)>,
     /// Request timeout in seconds
     pub timeout_seconds: u64,
+    /// IP version used to reach QRZ, e.g. IPv4 only on networks with broken IPv6
+    pub ip_family: IpFamily,
+    /// Local address to send requests from
+    ///
+    /// Must match `ip_family` unless that is [`IpFamily::Any`].
+    pub local_address: Option<IpAddr>,
     /// Maximum number of automatic retry attempts
     pub max_retries: u32,
+    /// Maximum extra requests (re-authentication retries and hedges) per minute; `None` is unlimited
+    pub retry_budget_per_minute: Option<u32>,
+    /// Send a second, hedged request when a lookup has not answered within this many milliseconds
+    ///
+    /// A hedged request is a second lookup as far as QRZ is concerned, so it
+    /// counts against the daily lookup limit and the client's own count.
+    /// Hedges are also limited by `retry_budget_per_minute`.
+    pub hedge_after_ms: Option<u64>,
+    /// Log a warning for lookups taking at least this many milliseconds
+    pub slow_lookup_threshold_ms: Option<u64>,
+    /// How long DXCC entity lookups are cached, in seconds; `None` disables the cache
+    pub dxcc_cache_ttl_seconds: Option<u64>,
+    /// How long callsign lookups are cached, in seconds; `None` disables the cache
+    pub callsign_cache_ttl_seconds: Option<u64>,
+    /// Upper bound for adaptive callsign cache lifetimes, in seconds
+    ///
+    /// When set, every refresh that finds a record's `moddate` unchanged doubles
+    /// its cache lifetime, starting from `callsign_cache_ttl_seconds`, up to this
+    /// bound. A record that was edited starts over.
+    pub callsign_cache_max_ttl_seconds: Option<u64>,
+    /// How a refreshed callsign record is combined with its expired cache entry
+    pub callsign_merge_policy: CacheMergePolicy,
+    /// How callsigns are rewritten before lookup, unless overridden with
+    /// [`LookupOptions`]
+    pub callsign_normalization: CallsignNormalization,
+    /// Daily lookup limit used by [`QrzXmlClient::quota`] and the percentage warnings
+    pub daily_lookup_limit: Option<u32>,
+    /// Fail lookups with [`QrzXmlError::QuotaExceeded`] once `daily_lookup_limit` is reached
+    pub enforce_daily_lookup_limit: bool,
+    /// Drop the client's own lookup count when QRZ's count resets for a new 24-hour window
+    ///
+    /// The client counts the lookups it sends and uses that count when it is
+    /// higher than QRZ's. With this set (the default), a drop in the reported
+    /// count resynchronizes the estimate with QRZ's new window at once; when
+    /// cleared, the client's count is kept until its own window ends.
+    pub resync_quota_on_rollover: bool,
+    /// Reject implausible callsigns and out-of-range DXCC entity numbers locally
+    ///
+    /// Such lookups fail with [`QrzXmlError::InvalidInput`] before a request
+    /// is sent, so they do not count against the daily lookup limit. See
+    /// [`validate`] for the rules applied.
+    pub validate_queries: bool,
+    /// How responses deviating from the expected structure are handled
+    pub parse_mode: ParseMode,
+    /// Reduce lookup concurrency as the daily lookup count approaches a ceiling
+    pub concurrency_governor: Option<GovernorConfig>,
+    /// Space lookups further apart as the daily lookup count approaches a ceiling
+    pub pacing: Option<PacingConfig>,
+    /// Emit a [`ClientEvent::QuotaWarning`] when these percentages of `daily_lookup_limit` are reached
+    pub quota_warning_percentages: Vec<u8>,
+    /// Sign requests with a shared-secret HMAC header, for use behind an internal proxy
+    #[cfg(feature = "request-signing")]
+    pub request_signer: Option<RequestSigner>,
+    /// Reuse the previous session key when QRZ rejects a login for having too many sessions
+    ///
+    /// Only keys given up while still valid, e.g. by
+    /// [`QrzXmlClient::reauthenticate`], are reused; keys QRZ rejected as
+    /// expired are not.
+    pub reuse_previous_session_key: bool,
+    /// How long to stop attempting logins after a "too many sessions" rejection, in seconds
+    pub too_many_sessions_backoff_seconds: u64,
+    /// Refuse further logins once QRZ rejects the username or password
+    ///
+    /// Repeated failed logins can get an account locked on QRZ's side. With
+    /// this set, a rejected login makes automatic logins fail fast with
+    /// [`QrzXmlError::LoginLockedOut`] until new credentials are set with
+    /// [`QrzXmlClient::set_password`] or
+    /// [`QrzXmlClient::reset_login_lockout`] is called. An explicit
+    /// [`QrzXmlClient::authenticate`] always tries again.
+    pub lock_out_after_auth_failure: bool,
+    /// Pause logins after this many consecutive failed logins; `None` never pauses
+    ///
+    /// Bounds how often automatic re-authentication can retry across lookups,
+    /// so a bulk job cannot keep hammering QRZ with failing logins.
+    pub max_failed_logins: Option<u32>,
+    /// How long logins stay paused after `max_failed_logins` failures, in seconds
+    pub login_cooldown_seconds: u64,
 }
 
 impl Default for QrzXmlClientConfig {
@@ -29,18 +179,214 @@ impl Default for QrzXmlClientConfig {
         Self {
             base_url: DEFAULT_BASE_URL.to_string(),
             user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: Vec::new(),
+            extra_query_params: Vec::new(),
             timeout_seconds: 30,
+            ip_family: IpFamily::Any,
+            local_address: None,
             max_retries: 3,
+            retry_budget_per_minute: None,
+            hedge_after_ms: None,
+            slow_lookup_threshold_ms: None,
+            dxcc_cache_ttl_seconds: None,
+            callsign_cache_ttl_seconds: None,
+            callsign_cache_max_ttl_seconds: None,
+            callsign_merge_policy: CacheMergePolicy::PreferFresh,
+            callsign_normalization: CallsignNormalization::None,
+            daily_lookup_limit: None,
+            enforce_daily_lookup_limit: false,
+            resync_quota_on_rollover: true,
+            validate_queries: false,
+            parse_mode: ParseMode::Lenient,
+            concurrency_governor: None,
+            pacing: None,
+            quota_warning_percentages: vec![80, 100],
+            #[cfg(feature = "request-signing")]
+            request_signer: None,
+            reuse_previous_session_key: true,
+            too_many_sessions_backoff_seconds: 300,
+            lock_out_after_auth_failure: true,
+            max_failed_logins: Some(3),
+            login_cooldown_seconds: 900,
         }
     }
 }
 
+/// Per-request settings for callsign lookups
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LookupOptions {
+    /// Overrides [`QrzXmlClientConfig::callsign_normalization`] when set
+    pub normalization: Option<CallsignNormalization>,
+}
+
+impl LookupOptions {
+    /// Rewrite the callsign this way before the lookup
+    pub fn with_normalization(mut self, normalization: CallsignNormalization) -> Self {
+        self.normalization = Some(normalization);
+        self
+    }
+}
+
+/// IP versions the client may use to connect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IpFamily {
+    /// Use whatever the resolver returns, falling back between IPv6 and IPv4
+    #[default]
+    Any,
+    /// Connect over IPv4 only
+    V4,
+    /// Connect over IPv6 only
+    V6,
+}
+
+impl QrzXmlClientConfig {
+    /// Use the given agent string for the `User-Agent` header and the `agent` parameter
+    pub fn with_user_agent(mut self, user_agent: UserAgent) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Send an extra HTTP header with every request
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Append an extra query parameter to every request
+    pub fn with_query_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_query_params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Settings by name, with secrets and proxy-specific values redacted
+    fn diagnostics(&self) -> BTreeMap<String, String> {
+        let redacted = |pairs: &[(String, String)]| {
+            pairs
+                .iter()
+                .map(|(name, _)| format!("{}={}", name, diagnostics::REDACTED))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut settings = BTreeMap::new();
+        let mut set = |name: &str, value: String| {
+            settings.insert(name.to_string(), value);
+        };
+
+        set("base_url", self.base_url.clone());
+        set("user_agent", self.user_agent.clone());
+        set("extra_headers", redacted(&self.extra_headers));
+        set("extra_query_params", redacted(&self.extra_query_params));
+        set("timeout_seconds", self.timeout_seconds.to_string());
+        set("ip_family", format!("{:?}", self.ip_family));
+        set("local_address", format!("{:?}", self.local_address));
+        set("max_retries", self.max_retries.to_string());
+        set(
+            "retry_budget_per_minute",
+            format!("{:?}", self.retry_budget_per_minute),
+        );
+        set("hedge_after_ms", format!("{:?}", self.hedge_after_ms));
+        set(
+            "slow_lookup_threshold_ms",
+            format!("{:?}", self.slow_lookup_threshold_ms),
+        );
+        set(
+            "dxcc_cache_ttl_seconds",
+            format!("{:?}", self.dxcc_cache_ttl_seconds),
+        );
+        set(
+            "callsign_cache_ttl_seconds",
+            format!("{:?}", self.callsign_cache_ttl_seconds),
+        );
+        set(
+            "callsign_cache_max_ttl_seconds",
+            format!("{:?}", self.callsign_cache_max_ttl_seconds),
+        );
+        set(
+            "callsign_merge_policy",
+            format!("{:?}", self.callsign_merge_policy),
+        );
+        set(
+            "callsign_normalization",
+            format!("{:?}", self.callsign_normalization),
+        );
+        set(
+            "daily_lookup_limit",
+            format!("{:?}", self.daily_lookup_limit),
+        );
+        set(
+            "enforce_daily_lookup_limit",
+            self.enforce_daily_lookup_limit.to_string(),
+        );
+        set(
+            "resync_quota_on_rollover",
+            self.resync_quota_on_rollover.to_string(),
+        );
+        set("validate_queries", self.validate_queries.to_string());
+        set("parse_mode", format!("{:?}", self.parse_mode));
+        set(
+            "concurrency_governor",
+            format!("{:?}", self.concurrency_governor),
+        );
+        set("pacing", format!("{:?}", self.pacing));
+        set(
+            "quota_warning_percentages",
+            format!("{:?}", self.quota_warning_percentages),
+        );
+        #[cfg(feature = "request-signing")]
+        set(
+            "request_signer",
+            if self.request_signer.is_some() {
+                diagnostics::REDACTED.to_string()
+            } else {
+                "None".to_string()
+            },
+        );
+        set(
+            "reuse_previous_session_key",
+            self.reuse_previous_session_key.to_string(),
+        );
+        set(
+            "too_many_sessions_backoff_seconds",
+            self.too_many_sessions_backoff_seconds.to_string(),
+        );
+        set(
+            "lock_out_after_auth_failure",
+            self.lock_out_after_auth_failure.to_string(),
+        );
+        set("max_failed_logins", format!("{:?}", self.max_failed_logins));
+        set(
+            "login_cooldown_seconds",
+            self.login_cooldown_seconds.to_string(),
+        );
+        settings
+    }
+}
+
 /// Internal session state
 #[derive(Debug, Clone)]
 struct SessionState {
     key: Option<String>,
     count: Option<u32>,
     sub_exp: Option<String>,
+    gm_time: Option<String>,
+    message: Option<String>,
+    /// Key of the last session given up while still valid, kept for "too many
+    /// sessions" recovery; keys QRZ rejected are never kept
+    previous_key: Option<String>,
+    /// Logins are not attempted before this instant after a "too many sessions" rejection
+    login_backoff_until: Option<Instant>,
+    /// Reason QRZ gave for rejecting the credentials, while logins are locked out
+    auth_failure: Option<String>,
+    /// Consecutive failed logins since the last success or cooldown
+    failed_logins: u32,
+    /// Logins are not attempted before this instant after repeated failures
+    login_cooldown_until: Option<Instant>,
+    /// No requests are sent before this instant (and Unix time) after QRZ refused connections
+    refused_until: Option<(Instant, u64)>,
+    /// Whether a refusal was looked up in the session store yet
+    refusal_restored: bool,
+    /// When the current session was established, in seconds since the Unix epoch, if known
+    created_at: Option<u64>,
 }
 
 impl SessionState {
@@ -49,11 +395,26 @@ impl SessionState {
             key: None,
             count: None,
             sub_exp: None,
+            gm_time: None,
+            message: None,
+            previous_key: None,
+            login_backoff_until: None,
+            auth_failure: None,
+            failed_logins: 0,
+            login_cooldown_until: None,
+            refused_until: None,
+            refusal_restored: false,
+            created_at: None,
         }
     }
 
-    fn update_from_session_info(&mut self, session: &SessionInfo) {
+    /// Merge reported session fields, returning the message if it is new
+    /// `now` is the current Unix time, recorded as the creation time of new keys
+    fn update_from_session_info(&mut self, session: &SessionInfo, now: u64) -> Option<String> {
         if let Some(key) = &session.key {
+            if self.key.as_ref() != Some(key) {
+                self.created_at = Some(now);
+            }
             self.key = Some(key.clone());
         }
         if let Some(count) = session.count {
@@ -62,6 +423,28 @@ impl SessionState {
         if let Some(sub_exp) = &session.sub_exp {
             self.sub_exp = Some(sub_exp.clone());
         }
+        if let Some(gm_time) = &session.gm_time {
+            self.gm_time = Some(gm_time.clone());
+        }
+        match &session.message {
+            Some(message) if self.message.as_ref() != Some(message) => {
+                self.message = Some(message.clone());
+                Some(message.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn snapshot(&self, include_key: bool) -> SessionInfo {
+        SessionInfo {
+            key: self.key.clone().filter(|_| include_key),
+            count: self.count,
+            sub_exp: self.sub_exp.clone(),
+            gm_time: self.gm_time.clone(),
+            message: self.message.clone(),
+            error: None,
+            remark: None,
+        }
     }
 
     fn has_valid_session(&self) -> bool {
@@ -69,9 +452,20 @@ impl SessionState {
     }
 
     fn clear(&mut self) {
-        self.key = None;
+        if let Some(key) = self.key.take() {
+            self.previous_key = Some(key);
+        }
         self.count = None;
         self.sub_exp = None;
+        self.gm_time = None;
+        self.message = None;
+        self.created_at = None;
+    }
+
+    /// Drop a session QRZ rejected, so its key is not reused
+    fn expire(&mut self) {
+        self.clear();
+        self.previous_key = None;
     }
 }
 
@@ -81,14 +475,44 @@ pub struct QrzXmlClient {
     http_client: Client,
     /// QRZ username
     username: String,
-    /// QRZ password
-    password: String,
+    /// QRZ password, replaceable with [`QrzXmlClient::set_password`]
+    password: std::sync::Mutex<String>,
     /// API version to use
     api_version: ApiVersion,
     /// Client configuration
     config: QrzXmlClientConfig,
     /// Current session state
     session: Arc<RwLock<SessionState>>,
+    /// Serializes logins so concurrent requests share a single new session
+    login_lock: Arc<Mutex<()>>,
+    /// Sender for client events
+    events: broadcast::Sender<ClientEvent>,
+    /// Budget for re-authentication retries and hedged requests
+    retry_budget: RetryBudget,
+    /// Lookup latency histograms
+    latency: LatencyTracker,
+    /// Latest lookup count and quota warning thresholds
+    quota: QuotaWatcher,
+    /// Optional governor limiting lookup concurrency near the daily ceiling
+    governor: Option<Governor>,
+    /// Optional pacer spacing lookups by the daily lookup count
+    pacer: Option<Pacer>,
+    /// Long-lived cache of DXCC entities, keyed by entity number or queried callsign
+    dxcc_cache: TtlCache<String, DxccInfo>,
+    /// Short-lived cache of callsign records
+    callsign_cache: TtlCache<String, CallsignInfo>,
+    /// Optional per-record lifetimes for the callsign cache
+    callsign_ttl: Option<AdaptiveTtl>,
+    /// Optional persistence for session keys
+    session_store: Option<Arc<dyn SessionStore>>,
+    /// Time source for backoffs, budgets and cache lifetimes
+    pub(crate) clock: Arc<dyn Clock>,
+    /// Deviations found in responses so far, for diagnostics
+    parse_warning_count: AtomicU64,
+    /// API version reported in the most recent response
+    server_version: std::sync::Mutex<Option<String>>,
+    /// Most recent lookup errors, for diagnostics
+    errors: ErrorLog,
 }
 
 impl QrzXmlClient {
@@ -115,166 +539,724 @@ impl QrzXmlClient {
     ) -> Result<Self> {
         let http_client = Client::builder()
             .user_agent(&config.user_agent)
+            .default_headers(extra_headers(&config.extra_headers)?)
+            .local_address(local_address(config.ip_family, config.local_address)?)
             .timeout(std::time::Duration::from_secs(config.timeout_seconds))
             .build()?;
+        if let Some((name, _)) = config.extra_query_params.iter().find(|(name, _)| {
+            RESERVED_QUERY_PARAMS
+                .iter()
+                .any(|reserved| reserved.eq_ignore_ascii_case(name))
+        }) {
+            return Err(QrzXmlError::invalid_input(format!(
+                "Query parameter {} is set by the client",
+                name
+            )));
+        }
 
         Ok(Self {
             http_client,
             username: username.into(),
-            password: password.into(),
+            password: std::sync::Mutex::new(password.into()),
             api_version,
-            config,
             session: Arc::new(RwLock::new(SessionState::new())),
+            login_lock: Arc::new(Mutex::new(())),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            retry_budget: RetryBudget::new(config.retry_budget_per_minute),
+            latency: LatencyTracker::default(),
+            quota: QuotaWatcher::new(
+                config.daily_lookup_limit,
+                &config.quota_warning_percentages,
+                config.resync_quota_on_rollover,
+            ),
+            governor: config.concurrency_governor.clone().map(Governor::new),
+            pacer: config.pacing.clone().map(Pacer::new),
+            dxcc_cache: TtlCache::new(config.dxcc_cache_ttl_seconds.map(Duration::from_secs)),
+            callsign_cache: TtlCache::new(
+                config.callsign_cache_ttl_seconds.map(Duration::from_secs),
+            ),
+            callsign_ttl: config
+                .callsign_cache_ttl_seconds
+                .zip(config.callsign_cache_max_ttl_seconds)
+                .map(|(base, max)| {
+                    AdaptiveTtl::new(Duration::from_secs(base), Duration::from_secs(max))
+                }),
+            session_store: None,
+            clock: Arc::new(TokioClock),
+            parse_warning_count: AtomicU64::new(0),
+            server_version: std::sync::Mutex::new(None),
+            errors: ErrorLog::default(),
+            config,
         })
     }
 
+    /// Create a client from the `QRZ_USERNAME` and `QRZ_PASSWORD` environment variables
+    ///
+    /// `QRZ_BASE_URL` and `QRZ_API_VERSION` are honored when set; otherwise the
+    /// default base URL and the current API version are used. Missing or empty
+    /// credentials fail with [`QrzXmlError::MissingEnvVar`].
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| env::var(name))
+    }
+
+    fn from_vars(var: impl Fn(&str) -> std::result::Result<String, VarError>) -> Result<Self> {
+        let optional = |name: &str| match var(name) {
+            Ok(value) if !value.trim().is_empty() => Ok(Some(value)),
+            Ok(_) | Err(VarError::NotPresent) => Ok(None),
+            Err(VarError::NotUnicode(_)) => Err(QrzXmlError::invalid_input(format!(
+                "Environment variable {} is not valid unicode",
+                name
+            ))),
+        };
+        let required =
+            |name: &str| optional(name)?.ok_or_else(|| QrzXmlError::missing_env_var(name));
+
+        let username = required(ENV_USERNAME)?;
+        let password = required(ENV_PASSWORD)?;
+        let api_version = match optional(ENV_API_VERSION)? {
+            Some(version) => version.parse()?,
+            None => ApiVersion::Current,
+        };
+
+        let mut config = QrzXmlClientConfig::default();
+        if let Some(base_url) = optional(ENV_BASE_URL)? {
+            Url::parse(&base_url)?;
+            config.base_url = base_url;
+        }
+
+        Self::with_config(username, password, api_version, config)
+    }
+
+    /// Create a client that resumes an existing session instead of logging in
+    ///
+    /// The key is not checked up front; it is used for the first request, and only
+    /// if QRZ rejects it does the client fall back to logging in with the password.
+    pub fn with_session_key(
+        username: impl Into<String>,
+        password: impl Into<String>,
+        session_key: impl Into<String>,
+        api_version: ApiVersion,
+    ) -> Result<Self> {
+        Ok(Self::new(username, password, api_version)?.resume_session(session_key))
+    }
+
+    /// Create a client with credentials saved in the OS keyring under `service`
+    ///
+    /// See [`credentials::store_credentials`] for saving them. Uses the current
+    /// API version and the default configuration.
+    #[cfg(feature = "keyring")]
+    pub fn from_keyring(service: &str) -> Result<Self> {
+        let (username, password) = credentials::load_credentials(service)?;
+        Self::new(username, password, ApiVersion::Current)
+    }
+
+    /// Seed the client with an existing session key, validated lazily on first use
+    ///
+    /// A rejected key is treated like an expired session: the client logs in again
+    /// and retries the request once.
+    pub fn resume_session(mut self, session_key: impl Into<String>) -> Self {
+        let mut state = SessionState::new();
+        state.key = Some(session_key.into());
+        self.session = Arc::new(RwLock::new(state));
+        self
+    }
+
+    /// QRZ username this client logs in with
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Log in only if the client has no session yet
+    ///
+    /// A session restored from the session store or resumed from a saved key
+    /// counts, without being checked with QRZ; should QRZ reject it, the next
+    /// lookup renews it. Unlike [`reauthenticate`](Self::reauthenticate), an
+    /// existing session is never replaced.
+    pub async fn ensure_authenticated(&self) -> Result<()> {
+        self.session_key().await.map(|_| ())
+    }
+
     /// Perform initial authentication and establish a session
+    ///
+    /// Always sends a login, even after QRZ rejected the credentials and
+    /// automatic logins are locked out; a rejection locks them out again.
     pub async fn authenticate(&self) -> Result<()> {
         info!("Authenticating with QRZ.com");
-        self.login().await?;
+        let _guard = self.login_lock.lock().await;
+        self.session.write().await.auth_failure = None;
+        self.login_unlocked().await?;
         Ok(())
     }
 
     /// Look up information for a callsign
-    pub async fn lookup_callsign(&self, callsign: &str) -> Result<CallsignInfo> {
-        if callsign.is_empty() {
-            return Err(QrzXmlError::invalid_input("Callsign cannot be empty"));
-        }
+    ///
+    /// Accepts a [`Callsign`](crate::Callsign) as well as a plain string.
+    pub async fn lookup_callsign(&self, callsign: impl AsRef<str>) -> Result<CallsignInfo> {
+        self.lookup_callsign_with(callsign, &LookupOptions::default())
+            .await
+    }
 
-        let callsign = callsign.to_uppercase();
-        debug!("Looking up callsign: {}", callsign);
+    /// Look up information for a callsign with per-request options
+    ///
+    /// When normalization changes the callsign, the record's
+    /// `queried_callsign` holds the callsign as given.
+    pub async fn lookup_callsign_with(
+        &self,
+        callsign: impl AsRef<str>,
+        options: &LookupOptions,
+    ) -> Result<CallsignInfo> {
+        let original = callsign.as_ref();
+        let normalization = options
+            .normalization
+            .unwrap_or(self.config.callsign_normalization);
+        let callsign = normalization.apply(original);
+
+        let mut info = self.lookup_normalized_callsign(&callsign).await?;
+        if !callsign.eq_ignore_ascii_case(original) {
+            debug!("Looked up {} as {}", original, callsign);
+            info.queried_callsign = Some(original.to_string());
+        }
+        Ok(info)
+    }
 
-        let response = match self
-            .make_authenticated_request(&[("callsign", &callsign)])
-            .await
-        {
-            Ok(resp) => resp,
-            Err(QrzXmlError::SessionExpired) => {
-                warn!("Session expired, re-authenticating and retrying");
-                // Clear the old session first
-                {
-                    let mut session = self.session.write().await;
-                    session.clear();
-                }
-                self.login().await?;
-                self.make_authenticated_request(&[("callsign", &callsign)])
-                    .await?
+    async fn lookup_normalized_callsign(&self, callsign: &str) -> Result<CallsignInfo> {
+        self.track_lookup("callsign", callsign, async {
+            if callsign.is_empty() {
+                return Err(QrzXmlError::invalid_input("Callsign cannot be empty"));
+            }
+            if self.config.validate_queries {
+                validate::check_callsign(callsign)?;
             }
-            Err(e) => return Err(e),
-        };
 
-        match response.callsign {
-            Some(callsign_info) => {
-                info!("Successfully looked up callsign: {}", callsign_info.call);
-                Ok(callsign_info)
+            let callsign = callsign.to_uppercase();
+            if let Some(info) = self.callsign_cache.get(&callsign, self.clock.now()) {
+                debug!("Callsign cache hit: {}", info.call);
+                return Ok(info);
             }
-            None => {
-                if let Some(error) = response.session.error {
-                    if error.contains("not found") {
-                        Err(QrzXmlError::callsign_not_found(callsign))
+            debug!("Looking up callsign: {}", callsign);
+
+            let response = self
+                .make_authenticated_request_with_reauth(&[("callsign", &callsign)])
+                .await?;
+
+            match response.callsign {
+                Some(callsign_info) => {
+                    info!("Successfully looked up callsign: {}", callsign_info.call);
+                    let callsign_info = match self.callsign_cache.get_stale(&callsign) {
+                        Some(cached) => self
+                            .config
+                            .callsign_merge_policy
+                            .merge(&cached, callsign_info),
+                        None => callsign_info,
+                    };
+                    let ttl = self.callsign_ttl.as_ref().map(|adaptive| {
+                        adaptive.ttl_for(&callsign, callsign_info.moddate.as_deref())
+                    });
+                    self.callsign_cache.insert(
+                        callsign,
+                        callsign_info.clone(),
+                        self.clock.now(),
+                        ttl,
+                    );
+                    Ok(callsign_info)
+                }
+                None => {
+                    if let Some(error) = response.session.error {
+                        if error.contains("not found") {
+                            Err(QrzXmlError::callsign_not_found(callsign))
+                        } else {
+                            Err(QrzXmlError::api_error(error))
+                        }
                     } else {
-                        Err(QrzXmlError::api_error(error))
+                        Err(QrzXmlError::unexpected_response(
+                            "No callsign data in response".to_string(),
+                        ))
                     }
-                } else {
-                    Err(QrzXmlError::unexpected_response(
-                        "No callsign data in response".to_string(),
-                    ))
                 }
             }
-        }
+        })
+        .await
+    }
+
+    /// Spawn a worker that looks up callsigns received on `rx` and sends the
+    /// results to `tx`
+    ///
+    /// Lookups run one at a time and the next callsign is only read once the
+    /// previous result was accepted by `tx`, so a bounded output channel
+    /// applies backpressure to the input. Rate limit rejections are retried
+    /// after a growing delay. Must be called from within a Tokio runtime.
+    pub fn spawn_lookup_worker(
+        self: &Arc<Self>,
+        rx: mpsc::Receiver<String>,
+        tx: mpsc::Sender<LookupResult>,
+    ) -> LookupWorker {
+        worker::spawn(Arc::clone(self), rx, tx)
     }
 
     /// Fetch biography/HTML data for a callsign
-    pub async fn lookup_biography(&self, callsign: &str) -> Result<BiographyData> {
-        if callsign.is_empty() {
-            return Err(QrzXmlError::invalid_input("Callsign cannot be empty"));
+    ///
+    /// A callsign without a biography yields empty biography data, while one
+    /// QRZ does not know fails with [`QrzXmlError::CallsignNotFound`]; other
+    /// outcomes of [`lookup_biography_outcome`](Self::lookup_biography_outcome)
+    /// are returned as errors.
+    pub async fn lookup_biography(&self, callsign: impl AsRef<str>) -> Result<BiographyData> {
+        let callsign = callsign.as_ref();
+        let callsign = callsign.to_uppercase();
+        match self.lookup_biography_outcome(&callsign).await? {
+            BiographyOutcome::Html(html_content) => Ok(BiographyData::new(callsign, html_content)),
+            BiographyOutcome::NoBio => Ok(BiographyData::new(callsign, "")),
+            BiographyOutcome::NotFound => Err(QrzXmlError::callsign_not_found(callsign)),
+            BiographyOutcome::SubscriptionRequired => Err(QrzXmlError::SubscriptionRequired),
+            BiographyOutcome::SessionExpired => Err(QrzXmlError::SessionExpired),
+            BiographyOutcome::Error(message) => Err(QrzXmlError::api_error(message)),
         }
+    }
 
-        let callsign = callsign.to_uppercase();
-        debug!("Fetching biography for callsign: {}", callsign);
+    /// Fetch a callsign's biography, classifying what the endpoint returned
+    ///
+    /// A rejected session is renewed and the request retried once, as with
+    /// callsign lookups; `SessionExpired` is only returned if that fails too.
+    pub async fn lookup_biography_outcome(
+        &self,
+        callsign: impl AsRef<str>,
+    ) -> Result<BiographyOutcome> {
+        let callsign = callsign.as_ref();
+        self.track_lookup("biography", callsign, async {
+            if callsign.is_empty() {
+                return Err(QrzXmlError::invalid_input("Callsign cannot be empty"));
+            }
+            if self.config.validate_queries {
+                validate::check_callsign(callsign)?;
+            }
 
-        // Biography requests return HTML instead of XML
-        let html_content = self
-            .make_authenticated_html_request(&[("html", &callsign)])
-            .await?;
+            let callsign = callsign.to_uppercase();
+            debug!("Fetching biography for callsign: {}", callsign);
+
+            // Biography requests return HTML instead of XML
+            let params = [("html", callsign.as_str())];
+            let outcome = self
+                .with_session_retry(|| async {
+                    let body = self.make_authenticated_html_request(&params).await?;
+                    match BiographyOutcome::classify(&body) {
+                        BiographyOutcome::SessionExpired => {
+                            self.session_expired();
+                            Err(QrzXmlError::SessionExpired)
+                        }
+                        outcome => Ok(outcome),
+                    }
+                })
+                .await;
+            match outcome {
+                Err(QrzXmlError::SessionExpired) => Ok(BiographyOutcome::SessionExpired),
+                other => other,
+            }
+        })
+        .await
+    }
+
+    /// Look up a DXCC entity by number without any network access
+    ///
+    /// Entities fetched live and still cached take precedence over the
+    /// bundled snapshot, which may predate recent changes; see the
+    /// [`offline`](crate::offline) module.
+    #[cfg(feature = "offline-dxcc")]
+    pub fn dxcc_entity_offline(&self, entity: u32) -> Option<DxccInfo> {
+        self.dxcc_cache
+            .get(&dxcc_entity_key(entity), self.clock.now())
+            .or_else(|| crate::offline::entity(entity).cloned())
+    }
 
-        Ok(BiographyData::new(callsign, html_content))
+    /// Look up the DXCC entity of a callsign, offline where its prefix allows
+    ///
+    /// The callsign is matched against the bundled
+    /// [`prefix_table`](crate::offline::prefix_table) and the entity taken from
+    /// [`dxcc_entity_offline`](Self::dxcc_entity_offline), so `JA1ABC` resolves
+    /// to Japan with no request. Callsigns under a prefix shared by several
+    /// entities, such as `VP8` or `KG4`, or under no known prefix fall back to
+    /// [`lookup_dxcc_by_callsign`](Self::lookup_dxcc_by_callsign).
+    #[cfg(feature = "offline-dxcc")]
+    pub async fn dxcc_for_callsign_offline(&self, callsign: impl AsRef<str>) -> Result<DxccInfo> {
+        let callsign = callsign.as_ref();
+        if let PrefixMatch::Entity(entity) = crate::offline::prefix_table().match_callsign(callsign)
+        {
+            if let Some(info) = self.dxcc_entity_offline(entity) {
+                debug!("DXCC resolved offline: {} -> {}", callsign, entity);
+                return Ok(info);
+            }
+        }
+        self.lookup_dxcc_by_callsign(callsign).await
     }
 
     /// Look up DXCC entity by entity number
     pub async fn lookup_dxcc_entity(&self, entity: u32) -> Result<DxccInfo> {
-        debug!("Looking up DXCC entity: {}", entity);
-
-        let entity_str = entity.to_string();
-        let response = self
-            .make_authenticated_request(&[("dxcc", &entity_str)])
-            .await?;
+        self.track_lookup("dxcc", &entity.to_string(), async {
+            if self.config.validate_queries {
+                validate::check_dxcc_entity(entity)?;
+            }
 
-        match response.dxcc {
-            Some(dxcc_info) => {
-                info!(
-                    "Successfully looked up DXCC entity: {} - {}",
-                    entity, dxcc_info.name
-                );
-                Ok(dxcc_info)
-            }
-            None => {
-                if let Some(_error) = response.session.error {
-                    Err(QrzXmlError::dxcc_not_found(entity_str))
-                } else {
-                    Err(QrzXmlError::unexpected_response(
-                        "No DXCC data in response".to_string(),
-                    ))
+            let entity_str = entity.to_string();
+            if let Some(info) = self
+                .dxcc_cache
+                .get(&dxcc_entity_key(entity), self.clock.now())
+            {
+                debug!("DXCC cache hit: {}", entity);
+                return Ok(info);
+            }
+            debug!("Looking up DXCC entity: {}", entity);
+
+            let response = self
+                .make_authenticated_request_with_reauth(&[("dxcc", &entity_str)])
+                .await?;
+
+            match response.dxcc {
+                Some(dxcc_info) => {
+                    info!(
+                        "Successfully looked up DXCC entity: {} - {}",
+                        entity, dxcc_info.name
+                    );
+                    self.dxcc_cache.insert(
+                        dxcc_entity_key(entity),
+                        dxcc_info.clone(),
+                        self.clock.now(),
+                        None,
+                    );
+                    Ok(dxcc_info)
+                }
+                None => {
+                    if let Some(_error) = response.session.error {
+                        Err(QrzXmlError::dxcc_not_found(entity_str))
+                    } else {
+                        Err(QrzXmlError::unexpected_response(
+                            "No DXCC data in response".to_string(),
+                        ))
+                    }
                 }
             }
-        }
+        })
+        .await
     }
 
     /// Look up DXCC entity by callsign prefix matching
-    pub async fn lookup_dxcc_by_callsign(&self, callsign: &str) -> Result<DxccInfo> {
-        if callsign.is_empty() {
-            return Err(QrzXmlError::invalid_input("Callsign cannot be empty"));
-        }
+    pub async fn lookup_dxcc_by_callsign(&self, callsign: impl AsRef<str>) -> Result<DxccInfo> {
+        let callsign = callsign.as_ref();
+        self.track_lookup("dxcc", callsign, async {
+            if callsign.is_empty() {
+                return Err(QrzXmlError::invalid_input("Callsign cannot be empty"));
+            }
+            if self.config.validate_queries {
+                validate::check_callsign(callsign)?;
+            }
 
-        let callsign = callsign.to_uppercase();
-        debug!("Looking up DXCC entity for callsign: {}", callsign);
+            let callsign = callsign.to_uppercase();
+            if let Some(info) = self
+                .dxcc_cache
+                .get(&dxcc_callsign_key(&callsign), self.clock.now())
+            {
+                debug!("DXCC cache hit: {}", callsign);
+                return Ok(info);
+            }
+            debug!("Looking up DXCC entity for callsign: {}", callsign);
+
+            let response = self
+                .make_authenticated_request_with_reauth(&[("dxcc", &callsign)])
+                .await?;
+
+            match response.dxcc {
+                Some(dxcc_info) => {
+                    info!(
+                        "Successfully looked up DXCC entity for {}: {} - {}",
+                        callsign, dxcc_info.dxcc, dxcc_info.name
+                    );
+                    self.dxcc_cache.insert(
+                        dxcc_entity_key(dxcc_info.dxcc),
+                        dxcc_info.clone(),
+                        self.clock.now(),
+                        None,
+                    );
+                    self.dxcc_cache.insert(
+                        dxcc_callsign_key(&callsign),
+                        dxcc_info.clone(),
+                        self.clock.now(),
+                        None,
+                    );
+                    Ok(dxcc_info)
+                }
+                None => {
+                    if let Some(_error) = response.session.error {
+                        Err(QrzXmlError::dxcc_not_found(callsign))
+                    } else {
+                        Err(QrzXmlError::unexpected_response(
+                            "No DXCC data in response".to_string(),
+                        ))
+                    }
+                }
+            }
+        })
+        .await
+    }
 
-        let response = self
-            .make_authenticated_request(&[("dxcc", &callsign)])
-            .await?;
+    /// ISO 3166-1 country of a record's mailing address
+    ///
+    /// `ccode` holds the DXCC entity of the mailing address, so the country
+    /// comes from a lookup of that entity, which is cached like any other.
+    /// `None` if the record has no `ccode` or the entity has no ISO code.
+    #[cfg(feature = "iso3166")]
+    pub async fn mailing_country(&self, info: &CallsignInfo) -> Result<Option<CountryCode>> {
+        let Some(entity) = info.ccode else {
+            return Ok(None);
+        };
+        let dxcc = self.lookup_dxcc_entity(entity).await?;
+        Ok(dxcc.country_alpha2().or_else(|| dxcc.country_alpha3()))
+    }
 
-        match response.dxcc {
-            Some(dxcc_info) => {
-                info!(
-                    "Successfully looked up DXCC entity for {}: {} - {}",
-                    callsign, dxcc_info.dxcc, dxcc_info.name
-                );
-                Ok(dxcc_info)
-            }
-            None => {
-                if let Some(_error) = response.session.error {
-                    Err(QrzXmlError::dxcc_not_found(callsign))
-                } else {
-                    Err(QrzXmlError::unexpected_response(
-                        "No DXCC data in response".to_string(),
-                    ))
+    /// Look up a callsign together with its DXCC entity and derived location
+    ///
+    /// A failed DXCC lookup does not fail the whole call; the station is
+    /// returned without entity details and `provenance.dxcc_resolved` unset.
+    pub async fn station(&self, callsign: impl AsRef<str>) -> Result<Station> {
+        let callsign = callsign.as_ref();
+        let info = self.lookup_callsign(callsign).await?;
+
+        let dxcc = match info.dxcc {
+            Some(entity) => match self.lookup_dxcc_entity(entity).await {
+                Ok(dxcc) => Some(dxcc),
+                Err(e) => {
+                    warn!(
+                        "Failed to resolve DXCC entity {} for {}: {}",
+                        entity, info.call, e
+                    );
+                    None
                 }
-            }
-        }
+            },
+            None => None,
+        };
+
+        Ok(Station::new(info, dxcc))
+    }
+
+    /// Look up a callsign and check whether a claimed state or zone exchange is plausible
+    pub async fn validate_exchange(
+        &self,
+        callsign: &str,
+        claimed_state_or_zone: &str,
+    ) -> Result<ExchangeValidation> {
+        let info = self.lookup_callsign(callsign).await?;
+        Ok(exchange::validate_exchange(
+            callsign,
+            &info,
+            claimed_state_or_zone,
+        ))
     }
 
     /// Get all DXCC entities (use sparingly)
+    ///
+    /// Every entity returned is also cached by its number, so later
+    /// [`lookup_dxcc_entity`](Self::lookup_dxcc_entity) calls are served
+    /// without a request.
     pub async fn lookup_all_dxcc_entities(&self) -> Result<Vec<DxccInfo>> {
-        warn!("Fetching all DXCC entities - use sparingly to avoid server overload");
+        self.track_lookup("dxcc", "all", async {
+            warn!("Fetching all DXCC entities - use sparingly to avoid server overload");
+
+            let response = self
+                .with_session_retry(|| self.make_dxcc_list_request())
+                .await?;
+            if response.entities.is_empty() {
+                return Err(QrzXmlError::unexpected_response(
+                    "No DXCC data in response".to_string(),
+                ));
+            }
 
-        let _response = self.make_authenticated_request(&[("dxcc", "all")]).await?;
+            info!("Fetched {} DXCC entities", response.entities.len());
+            for dxcc_info in &response.entities {
+                self.dxcc_cache.insert(
+                    dxcc_entity_key(dxcc_info.dxcc),
+                    dxcc_info.clone(),
+                    self.clock.now(),
+                    None,
+                );
+            }
+            Ok(response.entities)
+        })
+        .await
+    }
 
-        // The "all" response returns multiple DXCC records
-        // This is a bit tricky to handle with our current structure
-        // For now, we'll return an error suggesting to use the individual lookup methods
-        Err(QrzXmlError::invalid_input(
-            "Bulk DXCC lookup not yet implemented - use individual entity lookups".to_string(),
-        ))
+    /// Stream all DXCC entities, parsing them as the response arrives (use sparingly)
+    ///
+    /// Unlike [`lookup_all_dxcc_entities`](Self::lookup_all_dxcc_entities),
+    /// only the entity being read is held in memory and dropping the stream
+    /// stops reading the response. Entities are not cached, and the configured
+    /// parse mode does not apply.
+    pub async fn dxcc_entities_stream(&self) -> Result<DxccEntityStream<'_>> {
+        warn!("Streaming all DXCC entities - use sparingly to avoid server overload");
+
+        let (url, full_url, permit) = self
+            .prepare_authenticated_request(&[("dxcc", "all")])
+            .await?;
+        debug!("Making streaming request to: {}", full_url);
+
+        let response = telemetry::in_request_span(&url, self.send_request(&full_url)).await?;
+        let content_type = content_type(&response);
+        Ok(DxccEntityStream::new(self, response, content_type, permit))
+    }
+
+    /// Run an arbitrary QRZ query and dispatch the response into a typed result
+    ///
+    /// This allows using QRZ query types the crate does not have first-class
+    /// methods for yet. The session key is added automatically; expired sessions
+    /// are renewed and the query retried once, as with callsign lookups.
+    pub async fn query(&self, params: &QueryParams) -> Result<QueryResult> {
+        if params.is_empty() {
+            return Err(QrzXmlError::invalid_input(
+                "Query parameters cannot be empty",
+            ));
+        }
+        if params.contains_key("s") {
+            return Err(QrzXmlError::invalid_input(
+                "The session key parameter is managed by the client",
+            ));
+        }
+
+        debug!(
+            "Running generic query: {:?}",
+            params.keys().collect::<Vec<_>>()
+        );
+        let pairs = params.as_pairs();
+        let response = self.make_authenticated_request_with_reauth(&pairs).await?;
+
+        if let Some(error) = response.session.error.clone() {
+            if response.callsign.is_none() && response.dxcc.is_none() {
+                return Err(QrzXmlError::api_error(error));
+            }
+        }
+
+        Ok(QueryResult::from_response(response))
+    }
+
+    /// Get the current session key, e.g. to persist it for [`resume_session`](Self::resume_session)
+    pub async fn current_session_key(&self) -> Option<String> {
+        self.session.read().await.key.clone()
+    }
+
+    /// Get a snapshot of the current session, or `None` if there is no session
+    ///
+    /// The session key is only included when `include_key` is set, so snapshots
+    /// can be logged for diagnostics without leaking credentials.
+    pub async fn session_snapshot(&self, include_key: bool) -> Option<SessionInfo> {
+        let session = self.session.read().await;
+        session
+            .has_valid_session()
+            .then(|| session.snapshot(include_key))
+    }
+
+    /// Look up a well-known callsign and report response fields the crate does not model
+    ///
+    /// Use this to detect QRZ schema changes before they turn into missing data.
+    #[cfg(feature = "strict")]
+    pub async fn probe_api(&self) -> Result<ApiProbeReport> {
+        let session_key = self.session_key().await?;
+        let url = self.build_url("")?;
+        let params = [("s", session_key.as_str()), ("callsign", PROBE_CALLSIGN)];
+
+        let xml_content = self.fetch_xml(&url, &params, false).await?;
+        let (response, unknown_fields) = probe::parse_with_unknown_fields(&xml_content)?;
+
+        if response.callsign.is_none() {
+            if let Some(error) = response.session.error {
+                return Err(QrzXmlError::api_error(error));
+            }
+        }
+        for field in &unknown_fields {
+            warn!("QRZ response contains unmodeled field: {}", field);
+        }
+
+        Ok(ApiProbeReport {
+            api_version: response.version,
+            unknown_fields,
+        })
+    }
+
+    /// Latest informational message from QRZ, e.g. a subscription expiration notice
+    pub async fn server_message(&self) -> Option<String> {
+        self.session.read().await.message.clone()
+    }
+
+    /// Get the subscription status reported with the current session
+    pub async fn subscription_status(&self) -> SubscriptionStatus {
+        let session = self.session.read().await;
+        SubscriptionStatus::from_session(session.sub_exp.as_deref(), session.message.as_deref())
+    }
+
+    /// Get lookup usage in the current 24-hour window
+    pub fn quota(&self) -> Quota {
+        Quota {
+            used: self.quota.count(self.clock.now()),
+            limit: self.config.daily_lookup_limit,
+        }
+    }
+
+    /// Current phase of the concurrency governor, if one is configured
+    pub fn governor_phase(&self) -> Option<GovernorPhase> {
+        let governor = self.governor.as_ref()?;
+        Some(governor.phase(self.quota.count(self.clock.now()).unwrap_or(0)))
+    }
+
+    /// Current interval between lookups set by count-based pacing, if configured
+    pub fn pacing_interval(&self) -> Option<Duration> {
+        let pacer = self.pacer.as_ref()?;
+        Some(pacer.interval(self.quota.count(self.clock.now()).unwrap_or(0)))
+    }
+
+    /// Collect a redacted snapshot of the configuration and state for bug reports
+    ///
+    /// Credentials, the session key and the values of extra headers and query
+    /// parameters are left out, so the snapshot can be shared as is.
+    pub async fn diagnostics(&self) -> Diagnostics {
+        let now = self.clock.now();
+        let session = {
+            let session = self.session.read().await;
+            SessionDiagnostics {
+                authenticated: session.has_valid_session(),
+                created_at: session.created_at,
+                count: session.count,
+                sub_exp: session.sub_exp.clone(),
+                gm_time: session.gm_time.clone(),
+                message: session.message.clone(),
+                failed_logins: session.failed_logins,
+                login_locked_out: session.auth_failure.is_some(),
+                connection_refused_until: session.refused_until.map(|(_, until)| until),
+            }
+        };
+        let cache_stats = |(enabled, entries, expired)| CacheStats {
+            enabled,
+            entries,
+            expired,
+        };
+
+        Diagnostics {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            api_version: self.api_version.to_string(),
+            server_version: self
+                .server_version
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+            config: self.config.diagnostics(),
+            session,
+            lookups_used: self.quota.count(now),
+            callsign_cache: cache_stats(self.callsign_cache.stats(now)),
+            dxcc_cache: cache_stats(self.dxcc_cache.stats(now)),
+            recent_errors: self.errors.recent(),
+            parse_warnings: self.parse_warning_count.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drop all cached callsign and DXCC lookups, and reset adaptive cache lifetimes
+    pub fn clear_caches(&self) {
+        self.dxcc_cache.clear();
+        self.callsign_cache.clear();
+        if let Some(adaptive) = &self.callsign_ttl {
+            adaptive.clear();
+        }
     }
 
     /// Get current session information
@@ -283,6 +1265,53 @@ impl QrzXmlClient {
         Some((session.count, session.sub_exp.clone()))
     }
 
+    /// Export the current session for external storage, if there is one
+    ///
+    /// The creation time falls back to now for sessions of unknown age, such as
+    /// keys handed to [`resume_session`](Self::resume_session).
+    pub async fn export_session(&self) -> Option<SessionSnapshot> {
+        let now = self.unix_now();
+        let session = self.session.read().await;
+        Some(SessionSnapshot {
+            key: session.key.clone()?,
+            count: session.count,
+            sub_exp: session.sub_exp.clone(),
+            created_at: session.created_at.unwrap_or(now),
+        })
+    }
+
+    /// Continue a session exported with [`export_session`](Self::export_session)
+    ///
+    /// Like [`resume_session`](Self::resume_session), the key is validated lazily
+    /// and a rejected key leads to a new login. Snapshots older than
+    /// [`SESSION_MAX_AGE`](crate::session_store::SESSION_MAX_AGE) are refused.
+    pub async fn import_session(&self, snapshot: SessionSnapshot) -> Result<()> {
+        if snapshot.key.is_empty() {
+            return Err(QrzXmlError::invalid_input("Session snapshot has no key"));
+        }
+        if snapshot.is_expired_at(self.clock.system_now()) {
+            return Err(QrzXmlError::invalid_input("Session snapshot is too old"));
+        }
+
+        let mut session = self.session.write().await;
+        session.clear();
+        session.key = Some(snapshot.key);
+        session.count = snapshot.count;
+        session.sub_exp = snapshot.sub_exp;
+        session.created_at = Some(snapshot.created_at);
+        Ok(())
+    }
+
+    /// Snapshot of lookup latency histograms, keyed by lookup kind
+    pub fn latency_histograms(&self) -> BTreeMap<String, LatencyHistogram> {
+        self.latency.snapshot()
+    }
+
+    /// Subscribe to client events such as session changes and failed lookups
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ClientEvent> {
+        self.events.subscribe()
+    }
+
     /// Check if currently authenticated
     pub async fn is_authenticated(&self) -> bool {
         let session = self.session.read().await;
@@ -291,32 +1320,224 @@ impl QrzXmlClient {
 
     /// Force re-authentication (clears current session)
     pub async fn reauthenticate(&self) -> Result<()> {
+        telemetry::record_session_event(SessionEvent::ForcedReauth);
         {
             let mut session = self.session.write().await;
             session.clear();
         }
+        self.forget_stored_session();
         self.authenticate().await
     }
 
-    /// Internal method to perform login
-    async fn login(&self) -> Result<SessionInfo> {
+    /// Replace the password and allow logins again
+    ///
+    /// Clears the lockout from rejected credentials and any pause after
+    /// repeated failed logins, as [`reset_login_lockout`](Self::reset_login_lockout)
+    /// does. The current session is kept; the new password is used for the
+    /// next login.
+    pub async fn set_password(&self, password: impl Into<String>) {
+        *self.password.lock().unwrap_or_else(|e| e.into_inner()) = password.into();
+        self.reset_login_lockout().await;
+    }
+
+    /// Allow logins again after QRZ rejected the credentials or repeated logins failed
+    ///
+    /// See [`QrzXmlClientConfig::lock_out_after_auth_failure`] and
+    /// [`QrzXmlClientConfig::max_failed_logins`].
+    pub async fn reset_login_lockout(&self) {
+        let mut session = self.session.write().await;
+        session.auth_failure = None;
+        session.failed_logins = 0;
+        session.login_cooldown_until = None;
+    }
+
+    /// Check if logins are refused because QRZ rejected the credentials
+    pub async fn is_login_locked_out(&self) -> bool {
+        self.session.read().await.auth_failure.is_some()
+    }
+
+    /// When QRZ will be contacted again after refusing connections, in seconds since the Unix epoch
+    ///
+    /// QRZ asks clients to stop for 24 hours once it refuses connections; until
+    /// then every request fails locally with [`QrzXmlError::ConnectionRefused`].
+    pub async fn connection_refused_until(&self) -> Option<u64> {
+        self.check_connection_refused()
+            .await
+            .err()
+            .and_then(|e| match e {
+                QrzXmlError::ConnectionRefused { until } => Some(until),
+                _ => None,
+            })
+    }
+
+    /// Contact QRZ again before the 24-hour refusal period is over
+    pub async fn clear_connection_refused(&self) {
+        self.session.write().await.refused_until = None;
+        self.save_refusal(None);
+    }
+
+    /// Persist sessions in the given store so they survive process restarts
+    ///
+    /// A stored session for this user is picked up the first time a key is needed,
+    /// new sessions are saved after every login, and the stored session is cleared
+    /// when QRZ reports it expired.
+    pub fn with_session_store(mut self, store: Arc<dyn SessionStore>) -> Self {
+        self.session_store = Some(store);
+        self
+    }
+
+    /// Use a different time source, e.g. a [`MockClock`](crate::clock::MockClock) in tests
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Get the current session key, logging in first if there is none
+    ///
+    /// Concurrent callers without a session wait for a single login instead of
+    /// each starting their own.
+    async fn session_key(&self) -> Result<String> {
+        if let Some(key) = self.session.read().await.key.clone() {
+            return Ok(key);
+        }
+
+        let _guard = self.login_lock.lock().await;
+
+        // Another task may have logged in while we were waiting for the lock
+        if let Some(key) = self.session.read().await.key.clone() {
+            return Ok(key);
+        }
+
+        self.establish_session(None).await?;
+        let session = self.session.read().await;
+        session.key.clone().ok_or(QrzXmlError::NoSessionKey)
+    }
+
+    /// Restore a stored session or log in; callers must hold `login_lock`
+    ///
+    /// The session store stays locked meanwhile, so other processes sharing it
+    /// pick up this login instead of starting their own. `expired` is a key QRZ
+    /// just rejected, which is never restored.
+    async fn establish_session(&self, expired: Option<&str>) -> Result<()> {
+        let _store_lock = self.lock_session_store().await;
+
+        if self.restore_stored_session(expired).await.is_some() {
+            return Ok(());
+        }
+        if expired.is_some() {
+            self.forget_stored_session();
+        }
+
+        self.login_unlocked().await?;
+        Ok(())
+    }
+
+    /// Lock this user's session in the session store, if the store supports it
+    async fn lock_session_store(&self) -> Option<SessionLock> {
+        let store = Arc::clone(self.session_store.as_ref()?);
+        let username = self.username.clone();
+        let locked = tokio::task::spawn_blocking(move || store.lock(&username))
+            .await
+            .unwrap_or_else(|e| Err(QrzXmlError::session_store(e.to_string())));
+        match locked {
+            Ok(lock) => lock,
+            Err(e) => {
+                warn!("Failed to lock session store: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Perform login; callers must hold `login_lock`
+    async fn login_unlocked(&self) -> Result<SessionInfo> {
+        self.check_connection_refused().await?;
+        {
+            let session = self.session.read().await;
+            if let Some(reason) = &session.auth_failure {
+                warn!("Skipping login, QRZ rejected the credentials earlier");
+                return Err(QrzXmlError::login_locked_out(reason.clone()));
+            }
+            if let Some(until) = session.login_backoff_until {
+                if self.clock.now() < until {
+                    warn!("Skipping login while backing off from a too-many-sessions rejection");
+                    return Err(QrzXmlError::TooManySessions);
+                }
+            }
+            if let Some(until) = session.login_cooldown_until {
+                let now = self.clock.now();
+                if now < until {
+                    warn!("Skipping login while cooling down from repeated failures");
+                    return Err(QrzXmlError::LoginCoolingDown {
+                        retry_after: until - now,
+                    });
+                }
+            }
+        }
+
+        let result = self.send_login().await;
+        self.record_login_outcome(&result).await;
+        result
+    }
+
+    /// Count consecutive failed logins, starting a cooldown once the limit is reached
+    async fn record_login_outcome(&self, result: &Result<SessionInfo>) {
+        if result.is_err() {
+            telemetry::record_session_event(SessionEvent::LoginFailed);
+        }
+        let mut session = self.session.write().await;
+        match result {
+            Ok(_) => session.failed_logins = 0,
+            Err(QrzXmlError::AuthenticationFailed { .. } | QrzXmlError::NoSessionKey) => {
+                session.failed_logins += 1;
+                let Some(max) = self.config.max_failed_logins else {
+                    return;
+                };
+                if session.failed_logins >= max {
+                    warn!(
+                        "{} consecutive failed logins, pausing logins for {} seconds",
+                        session.failed_logins, self.config.login_cooldown_seconds
+                    );
+                    session.failed_logins = 0;
+                    session.login_cooldown_until = Some(
+                        self.clock.now() + Duration::from_secs(self.config.login_cooldown_seconds),
+                    );
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Send the login request and record the new session
+    async fn send_login(&self) -> Result<SessionInfo> {
         let url = self.build_url("")?;
 
+        let password = self
+            .password
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
         let params = [
             ("username", self.username.as_str()),
-            ("password", self.password.as_str()),
+            ("password", password.as_str()),
             ("agent", &self.config.user_agent),
         ];
 
         debug!("Performing login to QRZ.com");
-        let response = self.make_request(&url, &params).await?;
+        // Never hedge logins, a duplicate would create a second session
+        let response = self.make_request(&url, &params, false).await?;
 
         let session_info = response.session.clone();
 
         if let Some(error) = &session_info.error {
             if error.contains("Connection refused") {
-                return Err(QrzXmlError::ConnectionRefused);
+                return Err(self.enter_connection_refused().await);
+            } else if is_too_many_sessions(error) {
+                return self.handle_too_many_sessions().await;
             } else if error.contains("password") || error.contains("username") {
+                if self.config.lock_out_after_auth_failure {
+                    warn!("QRZ rejected the credentials, refusing further logins");
+                    self.session.write().await.auth_failure = Some(error.clone());
+                }
                 return Err(QrzXmlError::auth_failed(error.clone()));
             } else {
                 return Err(QrzXmlError::api_error(error.clone()));
@@ -328,47 +1549,247 @@ impl QrzXmlClient {
         }
 
         // Update our internal session state
-        {
+        let new_message = {
             let mut session = self.session.write().await;
-            session.update_from_session_info(&session_info);
-        }
-
+            session.login_backoff_until = None;
+            session.update_from_session_info(&session_info, self.unix_now())
+        };
+        self.emit(ClientEvent::SessionEstablished {
+            count: session_info.count,
+            sub_exp: session_info.sub_exp.clone(),
+        });
+        self.check_quota(session_info.count);
+        self.report_message(new_message);
+        self.save_stored_session(&session_info);
+
+        telemetry::record_session_event(SessionEvent::LoginSucceeded);
         info!("Successfully authenticated with QRZ.com");
         Ok(session_info)
     }
 
-    /// Make an authenticated request that returns XML
-    async fn make_authenticated_request(&self, params: &[(&str, &str)]) -> Result<QrzXmlResponse> {
-        let session_key = {
-            let session = self.session.read().await;
-            session.key.clone()
+    /// Seed the session from the session store, returning the restored key
+    ///
+    /// A stored key equal to `expired` is ignored.
+    async fn restore_stored_session(&self, expired: Option<&str>) -> Option<String> {
+        let store = self.session_store.as_ref()?;
+        let stored = match store.load(&self.username) {
+            Ok(Some(stored)) => stored,
+            Ok(None) => return None,
+            Err(e) => {
+                warn!("Failed to load stored session: {}", e);
+                return None;
+            }
         };
 
-        let session_key = match session_key {
-            Some(key) => key,
-            None => {
-                // Need to authenticate first
-                self.login().await?;
-                let session = self.session.read().await;
-                session.key.clone().ok_or(QrzXmlError::NoSessionKey)?
-            }
+        if stored.username != self.username
+            || stored.is_expired_at(self.clock.system_now())
+            || expired == Some(stored.key.as_str())
+        {
+            debug!("Ignoring stale stored session");
+            return None;
+        }
+
+        debug!("Restored session from session store");
+        telemetry::record_session_event(SessionEvent::Reused);
+        let mut session = self.session.write().await;
+        session.key = Some(stored.key.clone());
+        session.count = stored.count;
+        session.sub_exp = stored.sub_exp;
+        session.created_at = Some(stored.saved_at);
+        Some(stored.key)
+    }
+
+    /// Save a freshly established session to the session store
+    fn save_stored_session(&self, session_info: &SessionInfo) {
+        let (Some(store), Some(key)) = (&self.session_store, &session_info.key) else {
+            return;
         };
 
+        let stored = StoredSession {
+            count: session_info.count,
+            sub_exp: session_info.sub_exp.clone(),
+            saved_at: self.unix_now(),
+            ..StoredSession::new(&self.username, key)
+        };
+        if let Err(e) = store.save(&stored) {
+            warn!("Failed to save session: {}", e);
+        }
+    }
+
+    /// Remove this user's session from the session store
+    fn forget_stored_session(&self) {
+        if let Some(store) = &self.session_store {
+            if let Err(e) = store.clear(&self.username) {
+                warn!("Failed to clear stored session: {}", e);
+            }
+        }
+    }
+
+    /// Back off after a "too many sessions" login rejection, reusing the previous key if allowed
+    async fn handle_too_many_sessions(&self) -> Result<SessionInfo> {
+        let mut session = self.session.write().await;
+        session.login_backoff_until = Some(
+            self.clock.now() + Duration::from_secs(self.config.too_many_sessions_backoff_seconds),
+        );
+
+        if self.config.reuse_previous_session_key {
+            if let Some(key) = session.previous_key.take() {
+                warn!("QRZ reports too many sessions, reusing previous session key");
+                telemetry::record_session_event(SessionEvent::Reused);
+                session.key = Some(key.clone());
+                session.created_at = None;
+                return Ok(SessionInfo {
+                    key: Some(key),
+                    count: None,
+                    sub_exp: None,
+                    gm_time: None,
+                    message: None,
+                    error: None,
+                    remark: None,
+                });
+            }
+        }
+
+        warn!("QRZ reports too many sessions, backing off");
+        Err(QrzXmlError::TooManySessions)
+    }
+
+    /// Fail with [`QrzXmlError::ConnectionRefused`] while QRZ's refusal period lasts
+    ///
+    /// A refusal saved by another client sharing the session store is picked
+    /// up the first time this is checked.
+    async fn check_connection_refused(&self) -> Result<()> {
+        {
+            let session = self.session.read().await;
+            if session.refusal_restored && session.refused_until.is_none() {
+                return Ok(());
+            }
+        }
+
+        let now = self.clock.now();
+        let mut session = self.session.write().await;
+        if !session.refusal_restored {
+            session.refusal_restored = true;
+            if let Some(until) = self.load_refusal() {
+                let remaining = until.saturating_sub(self.unix_now());
+                session.refused_until = Some((now + Duration::from_secs(remaining), until));
+            }
+        }
+
+        match session.refused_until {
+            Some((instant, until)) if now < instant => {
+                Err(QrzXmlError::ConnectionRefused { until })
+            }
+            Some(_) => {
+                session.refused_until = None;
+                drop(session);
+                self.save_refusal(None);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Stop contacting QRZ for 24 hours after it refused connections
+    async fn enter_connection_refused(&self) -> QrzXmlError {
+        warn!("QRZ is refusing connections, not contacting it for 24 hours");
+        let until = self.unix_now() + CONNECTION_REFUSED_LOCKOUT.as_secs();
+        self.session.write().await.refused_until =
+            Some((self.clock.now() + CONNECTION_REFUSED_LOCKOUT, until));
+        self.save_refusal(Some(until));
+        QrzXmlError::ConnectionRefused { until }
+    }
+
+    /// Load a refusal saved in the session store
+    fn load_refusal(&self) -> Option<u64> {
+        let store = self.session_store.as_ref()?;
+        match store.load_refused_until(&self.username) {
+            Ok(until) => until,
+            Err(e) => {
+                warn!("Failed to load connection refusal: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Save or clear a refusal in the session store
+    fn save_refusal(&self, until: Option<u64>) {
+        if let Some(store) = &self.session_store {
+            if let Err(e) = store.save_refused_until(&self.username, until) {
+                warn!("Failed to save connection refusal: {}", e);
+            }
+        }
+    }
+
+    /// Make an authenticated request that returns XML
+    async fn make_authenticated_request(&self, params: &[(&str, &str)]) -> Result<QrzXmlResponse> {
+        let xml_content = self.fetch_authenticated_xml(params).await?;
+        let response = self.parse_xml(&xml_content, params, parse::parse_response)?;
+        self.record_server_version(&response.version);
+        self.check_session_info(&response.session).await?;
+        Ok(response)
+    }
+
+    /// Make an authenticated `dxcc=all` request, which lists every entity
+    async fn make_dxcc_list_request(&self) -> Result<DxccListResponse> {
+        let params = [("dxcc", "all")];
+        let xml_content = self.fetch_authenticated_xml(&params).await?;
+        let response = self.parse_xml(&xml_content, &params, parse::parse_dxcc_list)?;
+        self.record_server_version(&response.version);
+        self.check_session_info(&response.session).await?;
+        Ok(response)
+    }
+
+    /// Fetch the XML body of an authenticated request
+    async fn fetch_authenticated_xml(&self, params: &[(&str, &str)]) -> Result<String> {
+        let (url, full_url, _permit) = self.prepare_authenticated_request(params).await?;
+
+        debug!("Making request to: {}", full_url);
+
+        let xml_content = self.send_get(&url, &full_url, true).await?;
+        debug!("Received XML response: {}", xml_content);
+        Ok(xml_content)
+    }
+
+    /// Check that an authenticated request may be sent and build its URL
+    ///
+    /// Returns the base and full URLs and the governor permit, which must be
+    /// held until the response has been read.
+    async fn prepare_authenticated_request(
+        &self,
+        params: &[(&str, &str)],
+    ) -> Result<(String, String, Option<GovernorPermit<'_>>)> {
+        self.check_connection_refused().await?;
+        self.pace().await;
+        let permit = self.acquire_governor_permit().await?;
+        self.check_lookup_budget()?;
+        let session_key = self.session_key().await?;
+
         let url = self.build_url("")?;
         let mut all_params = vec![("s", session_key.as_str())];
         all_params.extend_from_slice(params);
+        let full_url = format!("{}?{}", url, self.query_string(&all_params));
+        Ok((url, full_url, permit))
+    }
 
-        let response = self.make_request(&url, &all_params).await?;
-
+    /// Update the session from the `Session` element of an authenticated
+    /// response, failing on session errors
+    pub(crate) async fn check_session_info(&self, session_info: &SessionInfo) -> Result<()> {
         // Update session info from response
-        {
+        let new_message = {
             let mut session = self.session.write().await;
-            session.update_from_session_info(&response.session);
-        }
+            session.update_from_session_info(session_info, self.unix_now())
+        };
+        self.check_quota(session_info.count);
+        self.report_message(new_message);
 
         // Check for session-related errors
-        if let Some(error) = &response.session.error {
+        if let Some(error) = &session_info.error {
+            if error.contains("Connection refused") {
+                return Err(self.enter_connection_refused().await);
+            }
             if error.contains("Session Timeout") || error.contains("session") {
+                self.session_expired();
                 return Err(QrzXmlError::SessionExpired);
             }
             if error.contains("not found") {
@@ -381,78 +1802,98 @@ impl QrzXmlClient {
         }
 
         // Check if we have a valid session key in response
-        if !response.session.has_valid_session() {
+        if !session_info.has_valid_session() {
+            self.session_expired();
             return Err(QrzXmlError::SessionExpired);
         }
 
-        Ok(response)
+        Ok(())
     }
 
-    /// Make an authenticated request that returns HTML (for biography)
-    async fn make_authenticated_html_request(&self, params: &[(&str, &str)]) -> Result<String> {
-        let session_key = {
-            let session = self.session.read().await;
-            session.key.clone()
-        };
+    /// Make an authenticated request, renewing the session and retrying once if it expired
+    async fn make_authenticated_request_with_reauth(
+        &self,
+        params: &[(&str, &str)],
+    ) -> Result<QrzXmlResponse> {
+        self.with_session_retry(|| self.make_authenticated_request(params))
+            .await
+    }
 
-        let session_key = match session_key {
-            Some(key) => key,
-            None => {
-                // Need to authenticate first
-                self.login().await?;
-                let session = self.session.read().await;
-                session.key.clone().ok_or(QrzXmlError::NoSessionKey)?
+    /// Run an authenticated request, renewing the session and retrying once if it expired
+    ///
+    /// Shared by every endpoint, so they all recover from expired sessions the
+    /// same way. `SessionExpired` is returned if the retry budget is exhausted
+    /// or the retried request is rejected too.
+    async fn with_session_retry<T, F, Fut>(&self, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let used_key = self.session.read().await.key.clone();
+        match request().await {
+            Err(QrzXmlError::SessionExpired) => {
+                // Without a key beforehand, the request logged in itself
+                let expired = match used_key {
+                    Some(key) => Some(key),
+                    None => self.session.read().await.key.clone(),
+                };
+                if !self.renew_session_for_retry(expired.as_deref()).await? {
+                    return Err(QrzXmlError::SessionExpired);
+                }
+                request().await
             }
-        };
+            other => other,
+        }
+    }
 
-        let url = self.build_url("")?;
-        let mut all_params = vec![("s", session_key.as_str())];
-        all_params.extend_from_slice(params);
+    /// Replace the session whose key QRZ rejected as `expired`, if the retry
+    /// budget allows a retry
+    ///
+    /// Concurrent requests failing on the same key renew it once: whoever takes
+    /// `login_lock` first logs in, and the others retry with the new key.
+    async fn renew_session_for_retry(&self, expired: Option<&str>) -> Result<bool> {
+        if !self.retry_budget.try_acquire(self.clock.now()) {
+            warn!("Session expired but the retry budget is exhausted");
+            return Ok(false);
+        }
 
-        let query_string = all_params
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
-            .collect::<Vec<_>>()
-            .join("&");
+        telemetry::in_reauth_span(async {
+            let _guard = self.login_lock.lock().await;
+            {
+                let mut session = self.session.write().await;
+                if session.key.is_some() && session.key.as_deref() != expired {
+                    debug!("Session already renewed by another task, retrying");
+                    return Ok(());
+                }
+                session.expire();
+            }
 
-        let full_url = format!("{}?{}", url, query_string);
+            warn!("Session expired, re-authenticating and retrying");
+            telemetry::record_reauth();
+            // Another process sharing the session store may have renewed it already
+            self.establish_session(expired).await
+        })
+        .await?;
+        Ok(true)
+    }
 
-        debug!("Making HTML request to: {}", full_url);
+    /// Make an authenticated request that returns HTML (for biography)
+    async fn make_authenticated_html_request(&self, params: &[(&str, &str)]) -> Result<String> {
+        let (url, full_url, _permit) = self.prepare_authenticated_request(params).await?;
 
-        let response = self
-            .http_client
-            .get(&full_url)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        let html_content = response.text().await?;
-
-        // Check if the response looks like an error (starts with XML)
-        if html_content.trim_start().starts_with("<?xml") {
-            // This might be an error response, try to parse as XML
-            match quick_xml::de::from_str::<QrzXmlResponse>(&html_content) {
-                Ok(xml_resp) => {
-                    if let Some(error) = xml_resp.session.error {
-                        return Err(QrzXmlError::api_error(error));
-                    }
-                }
-                Err(_) => {
-                    // Not valid XML, just return the HTML as-is
-                }
-            }
-        }
+        debug!("Making HTML request to: {}", full_url);
 
-        Ok(html_content)
+        self.send_get(&url, &full_url, true).await
     }
 
-    /// Make a raw HTTP request and parse XML response
-    async fn make_request(&self, url: &str, params: &[(&str, &str)]) -> Result<QrzXmlResponse> {
-        let query_string = params
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
-            .collect::<Vec<_>>()
-            .join("&");
+    /// Make a raw HTTP request and return the XML body
+    async fn fetch_xml(
+        &self,
+        url: &str,
+        params: &[(&str, &str)],
+        hedgeable: bool,
+    ) -> Result<String> {
+        let query_string = self.query_string(params);
 
         let full_url = if query_string.is_empty() {
             url.to_string()
@@ -462,26 +1903,279 @@ impl QrzXmlClient {
 
         debug!("Making request to: {}", full_url);
 
-        let response = self
-            .http_client
-            .get(&full_url)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        let xml_content = response.text().await?;
+        let xml_content = self.send_get(url, &full_url, hedgeable).await?;
         debug!("Received XML response: {}", xml_content);
+        Ok(xml_content)
+    }
+
+    /// Encode request parameters, followed by the configured extra parameters
+    fn query_string(&self, params: &[(&str, &str)]) -> String {
+        let extra = self
+            .config
+            .extra_query_params
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()));
+        params
+            .iter()
+            .copied()
+            .chain(extra)
+            .map(|(k, v)| format!("{}={}", urlencoding::encode(k), urlencoding::encode(v)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Make a request and parse the XML response
+    async fn make_request(
+        &self,
+        url: &str,
+        params: &[(&str, &str)],
+        hedgeable: bool,
+    ) -> Result<QrzXmlResponse> {
+        let xml_content = self.fetch_xml(url, params, hedgeable).await?;
+        let response = self.parse_xml(&xml_content, params, parse::parse_response)?;
+        self.record_server_version(&response.version);
+        Ok(response)
+    }
 
-        let parsed_response: QrzXmlResponse =
-            quick_xml::de::from_str(&xml_content).map_err(|e| {
+    /// Parse the XML response to a request in the configured mode, reporting
+    /// its warnings
+    fn parse_xml<T>(
+        &self,
+        xml_content: &str,
+        params: &[(&str, &str)],
+        parse: parse::Parser<T>,
+    ) -> Result<T> {
+        let (parsed_response, warnings) =
+            parse(xml_content, self.config.parse_mode).map_err(|e| {
                 warn!("Failed to parse XML response: {}", e);
                 warn!("Response content: {}", xml_content);
                 e
             })?;
 
+        if warnings.is_empty() {
+            return Ok(parsed_response);
+        }
+        for warning in &warnings {
+            warn!("QRZ response has {}", warning);
+        }
+        self.parse_warning_count
+            .fetch_add(warnings.len() as u64, Ordering::Relaxed);
+        let query = params
+            .iter()
+            .filter(|(name, _)| !PRIVATE_QUERY_PARAMS.contains(name))
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("&");
+        self.emit(ClientEvent::ParseWarnings { query, warnings });
         Ok(parsed_response)
     }
 
+    /// Remember the API version a response reported
+    fn record_server_version(&self, version: &Option<String>) {
+        if version.is_some() {
+            *self
+                .server_version
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = version.clone();
+        }
+    }
+
+    /// Run a lookup with instrumentation, emitting an event if it fails
+    async fn track_lookup<T>(
+        &self,
+        kind: &'static str,
+        query: &str,
+        lookup: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let started = Instant::now();
+        let result = telemetry::instrument_lookup(kind, lookup).await;
+        let elapsed = started.elapsed();
+        self.latency.record(kind, elapsed);
+
+        if let Some(threshold) = self.config.slow_lookup_threshold_ms {
+            if elapsed >= Duration::from_millis(threshold) {
+                // Only the endpoint is logged; credentials and session keys stay out of logs
+                warn!(
+                    "Slow QRZ lookup: kind={} query={} elapsed={:?} outcome={} endpoint={} api_version={}",
+                    kind,
+                    query,
+                    elapsed,
+                    if result.is_ok() { "success" } else { "error" },
+                    self.config.base_url,
+                    self.api_version,
+                );
+            }
+        }
+
+        if let Err(e) = &result {
+            self.errors.record(kind, query, e, self.unix_now());
+            self.emit(ClientEvent::LookupFailed {
+                kind,
+                query: query.to_string(),
+                error: e.to_string(),
+            });
+        }
+        result
+    }
+
+    /// The clock's current time in seconds since the Unix epoch
+    fn unix_now(&self) -> u64 {
+        session_store::unix_seconds(self.clock.system_now())
+    }
+
+    /// Report that QRZ rejected the session in use
+    fn session_expired(&self) {
+        telemetry::record_session_event(SessionEvent::Expired);
+        self.emit(ClientEvent::SessionExpired);
+    }
+
+    /// Emit quota warnings for thresholds the lookup count reached, and a reset
+    /// event if it started a new window
+    fn check_quota(&self, count: Option<u32>) {
+        let Some(count) = count else {
+            return;
+        };
+        let observation = self.quota.observe(count, self.clock.now());
+        if let Some(previous) = observation.rolled_over_from {
+            info!(
+                "QRZ lookup count dropped from {} to {}, new 24-hour window",
+                previous, count
+            );
+            self.emit(ClientEvent::QuotaReset { previous, count });
+        }
+        for threshold in observation.thresholds {
+            warn!("QRZ lookup count {} reached threshold {}", count, threshold);
+            self.emit(ClientEvent::QuotaWarning { count, threshold });
+        }
+    }
+
+    /// Wait for this lookup's turn under count-based pacing, if configured
+    async fn pace(&self) {
+        let Some(pacer) = &self.pacer else {
+            return;
+        };
+        let now = self.clock.now();
+        let wait = pacer.reserve(now, self.quota.count(now).unwrap_or(0));
+        if !wait.is_zero() {
+            debug!("Pacing lookup, waiting {:?}", wait);
+            self.clock.sleep(wait).await;
+        }
+    }
+
+    /// Wait for the concurrency governor, if configured, to admit a lookup
+    async fn acquire_governor_permit(&self) -> Result<Option<GovernorPermit<'_>>> {
+        match &self.governor {
+            Some(governor) => {
+                let count = self.quota.count(self.clock.now()).unwrap_or(0);
+                governor.acquire(count).await.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Fail fast if the enforced daily lookup limit is used up, otherwise count the lookup
+    fn check_lookup_budget(&self) -> Result<()> {
+        if let (true, Some(limit)) = (
+            self.config.enforce_daily_lookup_limit,
+            self.config.daily_lookup_limit,
+        ) {
+            let used = self.quota.count(self.clock.now()).unwrap_or(0);
+            if used >= limit {
+                warn!("Daily lookup budget of {} exhausted", limit);
+                return Err(QrzXmlError::QuotaExceeded { used, limit });
+            }
+        }
+        self.quota.record_lookup(self.clock.now());
+        Ok(())
+    }
+
+    /// Log and emit a server message that was not seen before
+    fn report_message(&self, message: Option<String>) {
+        if let Some(message) = message {
+            info!("QRZ message: {}", message);
+            self.emit(ClientEvent::ServerMessage { message });
+        }
+    }
+
+    /// Send an event to subscribers; having none is not an error
+    fn emit(&self, event: ClientEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Send a GET request and return the response body
+    ///
+    /// `url` is the endpoint without its query string; it is what gets recorded on
+    /// the request span so credentials and session keys never end up in traces.
+    ///
+    /// When hedging is configured and `hedgeable` is set, a second identical request
+    /// is fired if the first has not answered within the threshold, and whichever
+    /// finishes first wins. Only idempotent lookups may be hedged.
+    async fn send_get(&self, url: &str, full_url: &str, hedgeable: bool) -> Result<String> {
+        let hedge_after = match self.config.hedge_after_ms {
+            Some(ms) if hedgeable => Duration::from_millis(ms),
+            _ => return self.send_once(url, full_url).await,
+        };
+
+        let mut first = Box::pin(self.send_once(url, full_url));
+        if let Ok(result) = tokio::time::timeout(hedge_after, &mut first).await {
+            return result;
+        }
+
+        if !self.retry_budget.try_acquire(self.clock.now()) {
+            debug!("Retry budget exhausted, not hedging slow request");
+            return first.await;
+        }
+
+        debug!(
+            "Request slower than {:?}, sending hedged request",
+            hedge_after
+        );
+        // QRZ counts the duplicate as a lookup of its own
+        self.quota.record_lookup(self.clock.now());
+        let second = self.send_once(url, full_url);
+        tokio::select! {
+            result = &mut first => result,
+            result = second => result,
+        }
+    }
+
+    /// Send a single GET request and return the response body
+    async fn send_once(&self, url: &str, full_url: &str) -> Result<String> {
+        telemetry::in_request_span(url, async {
+            let started = Instant::now();
+            let result = async {
+                let response = self.send_request(full_url).await?;
+                let content_type = content_type(&response);
+                let bytes = response.bytes().await?;
+                Ok(charset::decode_body(&bytes, content_type.as_deref()))
+            }
+            .await;
+            telemetry::record_request_duration(started.elapsed(), &result);
+            result
+        })
+        .await
+    }
+
+    /// Send a single GET request, failing on error statuses, without reading the body
+    async fn send_request(&self, full_url: &str) -> Result<reqwest::Response> {
+        let mut request = self.http_client.get(full_url);
+        if let Some(headers) = telemetry::propagation_headers() {
+            request = request.headers(headers);
+        }
+        #[cfg(feature = "request-signing")]
+        if let Some(signer) = &self.config.request_signer {
+            request = request.header(signer.header_name(), signer.sign(full_url)?);
+        }
+
+        let response = request.send().await?;
+        telemetry::record_status(response.status().as_u16());
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            self.emit(ClientEvent::RateLimited);
+            return Err(QrzXmlError::RateLimitExceeded);
+        }
+        Ok(response.error_for_status()?)
+    }
+
     /// Build URL for API requests
     pub fn build_url(&self, path: &str) -> Result<String> {
         let mut url = Url::parse(&self.config.base_url)?;
@@ -508,6 +2202,61 @@ impl QrzXmlClient {
     }
 }
 
+/// DXCC cache key of an entity number, kept apart from callsign keys
+fn dxcc_entity_key(entity: u32) -> String {
+    format!("entity:{}", entity)
+}
+
+/// DXCC cache key of a callsign, kept apart from entity number keys
+fn dxcc_callsign_key(callsign: &str) -> String {
+    format!("call:{}", callsign)
+}
+
+/// Address to bind connections to, restricting them to the requested IP version
+///
+/// Binding to the unspecified address of one family makes the connector skip
+/// resolved addresses of the other.
+fn local_address(family: IpFamily, address: Option<IpAddr>) -> Result<Option<IpAddr>> {
+    match (family, address) {
+        (IpFamily::V4, Some(IpAddr::V6(_))) | (IpFamily::V6, Some(IpAddr::V4(_))) => Err(
+            QrzXmlError::invalid_input("Local address does not match the configured IP family"),
+        ),
+        (_, Some(address)) => Ok(Some(address)),
+        (IpFamily::Any, None) => Ok(None),
+        (IpFamily::V4, None) => Ok(Some(Ipv4Addr::UNSPECIFIED.into())),
+        (IpFamily::V6, None) => Ok(Some(Ipv6Addr::UNSPECIFIED.into())),
+    }
+}
+
+/// Build the configured extra headers, rejecting invalid names or values
+fn extra_headers(headers: &[(String, String)]) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let invalid = || QrzXmlError::invalid_input(format!("Invalid header {}", name));
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?;
+        let value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+        map.append(name, value);
+    }
+    Ok(map)
+}
+
+/// Check if a login error means QRZ has too many active sessions for this account
+fn is_too_many_sessions(error: &str) -> bool {
+    let error = error.to_ascii_lowercase();
+    error.contains("too many sessions")
+        || error.contains("session limit")
+        || error.contains("maximum number of sessions")
+}
+
+/// The `Content-Type` header of a response
+fn content_type(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
 // Add a helper trait for URL encoding
 mod urlencoding {
     pub fn encode(input: &str) -> String {
@@ -525,6 +2274,45 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_from_env_vars() {
+        let vars = |entries: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                entries
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+                    .ok_or(VarError::NotPresent)
+            }
+        };
+
+        let client = QrzXmlClient::from_vars(vars(&[
+            (ENV_USERNAME, "testuser"),
+            (ENV_PASSWORD, "testpass"),
+            (ENV_BASE_URL, "http://localhost:8080/xml"),
+            (ENV_API_VERSION, "1.34"),
+        ]))
+        .unwrap();
+        assert_eq!(client.username(), "testuser");
+        assert_eq!(client.api_version, ApiVersion::version("1.34"));
+        assert_eq!(client.config.base_url, "http://localhost:8080/xml");
+
+        let missing = QrzXmlClient::from_vars(vars(&[(ENV_USERNAME, "testuser")]));
+        assert!(
+            matches!(missing, Err(QrzXmlError::MissingEnvVar { name }) if name == ENV_PASSWORD)
+        );
+
+        let bad_version = QrzXmlClient::from_vars(vars(&[
+            (ENV_USERNAME, "testuser"),
+            (ENV_PASSWORD, "testpass"),
+            (ENV_API_VERSION, "newest"),
+        ]));
+        assert!(matches!(
+            bad_version,
+            Err(QrzXmlError::InvalidApiVersion { .. })
+        ));
+    }
+
     #[test]
     fn test_url_building() {
         let config = QrzXmlClientConfig::default();
@@ -546,6 +2334,30 @@ mod tests {
         assert_eq!(url, "https://xmldata.qrz.com/xml");
     }
 
+    #[test]
+    fn test_ip_family() {
+        let config = QrzXmlClientConfig {
+            base_url: "http://[2001:db8::1]:8080/xml".to_string(),
+            ip_family: IpFamily::V6,
+            ..Default::default()
+        };
+        let client =
+            QrzXmlClient::with_config("test", "test", ApiVersion::Current, config).unwrap();
+        assert_eq!(
+            client.build_url("").unwrap(),
+            "http://[2001:db8::1]:8080/xml/current/"
+        );
+
+        let v4: IpAddr = "192.0.2.1".parse().unwrap();
+        assert_eq!(local_address(IpFamily::Any, None).unwrap(), None);
+        assert_eq!(
+            local_address(IpFamily::V4, None).unwrap(),
+            Some(Ipv4Addr::UNSPECIFIED.into())
+        );
+        assert_eq!(local_address(IpFamily::V4, Some(v4)).unwrap(), Some(v4));
+        assert!(local_address(IpFamily::V6, Some(v4)).is_err());
+    }
+
     #[test]
     fn test_session_state() {
         let mut session = SessionState::new();
@@ -558,11 +2370,30 @@ mod tests {
             gm_time: None,
             message: None,
             error: None,
+            remark: None,
         };
 
-        session.update_from_session_info(&session_info);
+        session.update_from_session_info(&session_info, 1000);
         assert!(session.has_valid_session());
         assert_eq!(session.key, Some("test_key".to_string()));
         assert_eq!(session.count, Some(42));
+
+        let snapshot = session.snapshot(false);
+        assert_eq!(snapshot.key, None);
+        assert_eq!(snapshot.sub_exp.as_deref(), Some("test_exp"));
+        assert_eq!(session.snapshot(true).key.as_deref(), Some("test_key"));
+
+        // Messages are only reported the first time they are seen
+        let with_message = SessionInfo {
+            message: Some("Your subscription expires in 3 days".to_string()),
+            ..session_info
+        };
+        assert_eq!(
+            session
+                .update_from_session_info(&with_message, 1000)
+                .as_deref(),
+            Some("Your subscription expires in 3 days")
+        );
+        assert_eq!(session.update_from_session_info(&with_message, 1000), None);
     }
 }
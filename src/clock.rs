@@ -0,0 +1,129 @@
+//! Time source used for session backoff, retry budgets, caches and worker backoff.
+//!
+//! The client reads the time and waits through a [`Clock`], so tests can swap
+//! in a [`MockClock`] (with the `test-util` feature) and step over login
+//! backoffs, cache lifetimes or rate limit waits without sleeping for real.
+//! Wall-clock times, such as session ages, connection refusal periods and
+//! error timestamps, come from the same clock, so session expiry can be tested
+//! the same way. Request timeouts and hedging still run on Tokio's timer.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant, SystemTime};
+
+/// A future returned by [`Clock::sleep`]
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Source of the current time and of delays
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// The current instant
+    fn now(&self) -> Instant;
+
+    /// The current wall-clock time, for session ages and timestamps
+    fn system_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    /// Wait for the given duration
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// The default clock, backed by Tokio's timer
+///
+/// Follows Tokio's paused time in tests using `tokio::time::pause`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// A clock that only moves when told to
+///
+/// Sleeps complete as soon as [`advance`](MockClock::advance) moves the clock
+/// past their deadline.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug)]
+pub struct MockClock {
+    start: Instant,
+    system_start: SystemTime,
+    elapsed: tokio::sync::watch::Sender<Duration>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MockClock {
+    /// Create a clock standing at the current instant
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            system_start: SystemTime::now(),
+            elapsed: tokio::sync::watch::Sender::new(Duration::ZERO),
+        }
+    }
+
+    /// Move the clock forward, waking sleeps whose deadline has passed
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.send_modify(|elapsed| *elapsed += duration);
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.borrow()
+    }
+
+    fn system_now(&self) -> SystemTime {
+        self.system_start + *self.elapsed.borrow()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        let mut elapsed = self.elapsed.subscribe();
+        let deadline = *elapsed.borrow() + duration;
+        Box::pin(async move {
+            while *elapsed.borrow_and_update() < deadline {
+                if elapsed.changed().await.is_err() {
+                    return;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_clock_sleep() {
+        let clock = MockClock::new();
+        let start = clock.now();
+
+        let sleep = tokio::spawn(clock.sleep(Duration::from_secs(60)));
+        clock.advance(Duration::from_secs(30));
+        tokio::task::yield_now().await;
+        assert!(!sleep.is_finished());
+
+        clock.advance(Duration::from_secs(30));
+        sleep.await.unwrap();
+        assert_eq!(clock.now() - start, Duration::from_secs(60));
+        let wall = clock.system_now().duration_since(clock.system_start);
+        assert_eq!(wall.unwrap(), Duration::from_secs(60));
+
+        clock.sleep(Duration::ZERO).await;
+    }
+}
@@ -0,0 +1,97 @@
+//! QRZ credentials in the OS keyring.
+//!
+//! Desktop logging applications should not keep the QRZ password in a plain
+//! configuration file. With the `keyring` feature, the username and password
+//! can be kept in the platform's credential store instead: the Secret Service
+//! on Linux, the Keychain on macOS and the Credential Manager on Windows.
+//!
+//! Credentials are stored under a service name chosen by the application, so
+//! several applications (or profiles) can keep separate accounts. The calls
+//! block on the platform store; from async code, run them with
+//! `tokio::task::spawn_blocking` where that matters.
+
+use crate::error::{QrzXmlError, Result};
+use keyring::Entry;
+
+/// Keyring user of the entry that holds the QRZ username for a service
+const USERNAME_ENTRY: &str = "qrz-username";
+
+/// Save a QRZ username and password under the given service name
+pub fn store_credentials(service: &str, username: &str, password: &str) -> Result<()> {
+    if username.is_empty() {
+        return Err(QrzXmlError::invalid_input("Username cannot be empty"));
+    }
+
+    entry(service, username)?
+        .set_password(password)
+        .map_err(keyring_error)?;
+    entry(service, USERNAME_ENTRY)?
+        .set_password(username)
+        .map_err(keyring_error)?;
+    debug!("Stored QRZ credentials in keyring service {}", service);
+    Ok(())
+}
+
+/// Load the QRZ username and password saved under the given service name
+pub fn load_credentials(service: &str) -> Result<(String, String)> {
+    let username = entry(service, USERNAME_ENTRY)?
+        .get_password()
+        .map_err(keyring_error)?;
+    let password = entry(service, &username)?
+        .get_password()
+        .map_err(keyring_error)?;
+    Ok((username, password))
+}
+
+/// Remove the credentials saved under the given service name
+///
+/// Succeeds if nothing was stored.
+pub fn delete_credentials(service: &str) -> Result<()> {
+    let username_entry = entry(service, USERNAME_ENTRY)?;
+    let username = match username_entry.get_password() {
+        Ok(username) => username,
+        Err(keyring::Error::NoEntry) => return Ok(()),
+        Err(e) => return Err(keyring_error(e)),
+    };
+
+    for entry in [entry(service, &username)?, username_entry] {
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(keyring_error(e)),
+        }
+    }
+    Ok(())
+}
+
+fn entry(service: &str, user: &str) -> Result<Entry> {
+    Entry::new(service, user).map_err(keyring_error)
+}
+
+fn keyring_error(error: keyring::Error) -> QrzXmlError {
+    match error {
+        keyring::Error::NoEntry => QrzXmlError::keyring("No QRZ credentials stored"),
+        other => QrzXmlError::keyring(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_credentials() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+
+        // Mock entries do not share storage, so nothing is ever found
+        assert!(store_credentials("qrz-xml-test", "testuser", "testpass").is_ok());
+        assert!(matches!(
+            load_credentials("qrz-xml-test"),
+            Err(QrzXmlError::Keyring { .. })
+        ));
+        assert!(delete_credentials("qrz-xml-test").is_ok());
+        assert!(matches!(
+            store_credentials("qrz-xml-test", "", "testpass"),
+            Err(QrzXmlError::InvalidInput { .. })
+        ));
+    }
+}
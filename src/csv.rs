@@ -0,0 +1,545 @@
+//! CSV export.
+//!
+//! [`CallsignInfo`] and [`DxccInfo`] carry a map of unmodeled elements and
+//! skip some fields when serializing, so they make poor CSV rows. [`CallsignRow`]
+//! and [`DxccRow`] are flat copies with one column per field, in the order of
+//! [`CALLSIGN_COLUMNS`] and [`DXCC_COLUMNS`]. Columns are only ever appended,
+//! so spreadsheets and scripts can rely on them. Missing values are empty cells.
+//!
+//! ```rust,no_run
+//! # async fn run(client: qrz_xml::QrzXmlClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let records = vec![client.lookup_callsign("W1AW").await?];
+//! let file = std::fs::File::create("lookups.csv")?;
+//! qrz_xml::csv::write_callsigns(file, &records)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{QrzXmlError, Result};
+use crate::types::{CallsignInfo, DxccInfo};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// Header of a callsign CSV file
+pub const CALLSIGN_COLUMNS: &[&str] = &[
+    "call",
+    "xref",
+    "aliases",
+    "dxcc",
+    "fname",
+    "name",
+    "addr1",
+    "addr2",
+    "state",
+    "zip",
+    "country",
+    "ccode",
+    "lat",
+    "lon",
+    "grid",
+    "county",
+    "fips",
+    "land",
+    "efdate",
+    "expdate",
+    "p_call",
+    "class",
+    "codes",
+    "qslmgr",
+    "email",
+    "url",
+    "u_views",
+    "bio",
+    "biodate",
+    "image",
+    "imageinfo",
+    "serial",
+    "moddate",
+    "msa",
+    "area_code",
+    "time_zone",
+    "gmt_offset",
+    "dst",
+    "eqsl",
+    "mqsl",
+    "cqzone",
+    "ituzone",
+    "born",
+    "user",
+    "lotw",
+    "iota",
+    "geoloc",
+    "attn",
+    "nickname",
+    "name_fmt",
+    "queried_callsign",
+];
+
+/// Header of a DXCC entity CSV file
+pub const DXCC_COLUMNS: &[&str] = &[
+    "dxcc",
+    "cc",
+    "ccc",
+    "name",
+    "continent",
+    "ituzone",
+    "cqzone",
+    "timezone",
+    "lat",
+    "lon",
+    "notes",
+];
+
+/// A callsign record as a flat CSV row
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CallsignRow {
+    /// Primary callsign
+    pub call: String,
+
+    /// Cross reference callsign that returned this record
+    pub xref: Option<String>,
+
+    /// Other callsigns that resolve to this record
+    pub aliases: Option<String>,
+
+    /// DXCC entity ID (country code)
+    pub dxcc: Option<u32>,
+
+    /// First name
+    pub fname: Option<String>,
+
+    /// Last name
+    pub name: Option<String>,
+
+    /// Address line 1 (house number and street)
+    pub addr1: Option<String>,
+
+    /// Address line 2 (city)
+    pub addr2: Option<String>,
+
+    /// State (USA only)
+    pub state: Option<String>,
+
+    /// ZIP/postal code
+    pub zip: Option<String>,
+
+    /// Country name for QSL mailing address
+    pub country: Option<String>,
+
+    /// DXCC entity code for mailing address country
+    pub ccode: Option<u32>,
+
+    /// Latitude (signed decimal, S < 0 > N)
+    pub lat: Option<f64>,
+
+    /// Longitude (signed decimal, W < 0 > E)
+    pub lon: Option<f64>,
+
+    /// Grid locator
+    pub grid: Option<String>,
+
+    /// County name (USA)
+    pub county: Option<String>,
+
+    /// FIPS county identifier (USA)
+    pub fips: Option<String>,
+
+    /// DXCC country name of the callsign
+    pub land: Option<String>,
+
+    /// License effective date (USA)
+    pub efdate: Option<String>,
+
+    /// License expiration date (USA)
+    pub expdate: Option<String>,
+
+    /// Previous callsign
+    pub p_call: Option<String>,
+
+    /// License class
+    pub class: Option<String>,
+
+    /// License type codes (USA)
+    pub codes: Option<String>,
+
+    /// QSL manager info
+    pub qslmgr: Option<String>,
+
+    /// Email address
+    pub email: Option<String>,
+
+    /// Web page address
+    pub url: Option<String>,
+
+    /// QRZ web page views
+    pub u_views: Option<u32>,
+
+    /// Approximate biography size in bytes
+    pub bio: Option<u64>,
+
+    /// Biography last update date
+    pub biodate: Option<String>,
+
+    /// Full URL of primary image
+    pub image: Option<String>,
+
+    /// Image dimensions (height:width:size)
+    pub imageinfo: Option<String>,
+
+    /// QRZ database serial number
+    pub serial: Option<u32>,
+
+    /// Last modified date
+    pub moddate: Option<String>,
+
+    /// Metro Service Area (USPS)
+    pub msa: Option<String>,
+
+    /// Telephone area code (USA)
+    pub area_code: Option<String>,
+
+    /// Time zone (USA)
+    pub time_zone: Option<String>,
+
+    /// GMT time offset
+    pub gmt_offset: Option<String>,
+
+    /// Daylight saving time observed
+    pub dst: Option<String>,
+
+    /// Will accept eQSL (Y/N or blank)
+    pub eqsl: Option<String>,
+
+    /// Will return paper QSL (Y/N or blank)
+    pub mqsl: Option<String>,
+
+    /// CQ Zone identifier
+    pub cqzone: Option<u32>,
+
+    /// ITU Zone identifier
+    pub ituzone: Option<u32>,
+
+    /// Operator's birth year
+    pub born: Option<u32>,
+
+    /// User who manages this callsign on QRZ
+    pub user: Option<String>,
+
+    /// Will accept LOTW (Y/N or blank)
+    pub lotw: Option<String>,
+
+    /// IOTA designator
+    pub iota: Option<String>,
+
+    /// Source of lat/long data
+    pub geoloc: Option<String>,
+
+    /// Attention address line (new in v1.34)
+    pub attn: Option<String>,
+
+    /// Nickname (new in v1.34)
+    pub nickname: Option<String>,
+
+    /// Combined full name and nickname (new in v1.34)
+    pub name_fmt: Option<String>,
+
+    /// The callsign as given to the lookup, when it was normalized
+    pub queried_callsign: Option<String>,
+}
+
+/// A DXCC entity as a flat CSV row
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DxccRow {
+    /// DXCC entity number
+    pub dxcc: u32,
+
+    /// 2-letter country code (ISO-3166)
+    pub cc: Option<String>,
+
+    /// 3-letter country code (ISO-3166)
+    pub ccc: Option<String>,
+
+    /// Long country name
+    pub name: String,
+
+    /// 2-letter continent designator
+    pub continent: Option<String>,
+
+    /// ITU Zone
+    pub ituzone: Option<u32>,
+
+    /// CQ Zone
+    pub cqzone: Option<u32>,
+
+    /// UTC timezone offset +/-
+    pub timezone: Option<String>,
+
+    /// Latitude (approximate center)
+    pub lat: Option<f64>,
+
+    /// Longitude (approximate center)
+    pub lon: Option<f64>,
+
+    /// Special notes and exceptions
+    pub notes: Option<String>,
+}
+
+impl From<&CallsignInfo> for CallsignRow {
+    fn from(info: &CallsignInfo) -> Self {
+        Self {
+            call: info.call.clone(),
+            xref: info.xref.clone(),
+            aliases: info.aliases.clone(),
+            dxcc: info.dxcc,
+            fname: info.fname.clone(),
+            name: info.name.clone(),
+            addr1: info.addr1.clone(),
+            addr2: info.addr2.clone(),
+            state: info.state.clone(),
+            zip: info.zip.clone(),
+            country: info.country.clone(),
+            ccode: info.ccode,
+            lat: info.lat,
+            lon: info.lon,
+            grid: info.grid.clone(),
+            county: info.county.clone(),
+            fips: info.fips.clone(),
+            land: info.land.clone(),
+            efdate: info.efdate.clone(),
+            expdate: info.expdate.clone(),
+            p_call: info.p_call.clone(),
+            class: info.class.clone(),
+            codes: info.codes.clone(),
+            qslmgr: info.qslmgr.clone(),
+            email: info.email.clone(),
+            url: info.url.clone(),
+            u_views: info.u_views,
+            bio: info.bio,
+            biodate: info.biodate.clone(),
+            image: info.image.clone(),
+            imageinfo: info.imageinfo.clone(),
+            serial: info.serial,
+            moddate: info.moddate.clone(),
+            msa: info.msa.clone(),
+            area_code: info.area_code.clone(),
+            time_zone: info.time_zone.clone(),
+            gmt_offset: info.gmt_offset.clone(),
+            dst: info.dst.clone(),
+            eqsl: info.eqsl.clone(),
+            mqsl: info.mqsl.clone(),
+            cqzone: info.cqzone,
+            ituzone: info.ituzone,
+            born: info.born,
+            user: info.user.clone(),
+            lotw: info.lotw.clone(),
+            iota: info.iota.clone(),
+            geoloc: info.geoloc.clone(),
+            attn: info.attn.clone(),
+            nickname: info.nickname.clone(),
+            name_fmt: info.name_fmt.clone(),
+            queried_callsign: info.queried_callsign.clone(),
+        }
+    }
+}
+
+impl From<CallsignRow> for CallsignInfo {
+    fn from(row: CallsignRow) -> Self {
+        Self {
+            call: row.call,
+            xref: row.xref,
+            aliases: row.aliases,
+            dxcc: row.dxcc,
+            fname: row.fname,
+            name: row.name,
+            addr1: row.addr1,
+            addr2: row.addr2,
+            state: row.state,
+            zip: row.zip,
+            country: row.country,
+            ccode: row.ccode,
+            lat: row.lat,
+            lon: row.lon,
+            grid: row.grid,
+            county: row.county,
+            fips: row.fips,
+            land: row.land,
+            efdate: row.efdate,
+            expdate: row.expdate,
+            p_call: row.p_call,
+            class: row.class,
+            codes: row.codes,
+            qslmgr: row.qslmgr,
+            email: row.email,
+            url: row.url,
+            u_views: row.u_views,
+            bio: row.bio,
+            biodate: row.biodate,
+            image: row.image,
+            imageinfo: row.imageinfo,
+            serial: row.serial,
+            moddate: row.moddate,
+            msa: row.msa,
+            area_code: row.area_code,
+            time_zone: row.time_zone,
+            gmt_offset: row.gmt_offset,
+            dst: row.dst,
+            eqsl: row.eqsl,
+            mqsl: row.mqsl,
+            cqzone: row.cqzone,
+            ituzone: row.ituzone,
+            born: row.born,
+            user: row.user,
+            lotw: row.lotw,
+            iota: row.iota,
+            geoloc: row.geoloc,
+            attn: row.attn,
+            nickname: row.nickname,
+            name_fmt: row.name_fmt,
+            queried_callsign: row.queried_callsign,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&DxccInfo> for DxccRow {
+    fn from(dxcc: &DxccInfo) -> Self {
+        Self {
+            dxcc: dxcc.dxcc,
+            cc: dxcc.cc.clone(),
+            ccc: dxcc.ccc.clone(),
+            name: dxcc.name.clone(),
+            continent: dxcc.continent.clone(),
+            ituzone: dxcc.ituzone,
+            cqzone: dxcc.cqzone,
+            timezone: dxcc.timezone.clone(),
+            lat: dxcc.lat,
+            lon: dxcc.lon,
+            notes: dxcc.notes.clone(),
+        }
+    }
+}
+
+impl From<DxccRow> for DxccInfo {
+    fn from(row: DxccRow) -> Self {
+        Self {
+            dxcc: row.dxcc,
+            cc: row.cc,
+            ccc: row.ccc,
+            name: row.name,
+            continent: row.continent,
+            ituzone: row.ituzone,
+            cqzone: row.cqzone,
+            timezone: row.timezone,
+            lat: row.lat,
+            lon: row.lon,
+            notes: row.notes,
+            ..Default::default()
+        }
+    }
+}
+
+/// Write callsign records as CSV, with a header row even if there are none
+pub fn write_callsigns<'a, W, I>(writer: W, records: I) -> Result<()>
+where
+    W: io::Write,
+    I: IntoIterator<Item = &'a CallsignInfo>,
+{
+    write_rows(
+        writer,
+        CALLSIGN_COLUMNS,
+        records.into_iter().map(CallsignRow::from),
+    )
+}
+
+/// Write DXCC entities as CSV, with a header row even if there are none
+pub fn write_dxcc<'a, W, I>(writer: W, entities: I) -> Result<()>
+where
+    W: io::Write,
+    I: IntoIterator<Item = &'a DxccInfo>,
+{
+    write_rows(
+        writer,
+        DXCC_COLUMNS,
+        entities.into_iter().map(DxccRow::from),
+    )
+}
+
+/// Read callsign records written by [`write_callsigns`]
+pub fn read_callsigns<R: io::Read>(reader: R) -> Result<Vec<CallsignInfo>> {
+    ::csv::Reader::from_reader(reader)
+        .deserialize::<CallsignRow>()
+        .map(|row| row.map(CallsignInfo::from).map_err(csv_error))
+        .collect()
+}
+
+fn write_rows<W, R>(writer: W, columns: &[&str], rows: impl Iterator<Item = R>) -> Result<()>
+where
+    W: io::Write,
+    R: Serialize,
+{
+    let mut csv = ::csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(writer);
+    csv.write_record(columns).map_err(csv_error)?;
+    for row in rows {
+        csv.serialize(row).map_err(csv_error)?;
+    }
+    csv.flush()
+        .map_err(|error| QrzXmlError::csv(error.to_string()))
+}
+
+fn csv_error(error: ::csv::Error) -> QrzXmlError {
+    QrzXmlError::csv(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_with_stable_header() {
+        let info = CallsignInfo {
+            call: "W1AW".to_string(),
+            fname: Some("Hiram, Percy".to_string()),
+            name: Some("Maxim".to_string()),
+            dxcc: Some(291),
+            lat: Some(41.714775),
+            ..Default::default()
+        };
+
+        let mut out = Vec::new();
+        write_callsigns(&mut out, [&info]).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next().unwrap(), CALLSIGN_COLUMNS.join(","));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("W1AW,,,291,\"Hiram, Percy\",Maxim,"));
+
+        let read = read_callsigns(text.as_bytes()).unwrap();
+        assert_eq!(CallsignRow::from(&read[0]), CallsignRow::from(&info));
+    }
+
+    #[test]
+    fn test_columns_match_rows() {
+        fn serde_header<R: Serialize>(row: R) -> String {
+            let mut csv = ::csv::Writer::from_writer(Vec::new());
+            csv.serialize(row).unwrap();
+            let text = String::from_utf8(csv.into_inner().unwrap()).unwrap();
+            text.lines().next().unwrap().to_string()
+        }
+        assert_eq!(
+            serde_header(CallsignRow::default()),
+            CALLSIGN_COLUMNS.join(",")
+        );
+        assert_eq!(serde_header(DxccRow::default()), DXCC_COLUMNS.join(","));
+
+        let mut out = Vec::new();
+        write_dxcc(&mut out, []).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            DXCC_COLUMNS.join(",") + "\n"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Heuristics for grouping callsign lookups that belong to the same operator.
+//!
+//! Club membership lists are usually built from callsigns, and a single operator
+//! can easily appear several times (a vanity call, a previous call, a club call
+//! they hold the license for). The helpers here group such records together so
+//! they can be deduplicated before reporting.
+
+use crate::types::CallsignInfo;
+
+/// A set of lookups that appear to belong to the same operator
+#[derive(Debug, Clone)]
+pub struct OperatorGroup<'a> {
+    /// The records in this group, in the order they were supplied
+    pub records: Vec<&'a CallsignInfo>,
+}
+
+impl<'a> OperatorGroup<'a> {
+    /// Callsigns of all records in this group
+    pub fn callsigns(&self) -> Vec<&'a str> {
+        self.records.iter().map(|r| r.call.as_str()).collect()
+    }
+
+    /// Check if this group contains more than one record
+    pub fn is_duplicate(&self) -> bool {
+        self.records.len() > 1
+    }
+}
+
+/// Group lookups that likely belong to the same operator.
+///
+/// Two records are linked when either of them references the other through
+/// `aliases`, `xref` or `p_call`, or when they share a full name together with
+/// either the same street address or the same grid square. Links are transitive,
+/// so alias chains collapse into a single group.
+///
+/// Every input record appears in exactly one group; groups are returned in the
+/// order of their first record.
+pub fn group_by_operator(records: &[CallsignInfo]) -> Vec<OperatorGroup<'_>> {
+    let mut parent: Vec<usize> = (0..records.len()).collect();
+
+    for i in 0..records.len() {
+        for j in (i + 1)..records.len() {
+            if likely_same_operator(&records[i], &records[j]) {
+                union(&mut parent, i, j);
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, OperatorGroup<'_>)> = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        let root = find(&mut parent, index);
+        match groups.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, group)) => group.records.push(record),
+            None => groups.push((
+                root,
+                OperatorGroup {
+                    records: vec![record],
+                },
+            )),
+        }
+    }
+
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Return only the groups that contain more than one record
+pub fn find_duplicates(records: &[CallsignInfo]) -> Vec<OperatorGroup<'_>> {
+    group_by_operator(records)
+        .into_iter()
+        .filter(OperatorGroup::is_duplicate)
+        .collect()
+}
+
+/// Check whether two records are likely the same operator
+pub fn likely_same_operator(a: &CallsignInfo, b: &CallsignInfo) -> bool {
+    if references(a, &b.call) || references(b, &a.call) {
+        return true;
+    }
+
+    let same_name = match (normalized_name(a), normalized_name(b)) {
+        (Some(x), Some(y)) => x == y,
+        _ => false,
+    };
+    if !same_name {
+        return false;
+    }
+
+    let same_address = match (normalized_address(a), normalized_address(b)) {
+        (Some(x), Some(y)) => x == y,
+        _ => false,
+    };
+    let same_grid = match (&a.grid, &b.grid) {
+        (Some(x), Some(y)) => !x.is_empty() && x.eq_ignore_ascii_case(y),
+        _ => false,
+    };
+
+    same_address || same_grid
+}
+
+/// Check if `record` points at `call` through its alias/xref/previous-call fields
+fn references(record: &CallsignInfo, call: &str) -> bool {
+    let matches = |s: &str| s.trim().eq_ignore_ascii_case(call);
+
+    record
+        .aliases
+        .as_deref()
+        .map(|aliases| aliases.split(',').any(matches))
+        .unwrap_or(false)
+        || record.xref.as_deref().map(matches).unwrap_or(false)
+        || record.p_call.as_deref().map(matches).unwrap_or(false)
+}
+
+fn normalized_name(record: &CallsignInfo) -> Option<String> {
+    record
+        .full_name()
+        .map(|n| normalize(&n))
+        .filter(|n| !n.is_empty())
+}
+
+fn normalized_address(record: &CallsignInfo) -> Option<String> {
+    let addr1 = record.addr1.as_deref().map(normalize)?;
+    if addr1.is_empty() {
+        return None;
+    }
+    let zip = record.zip.as_deref().map(normalize).unwrap_or_default();
+    Some(format!("{}|{}", addr1, zip))
+}
+
+fn normalize(s: &str) -> String {
+    s.split_whitespace()
+        .map(|w| w.to_ascii_uppercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let ra = find(parent, a);
+    let rb = find(parent, b);
+    if ra != rb {
+        parent[ra.max(rb)] = ra.min(rb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(call: &str) -> CallsignInfo {
+        CallsignInfo {
+            call: call.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_alias_chain_grouping() {
+        let a = CallsignInfo {
+            aliases: Some("N6UFT".to_string()),
+            ..record("AA7BQ")
+        };
+        let b = CallsignInfo {
+            p_call: Some("KJ6RK".to_string()),
+            ..record("N6UFT")
+        };
+        let c = record("KJ6RK");
+        let d = record("W1AW");
+
+        let records = vec![a, b, c, d];
+        let groups = group_by_operator(&records);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].callsigns(), vec!["AA7BQ", "N6UFT", "KJ6RK"]);
+        assert_eq!(groups[1].callsigns(), vec!["W1AW"]);
+        assert_eq!(find_duplicates(&records).len(), 1);
+    }
+
+    #[test]
+    fn test_name_and_location_matching() {
+        let a = CallsignInfo {
+            fname: Some("Fred".to_string()),
+            name: Some("Lloyd".to_string()),
+            grid: Some("DM32af".to_string()),
+            ..record("AA7BQ")
+        };
+        let b = CallsignInfo {
+            fname: Some("FRED".to_string()),
+            name: Some("LLOYD".to_string()),
+            grid: Some("dm32AF".to_string()),
+            ..record("K7ABC")
+        };
+        let c = CallsignInfo {
+            fname: Some("Fred".to_string()),
+            name: Some("Lloyd".to_string()),
+            grid: Some("FN31pr".to_string()),
+            ..record("W1XYZ")
+        };
+
+        assert!(likely_same_operator(&a, &b));
+        assert!(!likely_same_operator(&a, &c));
+    }
+}
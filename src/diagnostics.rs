@@ -0,0 +1,159 @@
+//! Redacted diagnostics for bug reports.
+//!
+//! [`QrzXmlClient::diagnostics`](crate::QrzXmlClient::diagnostics) collects
+//! what is usually asked for first when something goes wrong: the effective
+//! configuration, the session and quota state, cache usage, the most recent
+//! lookup errors and the API version QRZ answered with. Credentials, session
+//! keys, extra header and query parameter values and query strings in error
+//! messages are left out, so the result can be attached to an issue as is.
+
+use crate::error::QrzXmlError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+/// Lookup errors kept for diagnostics
+const RECENT_ERRORS: usize = 20;
+
+/// Placeholder for values that are left out
+pub const REDACTED: &str = "<redacted>";
+
+/// A snapshot of the client's configuration and state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostics {
+    /// Version of this crate
+    pub crate_version: String,
+    /// API version the client requests
+    pub api_version: String,
+    /// API version reported in the most recent response
+    pub server_version: Option<String>,
+    /// Effective configuration, by setting name
+    pub config: BTreeMap<String, String>,
+    /// Session state
+    pub session: SessionDiagnostics,
+    /// Lookups used in the current 24-hour window
+    pub lookups_used: Option<u32>,
+    /// Callsign cache usage
+    pub callsign_cache: CacheStats,
+    /// DXCC cache usage
+    pub dxcc_cache: CacheStats,
+    /// Most recent lookup errors, oldest first
+    pub recent_errors: Vec<RecentError>,
+    /// Deviations found in responses parsed so far; see
+    /// [`ClientEvent::ParseWarnings`](crate::events::ClientEvent::ParseWarnings)
+    pub parse_warnings: u64,
+}
+
+/// Session state without the session key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionDiagnostics {
+    /// A session key is held
+    pub authenticated: bool,
+    /// When the session was established, in seconds since the Unix epoch
+    pub created_at: Option<u64>,
+    /// Lookup count reported with the session
+    pub count: Option<u32>,
+    /// Subscription expiration date or "non-subscriber"
+    pub sub_exp: Option<String>,
+    /// Server time of the last session update
+    pub gm_time: Option<String>,
+    /// Last informational message from QRZ
+    pub message: Option<String>,
+    /// Consecutive failed logins
+    pub failed_logins: u32,
+    /// Logins are locked out after QRZ rejected the credentials
+    pub login_locked_out: bool,
+    /// No requests are sent before this Unix time after QRZ refused connections
+    pub connection_refused_until: Option<u64>,
+}
+
+/// Number of entries in a cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// The cache is enabled
+    pub enabled: bool,
+    /// Entries held, including expired ones
+    pub entries: usize,
+    /// Entries held that have expired
+    pub expired: usize,
+}
+
+/// A failed lookup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentError {
+    /// When the lookup failed, in seconds since the Unix epoch
+    pub at: u64,
+    /// Kind of lookup (`callsign`, `biography`, `dxcc`)
+    pub kind: String,
+    /// The callsign or entity that was looked up
+    pub query: String,
+    /// Description of the failure
+    pub error: String,
+}
+
+/// Ring buffer of the most recent lookup errors
+#[derive(Debug, Default)]
+pub(crate) struct ErrorLog {
+    errors: Mutex<VecDeque<RecentError>>,
+}
+
+impl ErrorLog {
+    /// Record a failed lookup at `at`, in seconds since the Unix epoch
+    pub(crate) fn record(&self, kind: &str, query: &str, error: &QrzXmlError, at: u64) {
+        let mut errors = self.errors.lock().unwrap_or_else(|e| e.into_inner());
+        if errors.len() == RECENT_ERRORS {
+            errors.pop_front();
+        }
+        errors.push_back(RecentError {
+            at,
+            kind: kind.to_string(),
+            query: query.to_string(),
+            error: describe_error(error),
+        });
+    }
+
+    pub(crate) fn recent(&self) -> Vec<RecentError> {
+        self.errors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Describe an error without the query string of a failed request's URL,
+/// which carries the session key or credentials
+pub(crate) fn describe_error(error: &QrzXmlError) -> String {
+    let mut text = error.to_string();
+    if let QrzXmlError::Network(e) = error {
+        if let Some(url) = e.url() {
+            let mut bare = url.clone();
+            bare.set_query(None);
+            text = text.replace(url.as_str(), bare.as_str());
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_log_keeps_most_recent() {
+        let log = ErrorLog::default();
+        for i in 0..RECENT_ERRORS + 5 {
+            log.record(
+                "callsign",
+                &format!("K{}AB", i),
+                &QrzXmlError::callsign_not_found("X"),
+                i as u64,
+            );
+        }
+        let recent = log.recent();
+        assert_eq!(recent.len(), RECENT_ERRORS);
+        assert_eq!((recent[0].query.as_str(), recent[0].at), ("K5AB", 5));
+        assert_eq!(recent[0].error, "Callsign not found: X");
+    }
+}
@@ -1,5 +1,6 @@
 //! Error types for the QRZ client library.
 
+use std::time::Duration;
 use thiserror::Error;
 
 /// Result type alias for convenience
@@ -28,6 +29,14 @@ pub enum QrzXmlError {
     #[error("Authentication failed: {reason}")]
     AuthenticationFailed { reason: String },
 
+    /// Logins are disabled because QRZ rejected the credentials earlier
+    #[error("Login disabled after QRZ rejected the credentials: {reason}")]
+    LoginLockedOut { reason: String },
+
+    /// Logins are paused after several consecutive failures
+    #[error("Logins paused after repeated failures - retrying in {retry_after:?}")]
+    LoginCoolingDown { retry_after: Duration },
+
     /// Session expired or invalid
     #[error("Session expired or invalid - re-authentication required")]
     SessionExpired,
@@ -45,8 +54,10 @@ pub enum QrzXmlError {
     InvalidInput { message: String },
 
     /// QRZ service is refusing connections
-    #[error("QRZ service is refusing connections - try again in 24 hours")]
-    ConnectionRefused,
+    ///
+    /// `until` is when the client will contact QRZ again, in seconds since the Unix epoch.
+    #[error("QRZ service is refusing connections - not trying again before Unix time {until}")]
+    ConnectionRefused { until: u64 },
 
     /// Subscription required for this operation
     #[error("A subscription is required to access this data")]
@@ -56,6 +67,14 @@ pub enum QrzXmlError {
     #[error("Rate limit exceeded - too many requests")]
     RateLimitExceeded,
 
+    /// The configured daily lookup budget is used up
+    #[error("Daily lookup budget exhausted: {used} of {limit} lookups used")]
+    QuotaExceeded { used: u32, limit: u32 },
+
+    /// QRZ rejected the login because too many sessions are active for the account
+    #[error("Too many active QRZ sessions - backing off before logging in again")]
+    TooManySessions,
+
     /// No session key present in response
     #[error("No session key received - authentication may have failed")]
     NoSessionKey,
@@ -64,6 +83,22 @@ pub enum QrzXmlError {
     #[error("Invalid API version: {version}")]
     InvalidApiVersion { version: String },
 
+    /// A required environment variable is not set
+    #[error("Environment variable {name} is not set")]
+    MissingEnvVar { name: String },
+
+    /// Loading or saving a persisted session failed
+    #[error("Session store error: {message}")]
+    SessionStore { message: String },
+
+    /// Reading or writing credentials in the OS keyring failed
+    #[error("Keyring error: {message}")]
+    Keyring { message: String },
+
+    /// Reading or writing CSV failed
+    #[error("CSV error: {message}")]
+    Csv { message: String },
+
     /// Generic API error for unexpected responses
     #[error("Unexpected API response: {message}")]
     UnexpectedResponse { message: String },
@@ -84,6 +119,13 @@ impl QrzXmlError {
         }
     }
 
+    /// Create a new login lockout error
+    pub fn login_locked_out(reason: impl Into<String>) -> Self {
+        Self::LoginLockedOut {
+            reason: reason.into(),
+        }
+    }
+
     /// Create a new callsign not found error
     pub fn callsign_not_found(callsign: impl Into<String>) -> Self {
         Self::CallsignNotFound {
@@ -112,6 +154,32 @@ impl QrzXmlError {
         }
     }
 
+    /// Create a new missing environment variable error
+    pub fn missing_env_var(name: impl Into<String>) -> Self {
+        Self::MissingEnvVar { name: name.into() }
+    }
+
+    /// Create a new session store error
+    pub fn session_store(message: impl Into<String>) -> Self {
+        Self::SessionStore {
+            message: message.into(),
+        }
+    }
+
+    /// Create a new keyring error
+    pub fn keyring(message: impl Into<String>) -> Self {
+        Self::Keyring {
+            message: message.into(),
+        }
+    }
+
+    /// Create a new CSV error
+    pub fn csv(message: impl Into<String>) -> Self {
+        Self::Csv {
+            message: message.into(),
+        }
+    }
+
     /// Check if this error indicates we should retry with authentication
     pub fn should_reauthenticate(&self) -> bool {
         matches!(
@@ -124,7 +192,11 @@ impl QrzXmlError {
     pub fn is_retryable(&self) -> bool {
         matches!(
             self,
-            QrzXmlError::Network(_) | QrzXmlError::SessionExpired | QrzXmlError::RateLimitExceeded
+            QrzXmlError::Network(_)
+                | QrzXmlError::SessionExpired
+                | QrzXmlError::RateLimitExceeded
+                | QrzXmlError::TooManySessions
+                | QrzXmlError::LoginCoolingDown { .. }
         )
     }
 
@@ -132,7 +204,7 @@ impl QrzXmlError {
     pub fn is_permission_error(&self) -> bool {
         matches!(
             self,
-            QrzXmlError::SubscriptionRequired | QrzXmlError::ConnectionRefused
+            QrzXmlError::SubscriptionRequired | QrzXmlError::ConnectionRefused { .. }
         )
     }
 }
@@ -155,6 +227,12 @@ mod tests {
         assert!(QrzXmlError::SessionExpired.should_reauthenticate());
         assert!(QrzXmlError::RateLimitExceeded.is_retryable());
         assert!(QrzXmlError::SubscriptionRequired.is_permission_error());
+        assert!(!QrzXmlError::QuotaExceeded {
+            used: 100,
+            limit: 100
+        }
+        .is_retryable());
+        assert!(!QrzXmlError::login_locked_out("bad password").is_retryable());
         assert!(!QrzXmlError::CallsignNotFound {
             callsign: "TEST".to_string()
         }
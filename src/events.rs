@@ -0,0 +1,84 @@
+//! Typed events describing client state changes.
+//!
+//! Subscribe with [`QrzXmlClient::subscribe_events`](crate::QrzXmlClient::subscribe_events)
+//! to let dashboards and TUIs react to session and quota changes without polling.
+//! Events are delivered over a `tokio::sync::broadcast` channel, so slow receivers
+//! may observe [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError::Lagged)
+//! and skip older events.
+
+use crate::parse::ParseWarning;
+
+/// Number of events buffered per receiver before older events are dropped
+pub const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// An event emitted by the client
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientEvent {
+    /// A new session was established by logging in
+    SessionEstablished {
+        /// Lookups performed in the current 24-hour period
+        count: Option<u32>,
+        /// Subscription expiration date or "non-subscriber"
+        sub_exp: Option<String>,
+    },
+
+    /// The server reported the current session as expired or invalid
+    SessionExpired,
+
+    /// The daily lookup count reached a configured warning threshold
+    ///
+    /// Emitted once per threshold and 24-hour window.
+    QuotaWarning {
+        /// Lookups performed in the current 24-hour period
+        count: u32,
+        /// The threshold that was reached, as a lookup count
+        threshold: u32,
+    },
+
+    /// The daily lookup count dropped far enough to show QRZ started a new
+    /// 24-hour window
+    ///
+    /// Warning thresholds are armed again; see
+    /// [`QrzXmlClientConfig::resync_quota_on_rollover`](crate::client::QrzXmlClientConfig::resync_quota_on_rollover)
+    /// for how the client's own count is treated.
+    QuotaReset {
+        /// Count reported before the reset
+        previous: u32,
+        /// Count reported in the new window
+        count: u32,
+    },
+
+    /// QRZ sent an informational message, such as a pending subscription expiration
+    ///
+    /// Emitted when the message differs from the previous one.
+    ServerMessage {
+        /// The message text
+        message: String,
+    },
+
+    /// The server rejected a request for exceeding its rate limit
+    RateLimited,
+
+    /// A response deviated from the expected structure but was parsed leniently
+    ///
+    /// Emitted once per response, so warnings of concurrent lookups are not
+    /// mixed up. In strict mode such responses fail with
+    /// [`QrzXmlError::XmlParsing`](crate::QrzXmlError::XmlParsing) instead.
+    ParseWarnings {
+        /// Query parameters of the request, e.g. `callsign=AA7BQ`, without the
+        /// session key or login data; empty for logins
+        query: String,
+        /// The deviations found
+        warnings: Vec<ParseWarning>,
+    },
+
+    /// A lookup failed
+    LookupFailed {
+        /// Kind of lookup (`callsign`, `biography`, `dxcc`)
+        kind: &'static str,
+        /// The callsign or entity that was looked up
+        query: String,
+        /// Description of the failure
+        error: String,
+    },
+}
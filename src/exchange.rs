@@ -0,0 +1,145 @@
+//! Contest exchange plausibility checks.
+//!
+//! Logging programs can compare the state, province or zone a station sends in
+//! a contest exchange with what QRZ has on record. The check is deliberately
+//! lenient: missing data or portable operation never makes an exchange
+//! implausible, since the record cannot prove the operator wrong in that case.
+
+use crate::types::CallsignInfo;
+
+/// Outcome of checking a claimed exchange against a callsign record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExchangeValidation {
+    /// Callsign as worked, normalized to uppercase
+    pub callsign: String,
+    /// The claimed exchange, normalized to uppercase
+    pub claimed: String,
+    /// Whether the claimed exchange is consistent with the record
+    pub plausible: bool,
+    /// Human-readable explanations of the outcome
+    pub reasons: Vec<String>,
+}
+
+/// Check a claimed state, province or zone against a callsign record
+///
+/// Numeric exchanges are compared with both the CQ and ITU zone, anything else
+/// with the state field, which QRZ also uses for Canadian provinces. The worked
+/// callsign is needed because QRZ returns the home record for portable calls.
+pub fn validate_exchange(
+    worked_call: &str,
+    info: &CallsignInfo,
+    claimed: &str,
+) -> ExchangeValidation {
+    let callsign = worked_call.trim().to_uppercase();
+    let claimed = claimed.trim().to_uppercase();
+    let mut reasons = Vec::new();
+
+    let mut plausible = if claimed.is_empty() {
+        reasons.push("Exchange is empty".to_string());
+        false
+    } else if let Ok(zone) = claimed.parse::<u32>() {
+        check_zone(info, zone, &mut reasons)
+    } else {
+        check_state(info, &claimed, &mut reasons)
+    };
+
+    if !plausible && !claimed.is_empty() && callsign.contains('/') {
+        reasons.push("Portable operation, the record location may not apply".to_string());
+        plausible = true;
+    }
+
+    ExchangeValidation {
+        callsign,
+        claimed,
+        plausible,
+        reasons,
+    }
+}
+
+fn check_zone(info: &CallsignInfo, zone: u32, reasons: &mut Vec<String>) -> bool {
+    match (info.cqzone, info.ituzone) {
+        (None, None) => {
+            reasons.push("No zone on record".to_string());
+            true
+        }
+        (Some(cq), _) if cq == zone => {
+            reasons.push(format!("Matches CQ zone {}", cq));
+            true
+        }
+        (_, Some(itu)) if itu == zone => {
+            reasons.push(format!("Matches ITU zone {}", itu));
+            true
+        }
+        (cq, itu) => {
+            let on_record = [
+                cq.map(|z| format!("CQ zone {}", z)),
+                itu.map(|z| format!("ITU zone {}", z)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", ");
+            reasons.push(format!("Record lists {}", on_record));
+            false
+        }
+    }
+}
+
+fn check_state(info: &CallsignInfo, claimed: &str, reasons: &mut Vec<String>) -> bool {
+    match info.state.as_deref().map(str::trim) {
+        None | Some("") => {
+            reasons.push("No state on record".to_string());
+            true
+        }
+        Some(state) if state.eq_ignore_ascii_case(claimed) => {
+            reasons.push(format!("Matches state {}", state));
+            true
+        }
+        Some(state) => {
+            reasons.push(format!("Record lists state {}", state));
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(xml: &str) -> CallsignInfo {
+        quick_xml::de::from_str(xml).unwrap()
+    }
+
+    #[test]
+    fn test_validate_exchange() {
+        let info = record(
+            "<Callsign><call>AA7BQ</call><state>AZ</state><cqzone>3</cqzone><ituzone>6</ituzone></Callsign>",
+        );
+
+        assert!(validate_exchange("AA7BQ", &info, "az").plausible);
+        assert!(validate_exchange("AA7BQ", &info, "3").plausible);
+        assert!(validate_exchange("AA7BQ", &info, "6").plausible);
+        assert!(!validate_exchange("AA7BQ", &info, "").plausible);
+
+        let check = validate_exchange("AA7BQ", &info, "CA");
+        assert!(!check.plausible);
+        assert_eq!(check.reasons, vec!["Record lists state AZ"]);
+
+        let check = validate_exchange("AA7BQ", &info, "5");
+        assert!(!check.plausible);
+        assert_eq!(check.reasons, vec!["Record lists CQ zone 3, ITU zone 6"]);
+    }
+
+    #[test]
+    fn test_validate_exchange_lenient() {
+        let info = record("<Callsign><call>AA7BQ</call></Callsign>");
+        assert!(validate_exchange("AA7BQ", &info, "CA").plausible);
+        assert!(validate_exchange("AA7BQ", &info, "5").plausible);
+
+        let info = record("<Callsign><call>AA7BQ</call><state>AZ</state></Callsign>");
+        let check = validate_exchange("aa7bq/6", &info, "CA");
+        assert_eq!(check.callsign, "AA7BQ/6");
+        assert!(check.plausible);
+        assert_eq!(check.reasons.len(), 2);
+    }
+}
@@ -0,0 +1,152 @@
+//! Maidenhead and great-circle geodesy.
+//!
+//! Nearly every consumer of a lookup wants to know how far away the station is
+//! and which way to point the antenna. These functions convert between grid
+//! squares and coordinates and compute great-circle distance and initial
+//! bearing on a spherical Earth, which is well within the precision of a grid
+//! square or a QRZ record.
+
+use crate::error::{QrzXmlError, Result};
+use crate::grid::GridSquare;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Mean Earth radius used for great-circle calculations
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Kilometers per statute mile
+const KM_PER_MILE: f64 = 1.609344;
+
+/// A point given either as a grid square or as coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    /// Center of a grid square
+    Grid(GridSquare),
+    /// Latitude and longitude in degrees
+    Coordinates(f64, f64),
+}
+
+impl Location {
+    /// The point as (lat, lon)
+    pub fn coordinates(&self) -> (f64, f64) {
+        match self {
+            Location::Grid(grid) => grid.center(),
+            Location::Coordinates(lat, lon) => (*lat, *lon),
+        }
+    }
+}
+
+impl From<GridSquare> for Location {
+    fn from(grid: GridSquare) -> Self {
+        Location::Grid(grid)
+    }
+}
+
+impl From<(f64, f64)> for Location {
+    fn from((lat, lon): (f64, f64)) -> Self {
+        Location::Coordinates(lat, lon)
+    }
+}
+
+impl FromStr for Location {
+    type Err = QrzXmlError;
+
+    /// Parse a grid square such as `FN31pr`
+    fn from_str(s: &str) -> Result<Self> {
+        s.parse().map(Location::Grid)
+    }
+}
+
+/// Distance and direction from one point to another
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DistanceBearing {
+    /// Great-circle distance in kilometers
+    pub distance_km: f64,
+    /// Initial bearing in degrees (0-360, clockwise from north)
+    pub bearing: f64,
+}
+
+impl DistanceBearing {
+    /// Distance and bearing between two (lat, lon) points
+    pub fn between(from: (f64, f64), to: (f64, f64)) -> Self {
+        Self {
+            distance_km: distance_km(from, to),
+            bearing: bearing(from, to),
+        }
+    }
+
+    /// Great-circle distance in statute miles
+    pub fn distance_miles(&self) -> f64 {
+        self.distance_km / KM_PER_MILE
+    }
+
+    /// Bearing for the long path, opposite the short-path bearing
+    pub fn long_path_bearing(&self) -> f64 {
+        (self.bearing + 180.0).rem_euclid(360.0)
+    }
+}
+
+/// Great-circle distance in kilometers between two (lat, lon) points
+pub fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+}
+
+/// Initial great-circle bearing in degrees from one (lat, lon) point to another
+pub fn bearing(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+
+    let y = (lon2 - lon1).sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * (lon2 - lon1).cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Center of a 4-, 6- or 8-character Maidenhead grid square as (lat, lon)
+pub fn grid_to_coordinates(grid: &str) -> Option<(f64, f64)> {
+    grid.parse::<GridSquare>().ok().map(|grid| grid.center())
+}
+
+/// 6-character Maidenhead grid square containing the given coordinates
+pub fn coordinates_to_grid(lat: f64, lon: f64) -> Option<String> {
+    GridSquare::from_coordinates(lat, lon, 6).map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_conversion() {
+        let (lat, lon) = grid_to_coordinates("FN31pr").unwrap();
+        assert!((lat - 41.729).abs() < 0.01);
+        assert!((lon - (-72.708)).abs() < 0.01);
+        assert_eq!(coordinates_to_grid(lat, lon).as_deref(), Some("FN31pr"));
+
+        assert_eq!(grid_to_coordinates("FN31"), Some((41.5, -73.0)));
+        assert_eq!(grid_to_coordinates("ZZ99"), None);
+        assert_eq!(grid_to_coordinates("FN3"), None);
+    }
+
+    #[test]
+    fn test_distance_and_bearing() {
+        // W1AW to the Greenwich meridian, roughly
+        let newington = (41.714775, -72.727260);
+        let greenwich = (51.477928, -0.001545);
+
+        let path = DistanceBearing::between(newington, greenwich);
+        assert!((path.distance_km - 5434.0).abs() < 10.0, "{:?}", path);
+        assert!((path.distance_miles() - 3377.0).abs() < 10.0, "{:?}", path);
+        assert!((path.bearing - 52.2).abs() < 0.5, "{:?}", path);
+        assert!((path.long_path_bearing() - 232.2).abs() < 0.5, "{:?}", path);
+
+        let origin: Location = "io91wm".parse().unwrap();
+        assert_eq!(origin, Location::Grid("IO91wm".parse().unwrap()));
+        assert!(distance_km(origin.coordinates(), greenwich) < 10.0);
+        assert!("nowhere".parse::<Location>().is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Lookup concurrency governor driven by the daily lookup count.
+//!
+//! Unattended jobs (overnight log enrichment, club roster refreshes) should
+//! slow down on their own as the daily count approaches the subscription
+//! limit. The governor runs lookups at full concurrency up to a percentage of
+//! the ceiling, serializes them from there on and stops them at the ceiling.
+
+use crate::error::{QrzXmlError, Result};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Configuration of the concurrency governor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GovernorConfig {
+    /// Daily lookup count at which lookups stop
+    pub ceiling: u32,
+    /// Percentage of the ceiling up to which lookups run at full concurrency
+    pub full_speed_until_percent: u8,
+    /// Maximum concurrent lookups while running at full speed
+    pub max_concurrency: usize,
+}
+
+impl GovernorConfig {
+    /// Govern lookups against the given daily ceiling, serializing from 80%
+    pub fn new(ceiling: u32) -> Self {
+        Self {
+            ceiling,
+            full_speed_until_percent: 80,
+            max_concurrency: 8,
+        }
+    }
+
+    /// Phase the governor is in at the given daily lookup count
+    pub fn phase(&self, count: u32) -> GovernorPhase {
+        let serialize_at =
+            (u64::from(self.ceiling) * u64::from(self.full_speed_until_percent)).div_ceil(100);
+        if count >= self.ceiling {
+            GovernorPhase::Stopped
+        } else if u64::from(count) >= serialize_at {
+            GovernorPhase::Serialized
+        } else {
+            GovernorPhase::FullSpeed
+        }
+    }
+}
+
+/// How the governor currently admits lookups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GovernorPhase {
+    /// Up to `max_concurrency` lookups run at once
+    FullSpeed,
+    /// Lookups run one at a time
+    Serialized,
+    /// Lookups fail with [`QrzXmlError::QuotaExceeded`]
+    Stopped,
+}
+
+/// Permit held for the duration of a governed request
+#[derive(Debug)]
+pub(crate) struct GovernorPermit<'a> {
+    _permits: SemaphorePermit<'a>,
+}
+
+/// Runtime state of the governor
+///
+/// Full-speed lookups take one permit each; a serialized lookup takes all of
+/// them, so it waits for lookups still in flight and runs alone.
+#[derive(Debug)]
+pub(crate) struct Governor {
+    config: GovernorConfig,
+    permits: Semaphore,
+    total_permits: u32,
+}
+
+impl Governor {
+    pub(crate) fn new(config: GovernorConfig) -> Self {
+        let concurrency = config.max_concurrency.clamp(1, Semaphore::MAX_PERMITS);
+        let total_permits = u32::try_from(concurrency).unwrap_or(u32::MAX);
+        Self {
+            permits: Semaphore::new(total_permits as usize),
+            total_permits,
+            config,
+        }
+    }
+
+    pub(crate) fn phase(&self, count: u32) -> GovernorPhase {
+        self.config.phase(count)
+    }
+
+    /// Wait until a lookup may run at the given daily count
+    pub(crate) async fn acquire(&self, count: u32) -> Result<GovernorPermit<'_>> {
+        let wanted = match self.phase(count) {
+            GovernorPhase::FullSpeed => 1,
+            GovernorPhase::Serialized => self.total_permits,
+            GovernorPhase::Stopped => {
+                return Err(QrzXmlError::QuotaExceeded {
+                    used: count,
+                    limit: self.config.ceiling,
+                })
+            }
+        };
+        let permits = self
+            .permits
+            .acquire_many(wanted)
+            .await
+            .map_err(|_| QrzXmlError::unexpected_response("Governor closed"))?;
+        Ok(GovernorPermit { _permits: permits })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases() {
+        let config = GovernorConfig::new(100);
+        assert_eq!(config.phase(0), GovernorPhase::FullSpeed);
+        assert_eq!(config.phase(79), GovernorPhase::FullSpeed);
+        assert_eq!(config.phase(80), GovernorPhase::Serialized);
+        assert_eq!(config.phase(99), GovernorPhase::Serialized);
+        assert_eq!(config.phase(100), GovernorPhase::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_serialized_permits_are_exclusive() {
+        let governor = Governor::new(GovernorConfig::new(100));
+
+        let first = governor.acquire(90).await.unwrap();
+        assert_eq!(governor.permits.available_permits(), 0);
+        drop(first);
+        assert!(matches!(
+            governor.acquire(100).await,
+            Err(QrzXmlError::QuotaExceeded {
+                used: 100,
+                limit: 100
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_serialized_lookup_waits_for_full_speed_lookups() {
+        let governor = Governor::new(GovernorConfig::new(100));
+
+        let in_flight = governor.acquire(10).await.unwrap();
+        let mut serialized = tokio_test::task::spawn(governor.acquire(90));
+        tokio_test::assert_pending!(serialized.poll());
+
+        drop(in_flight);
+        assert!(serialized.is_woken());
+        let _serialized = tokio_test::assert_ready_ok!(serialized.poll());
+        assert!(governor.permits.try_acquire().is_err());
+    }
+}
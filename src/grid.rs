@@ -0,0 +1,204 @@
+//! Maidenhead grid squares.
+//!
+//! QRZ stores the grid square as free text, so records contain anything from
+//! `fn31` to `FN31PR` to junk. [`GridSquare`] holds a validated locator of 4, 6
+//! or 8 characters in the customary case (`FN31pr47`) and converts between
+//! locators and coordinates.
+
+use crate::error::{QrzXmlError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A validated Maidenhead locator of 4, 6 or 8 characters
+///
+/// Fields are kept in upper case and subsquares in lower case. Serializes as
+/// its string form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct GridSquare(String);
+
+/// Size of each pair of locator characters in degrees of (longitude, latitude),
+/// and the number of values a character of that pair can take
+const PAIRS: [((f64, f64), u8); 4] = [
+    ((20.0, 10.0), 18),
+    ((2.0, 1.0), 10),
+    ((2.0 / 24.0, 1.0 / 24.0), 24),
+    ((2.0 / 240.0, 1.0 / 240.0), 10),
+];
+
+impl GridSquare {
+    /// The locator of the given length (4, 6 or 8) containing the coordinates
+    pub fn from_coordinates(lat: f64, lon: f64, len: usize) -> Option<Self> {
+        if !matches!(len, 4 | 6 | 8)
+            || !(-90.0..90.0).contains(&lat)
+            || !(-180.0..180.0).contains(&lon)
+        {
+            return None;
+        }
+
+        let (mut lon, mut lat) = (lon + 180.0, lat + 90.0);
+        let mut grid = String::with_capacity(len);
+        for (pair, ((width, height), values)) in PAIRS.iter().take(len / 2).enumerate() {
+            let x = ((lon / width) as u8).min(values - 1);
+            let y = ((lat / height) as u8).min(values - 1);
+            lon -= f64::from(x) * width;
+            lat -= f64::from(y) * height;
+            grid.push(pair_char(pair, x));
+            grid.push(pair_char(pair, y));
+        }
+        Some(Self(grid))
+    }
+
+    /// The locator as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Number of characters: 4, 6 or 8
+    pub fn precision(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Center of the square as (lat, lon)
+    pub fn center(&self) -> (f64, f64) {
+        let ((south, west), (north, east)) = self.bounds();
+        ((south + north) / 2.0, (west + east) / 2.0)
+    }
+
+    /// South-west and north-east corners of the square as (lat, lon)
+    pub fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let bytes = self.0.as_bytes();
+        let (mut lon, mut lat) = (-180.0, -90.0);
+        let (mut width, mut height) = (360.0, 180.0);
+        for (pair, chars) in bytes.chunks(2).enumerate() {
+            let ((w, h), _) = PAIRS[pair];
+            lon += f64::from(char_value(pair, chars[0])) * w;
+            lat += f64::from(char_value(pair, chars[1])) * h;
+            (width, height) = (w, h);
+        }
+        ((lat, lon), (lat + height, lon + width))
+    }
+
+    /// The enclosing square of a lower precision, or `None` if `len` is not
+    /// 4, 6 or 8 or more than this square's precision
+    pub fn truncate(&self, len: usize) -> Option<Self> {
+        (matches!(len, 4 | 6 | 8) && len <= self.precision())
+            .then(|| Self(self.0[..len].to_string()))
+    }
+}
+
+fn pair_char(pair: usize, value: u8) -> char {
+    char::from(match pair {
+        0 => b'A' + value,
+        2 => b'a' + value,
+        _ => b'0' + value,
+    })
+}
+
+fn char_value(pair: usize, c: u8) -> u8 {
+    match pair {
+        0 => c - b'A',
+        2 => c - b'a',
+        _ => c - b'0',
+    }
+}
+
+impl FromStr for GridSquare {
+    type Err = QrzXmlError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim();
+        let invalid = || QrzXmlError::invalid_input(format!("Invalid grid square: {}", s));
+        if !matches!(trimmed.len(), 4 | 6 | 8) || !trimmed.is_ascii() {
+            return Err(invalid());
+        }
+
+        let mut grid = String::with_capacity(trimmed.len());
+        for (pair, chars) in trimmed.as_bytes().chunks(2).enumerate() {
+            for &c in chars {
+                let c = match pair {
+                    0 => c.to_ascii_uppercase(),
+                    2 => c.to_ascii_lowercase(),
+                    _ => c,
+                };
+                let valid = match pair {
+                    0 => (b'A'..=b'R').contains(&c),
+                    2 => (b'a'..=b'x').contains(&c),
+                    _ => c.is_ascii_digit(),
+                };
+                if !valid {
+                    return Err(invalid());
+                }
+                grid.push(char::from(c));
+            }
+        }
+        Ok(Self(grid))
+    }
+}
+
+impl TryFrom<String> for GridSquare {
+    type Error = QrzXmlError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<GridSquare> for String {
+    fn from(grid: GridSquare) -> Self {
+        grid.0
+    }
+}
+
+impl fmt::Display for GridSquare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_normalizes_case() {
+        let grid: GridSquare = " fn31PR47 ".parse().unwrap();
+        assert_eq!(grid.as_str(), "FN31pr47");
+        assert_eq!(grid.precision(), 8);
+        assert_eq!(grid.truncate(4).unwrap().as_str(), "FN31");
+        assert_eq!(grid.truncate(2), None);
+
+        for invalid in ["", "FN3", "FN31p", "ZZ99", "FN31zz", "FN31prAB", "FN31pr4"] {
+            assert!(invalid.parse::<GridSquare>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_coordinates_round_trip() {
+        let grid: GridSquare = "FN31".parse().unwrap();
+        assert_eq!(grid.center(), (41.5, -73.0));
+        assert_eq!(grid.bounds(), ((41.0, -74.0), (42.0, -72.0)));
+
+        let (lat, lon) = (41.714775, -72.727260);
+        for (len, expected) in [(4, "FN31"), (6, "FN31pr"), (8, "FN31pr21")] {
+            let grid = GridSquare::from_coordinates(lat, lon, len).unwrap();
+            assert_eq!(grid.as_str(), expected);
+            let (center_lat, center_lon) = grid.center();
+            assert_eq!(
+                GridSquare::from_coordinates(center_lat, center_lon, len),
+                Some(grid)
+            );
+        }
+        assert_eq!(GridSquare::from_coordinates(91.0, 0.0, 6), None);
+        assert_eq!(GridSquare::from_coordinates(0.0, 0.0, 5), None);
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        let grid: GridSquare = serde_json::from_str("\"dm32AF\"").unwrap();
+        assert_eq!(grid.as_str(), "DM32af");
+        assert_eq!(serde_json::to_string(&grid).unwrap(), "\"DM32af\"");
+        assert!(serde_json::from_str::<GridSquare>("\"nope\"").is_err());
+    }
+}
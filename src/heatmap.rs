@@ -0,0 +1,210 @@
+//! Grid square heatmaps of looked-up stations.
+//!
+//! Special event stations and DXpeditions like to show where they were worked
+//! from. [`bin_by_grid`] counts stations per Maidenhead field (`FN`) or square
+//! (`FN31`) and gives each cell its bounds, ready to be drawn as rectangles on a
+//! map with a color scale from zero to [`GridHeatmap::max_count`].
+
+use crate::geo::coordinates_to_grid;
+use crate::grid::GridSquare;
+use crate::station::Station;
+use crate::types::CallsignInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Size of the cells stations are binned into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridPrecision {
+    /// 2-character fields of 20° longitude by 10° latitude
+    Field,
+    /// 4-character squares of 2° longitude by 1° latitude
+    Square,
+}
+
+/// Fields a record must expose to be placed on a heatmap
+pub trait GridRecord {
+    /// Maidenhead grid square of the station
+    fn grid_square(&self) -> Option<String>;
+}
+
+impl GridRecord for CallsignInfo {
+    fn grid_square(&self) -> Option<String> {
+        // Fall back to the coordinates for records without a grid
+        self.grid.clone().or_else(|| {
+            self.coordinates()
+                .and_then(|(lat, lon)| coordinates_to_grid(lat, lon))
+        })
+    }
+}
+
+impl GridRecord for Station {
+    fn grid_square(&self) -> Option<String> {
+        self.grid.clone()
+    }
+}
+
+/// A heatmap cell and the number of stations in it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridCell {
+    /// Field or square, in upper case
+    pub grid: String,
+    /// Number of stations in the cell
+    pub count: usize,
+    /// South-west corner as (lat, lon)
+    pub south_west: (f64, f64),
+    /// North-east corner as (lat, lon)
+    pub north_east: (f64, f64),
+}
+
+impl GridCell {
+    /// Center of the cell as (lat, lon)
+    pub fn center(&self) -> (f64, f64) {
+        (
+            (self.south_west.0 + self.north_east.0) / 2.0,
+            (self.south_west.1 + self.north_east.1) / 2.0,
+        )
+    }
+}
+
+/// Station counts per grid field or square
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridHeatmap {
+    /// Size of the cells
+    pub precision: GridPrecision,
+    /// Total number of records binned
+    pub total: usize,
+    /// Records without a usable grid square or coordinates
+    pub unlocated: usize,
+    /// Cells with at least one station, ordered by grid
+    pub cells: Vec<GridCell>,
+}
+
+impl GridHeatmap {
+    /// Highest count of any cell, or zero for an empty heatmap
+    pub fn max_count(&self) -> usize {
+        self.cells.iter().map(|c| c.count).max().unwrap_or(0)
+    }
+
+    /// The cell for a field or square, if any station was in it
+    pub fn get(&self, grid: &str) -> Option<&GridCell> {
+        self.cells
+            .iter()
+            .find(|c| c.grid.eq_ignore_ascii_case(grid))
+    }
+}
+
+/// Count stations per grid field or square.
+///
+/// Grid squares of any precision are truncated to the requested cell size.
+/// Records without a valid grid square (see [`GridSquare`]) count towards
+/// `total` and `unlocated`.
+pub fn bin_by_grid<'a, R, I>(records: I, precision: GridPrecision) -> GridHeatmap
+where
+    R: GridRecord + 'a,
+    I: IntoIterator<Item = &'a R>,
+{
+    let mut total = 0;
+    let mut unlocated = 0;
+    let mut cells: BTreeMap<String, GridCell> = BTreeMap::new();
+
+    for record in records {
+        total += 1;
+        match record
+            .grid_square()
+            .and_then(|grid| cell_of(&grid, precision))
+        {
+            Some(cell) => cells.entry(cell.grid.clone()).or_insert(cell).count += 1,
+            None => unlocated += 1,
+        }
+    }
+    let cells = cells.into_values().collect();
+
+    GridHeatmap {
+        precision,
+        total,
+        unlocated,
+        cells,
+    }
+}
+
+/// The empty cell a valid grid square falls in
+fn cell_of(grid: &str, precision: GridPrecision) -> Option<GridCell> {
+    let square = grid.parse::<GridSquare>().ok()?.truncate(4)?;
+    let (grid, (south_west, north_east)) = match precision {
+        GridPrecision::Square => (square.to_string(), square.bounds()),
+        GridPrecision::Field => {
+            // A field spans squares 00 through 99, starting at the corner of 00
+            let field = &square.as_str()[..2];
+            let ((lat, lon), _) = format!("{}00", field).parse::<GridSquare>().ok()?.bounds();
+            (field.to_string(), ((lat, lon), (lat + 10.0, lon + 20.0)))
+        }
+    };
+    Some(GridCell {
+        grid,
+        count: 0,
+        south_west,
+        north_east,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(call: &str, grid: Option<&str>) -> CallsignInfo {
+        CallsignInfo {
+            call: call.to_string(),
+            grid: grid.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bin_by_grid() {
+        let records = vec![
+            record("W1AW", Some("FN31pr")),
+            record("K1ABC", Some("fn31")),
+            record("N1XYZ", Some("FN42")),
+            record("AA7BQ", Some("DM32af")),
+            record("X0XX", None),
+            record("X1XX", Some("ZZ99")),
+            CallsignInfo {
+                lat: Some(41.7),
+                lon: Some(-72.7),
+                ..record("W1NO", None)
+            },
+        ];
+
+        let squares = bin_by_grid(&records, GridPrecision::Square);
+        assert_eq!(squares.total, 7);
+        assert_eq!(squares.unlocated, 2);
+        assert_eq!(squares.max_count(), 3);
+        assert_eq!(squares.cells.len(), 3);
+        let fn31 = squares.get("fn31").unwrap();
+        assert_eq!(fn31.count, 3);
+        assert_eq!(fn31.south_west, (41.0, -74.0));
+        assert_eq!(fn31.north_east, (42.0, -72.0));
+        assert_eq!(fn31.center(), (41.5, -73.0));
+
+        let fields = bin_by_grid(&records, GridPrecision::Field);
+        let grids: Vec<(&str, usize)> = fields
+            .cells
+            .iter()
+            .map(|c| (c.grid.as_str(), c.count))
+            .collect();
+        assert_eq!(grids, vec![("DM", 1), ("FN", 4)]);
+        assert_eq!(fields.get("FN").unwrap().north_east, (50.0, -60.0));
+    }
+
+    #[test]
+    fn test_cells_follow_grid_square_validation() {
+        assert!(cell_of("FN31zz", GridPrecision::Square).is_none());
+        assert!(cell_of("FN3", GridPrecision::Field).is_none());
+        let cell = cell_of(" fn31pr ", GridPrecision::Field).unwrap();
+        assert_eq!(cell.grid, "FN");
+        assert_eq!(
+            (cell.south_west, cell.north_east),
+            ((40.0, -80.0), (50.0, -60.0))
+        );
+    }
+}
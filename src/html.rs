@@ -0,0 +1,123 @@
+//! HTML entity decoding.
+//!
+//! QRZ escapes text fields twice, so after XML parsing names and addresses
+//! still contain `&amp;`, `&#39;` or `&eacute;`. [`decode_entities`] turns
+//! them into the characters they stand for.
+
+use std::borrow::Cow;
+
+/// Named entities of HTML 4 for Latin-1 code points 160 to 255, in order
+const LATIN1: [&str; 96] = [
+    "nbsp", "iexcl", "cent", "pound", "curren", "yen", "brvbar", "sect", "uml", "copy", "ordf",
+    "laquo", "not", "shy", "reg", "macr", "deg", "plusmn", "sup2", "sup3", "acute", "micro",
+    "para", "middot", "cedil", "sup1", "ordm", "raquo", "frac14", "frac12", "frac34", "iquest",
+    "Agrave", "Aacute", "Acirc", "Atilde", "Auml", "Aring", "AElig", "Ccedil", "Egrave", "Eacute",
+    "Ecirc", "Euml", "Igrave", "Iacute", "Icirc", "Iuml", "ETH", "Ntilde", "Ograve", "Oacute",
+    "Ocirc", "Otilde", "Ouml", "times", "Oslash", "Ugrave", "Uacute", "Ucirc", "Uuml", "Yacute",
+    "THORN", "szlig", "agrave", "aacute", "acirc", "atilde", "auml", "aring", "aelig", "ccedil",
+    "egrave", "eacute", "ecirc", "euml", "igrave", "iacute", "icirc", "iuml", "eth", "ntilde",
+    "ograve", "oacute", "ocirc", "otilde", "ouml", "divide", "oslash", "ugrave", "uacute", "ucirc",
+    "uuml", "yacute", "thorn", "yuml",
+];
+
+/// Other named entities seen in QRZ records
+const OTHER: [(&str, char); 18] = [
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("OElig", 'Œ'),
+    ("oelig", 'œ'),
+    ("Scaron", 'Š'),
+    ("scaron", 'š'),
+    ("Yuml", 'Ÿ'),
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("hellip", '…'),
+    ("euro", '€'),
+];
+
+/// Longest entity name or numeric reference between `&` and `;`
+const MAX_ENTITY_LEN: usize = 8;
+
+/// Decode named and numeric HTML entities, leaving unknown ones as they are
+///
+/// Decodes a single level, so `&amp;amp;` becomes `&amp;`.
+pub(crate) fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .char_indices()
+            .take(MAX_ENTITY_LEN + 1)
+            .find(|&(_, c)| c == ';')
+            .and_then(|(end, _)| Some((end, entity_char(&rest[1..1 + end])?)));
+        match entity {
+            Some((end, c)) => {
+                decoded.push(c);
+                rest = &rest[end + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    Cow::Owned(decoded)
+}
+
+fn entity_char(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|c| *c != '\0');
+    }
+
+    if let Some(index) = LATIN1.iter().position(|entity| *entity == name) {
+        return char::from_u32(160 + index as u32);
+    }
+    OTHER
+        .iter()
+        .find(|(entity, _)| *entity == name)
+        .map(|(_, c)| *c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(decode_entities("Radio Club"), "Radio Club");
+        assert_eq!(decode_entities("AT&amp;T ARC"), "AT&T ARC");
+        assert_eq!(decode_entities("O&#39;Brien"), "O'Brien");
+        assert_eq!(
+            decode_entities("Jos&eacute; &#x00D1;u&ntilde;ez"),
+            "José Ñuñez"
+        );
+        assert_eq!(decode_entities("M&uuml;ller&nbsp;"), "Müller\u{a0}");
+        assert_eq!(decode_entities("&amp;amp;"), "&amp;");
+    }
+
+    #[test]
+    fn test_unknown_entities_kept() {
+        assert_eq!(decode_entities("Smith & Sons"), "Smith & Sons");
+        assert_eq!(decode_entities("&bogus; &#xZZ; &;"), "&bogus; &#xZZ; &;");
+        assert_eq!(decode_entities("trailing &"), "trailing &");
+        assert_eq!(decode_entities("&#0;"), "&#0;");
+    }
+}
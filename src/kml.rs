@@ -0,0 +1,120 @@
+//! KML export.
+//!
+//! [`CallsignInfo::to_kml_placemark`](crate::CallsignInfo::to_kml_placemark)
+//! and [`DxccInfo::to_kml_placemark`](crate::DxccInfo::to_kml_placemark)
+//! render a `<Placemark>` element for Google Earth and other KML viewers.
+//! Placemarks are fragments; wrap them in a document with [`document`].
+
+use crate::types::{CallsignInfo, DxccInfo};
+use quick_xml::escape::escape;
+
+/// Wrap placemarks in a KML document
+pub fn document<I, S>(name: &str, placemarks: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut kml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n\
+         <Document>\n<name>{}</name>\n",
+        escape(name)
+    );
+    for placemark in placemarks {
+        kml.push_str(placemark.as_ref());
+    }
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+pub(crate) fn callsign_placemark(info: &CallsignInfo) -> Option<String> {
+    let description = [
+        info.class
+            .as_deref()
+            .map(|class| format!("Class {}", class.trim())),
+        info.grid
+            .as_deref()
+            .map(|grid| format!("Grid {}", grid.trim())),
+        info.country
+            .as_deref()
+            .map(|country| country.trim().to_string()),
+    ];
+    Some(placemark(&info.call, description, info.coordinates()?))
+}
+
+pub(crate) fn dxcc_placemark(dxcc: &DxccInfo) -> Option<String> {
+    let description = [
+        Some(format!("DXCC {}", dxcc.dxcc)),
+        dxcc.continent
+            .as_deref()
+            .map(|continent| continent.trim().to_string()),
+        dxcc.cqzone.map(|zone| format!("CQ zone {}", zone)),
+        dxcc.ituzone.map(|zone| format!("ITU zone {}", zone)),
+    ];
+    Some(placemark(&dxcc.name, description, dxcc.coordinates()?))
+}
+
+/// A placemark with the non-empty description lines
+fn placemark<const N: usize>(
+    name: &str,
+    description: [Option<String>; N],
+    (lat, lon): (f64, f64),
+) -> String {
+    let description: Vec<String> = description
+        .into_iter()
+        .flatten()
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    format!(
+        "<Placemark>\n<name>{}</name>\n<description>{}</description>\n\
+         <Point><coordinates>{},{}</coordinates></Point>\n</Placemark>\n",
+        escape(name.trim()),
+        escape(description.join("\n").as_str()),
+        lon,
+        lat
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_callsign_placemark() {
+        let mut info = CallsignInfo {
+            call: "W1AW".to_string(),
+            class: Some("C".to_string()),
+            grid: Some("FN31pr".to_string()),
+            country: Some("United States".to_string()),
+            lat: Some(41.714775),
+            lon: Some(-72.72726),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.to_kml_placemark().unwrap(),
+            "<Placemark>\n<name>W1AW</name>\n\
+             <description>Class C\nGrid FN31pr\nUnited States</description>\n\
+             <Point><coordinates>-72.72726,41.714775</coordinates></Point>\n</Placemark>\n"
+        );
+
+        info.lon = None;
+        assert_eq!(info.to_kml_placemark(), None);
+    }
+
+    #[test]
+    fn test_dxcc_placemark_in_document() {
+        let dxcc = DxccInfo {
+            dxcc: 1,
+            name: "Trinidad & Tobago".to_string(),
+            lat: Some(10.6),
+            lon: Some(-61.2),
+            ..Default::default()
+        };
+        let kml = document("Worked <DXCC>", dxcc.to_kml_placemark());
+        assert!(kml.contains("<name>Worked &lt;DXCC&gt;</name>"));
+        assert!(kml.contains("<name>Trinidad &amp; Tobago</name>"));
+        assert!(kml.contains("<description>DXCC 1</description>"));
+        assert!(kml.ends_with("</Placemark>\n</Document>\n</kml>\n"));
+    }
+}
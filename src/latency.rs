@@ -0,0 +1,164 @@
+//! Per-endpoint latency histograms.
+//!
+//! The client records how long every lookup takes, keyed by lookup kind
+//! (`callsign`, `biography`, `dxcc`). Snapshots are available through
+//! [`QrzXmlClient::latency_histograms`](crate::QrzXmlClient::latency_histograms)
+//! and help diagnose intermittent QRZ slowness without an external metrics stack.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the histogram buckets in milliseconds; the last bucket is unbounded
+pub const BUCKET_BOUNDS_MS: [u64; 9] = [50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000];
+
+/// Latency distribution of a single lookup kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    counts: [u64; BUCKET_BOUNDS_MS.len() + 1],
+    total: Duration,
+    min: Option<Duration>,
+    max: Option<Duration>,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: [0; BUCKET_BOUNDS_MS.len() + 1],
+            total: Duration::ZERO,
+            min: None,
+            max: None,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Record one observation
+    pub fn record(&mut self, latency: Duration) {
+        let ms = latency.as_millis();
+        let index = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| ms <= u128::from(*bound))
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.counts[index] += 1;
+        self.total += latency;
+        self.min = Some(self.min.map_or(latency, |m| m.min(latency)));
+        self.max = Some(self.max.map_or(latency, |m| m.max(latency)));
+    }
+
+    /// Number of observations
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Mean latency, if anything was recorded
+    pub fn mean(&self) -> Option<Duration> {
+        match self.count() {
+            0 => None,
+            n => Some(Duration::from_nanos(
+                (self.total.as_nanos() / u128::from(n)) as u64,
+            )),
+        }
+    }
+
+    /// Fastest observation
+    pub fn min(&self) -> Option<Duration> {
+        self.min
+    }
+
+    /// Slowest observation
+    pub fn max(&self) -> Option<Duration> {
+        self.max
+    }
+
+    /// Bucket counts as `(upper bound in ms, count)`; the last bound is `None` (unbounded)
+    pub fn buckets(&self) -> Vec<(Option<u64>, u64)> {
+        BUCKET_BOUNDS_MS
+            .iter()
+            .map(|b| Some(*b))
+            .chain(std::iter::once(None))
+            .zip(self.counts.iter().copied())
+            .collect()
+    }
+
+    /// Approximate percentile (0.0 to 100.0) as the upper bound of the bucket containing it
+    ///
+    /// Observations in the unbounded bucket report the maximum seen.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * count as f64).ceil() as u64;
+        let rank = rank.max(1);
+        let mut seen = 0;
+        for (index, bucket) in self.counts.iter().enumerate() {
+            seen += bucket;
+            if seen >= rank {
+                return match BUCKET_BOUNDS_MS.get(index) {
+                    Some(bound) => Some(Duration::from_millis(*bound)),
+                    None => self.max,
+                };
+            }
+        }
+        self.max
+    }
+}
+
+/// Thread-safe collection of histograms keyed by lookup kind
+#[derive(Debug, Default)]
+pub(crate) struct LatencyTracker {
+    histograms: Mutex<BTreeMap<&'static str, LatencyHistogram>>,
+}
+
+impl LatencyTracker {
+    pub(crate) fn record(&self, kind: &'static str, latency: Duration) {
+        let mut histograms = self.histograms.lock().unwrap_or_else(|e| e.into_inner());
+        histograms.entry(kind).or_default().record(latency);
+    }
+
+    pub(crate) fn snapshot(&self) -> BTreeMap<String, LatencyHistogram> {
+        let histograms = self.histograms.lock().unwrap_or_else(|e| e.into_inner());
+        histograms
+            .iter()
+            .map(|(kind, histogram)| (kind.to_string(), histogram.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram() {
+        let mut histogram = LatencyHistogram::default();
+        assert_eq!(histogram.percentile(50.0), None);
+
+        for ms in [10, 20, 80, 300, 45_000] {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        assert_eq!(histogram.count(), 5);
+        assert_eq!(histogram.min(), Some(Duration::from_millis(10)));
+        assert_eq!(histogram.max(), Some(Duration::from_millis(45_000)));
+        assert_eq!(histogram.percentile(40.0), Some(Duration::from_millis(50)));
+        assert_eq!(histogram.percentile(60.0), Some(Duration::from_millis(100)));
+        assert_eq!(
+            histogram.percentile(100.0),
+            Some(Duration::from_millis(45_000))
+        );
+        assert_eq!(histogram.buckets()[0], (Some(50), 2));
+        assert_eq!(histogram.buckets().last(), Some(&(None, 1)));
+        assert_eq!(histogram.mean(), Some(Duration::from_millis(9_082)));
+    }
+
+    #[test]
+    fn test_mean_beyond_u32_observations() {
+        let mut histogram = LatencyHistogram::default();
+        histogram.counts[0] = u64::from(u32::MAX) + 1;
+        histogram.total = Duration::from_millis(10) * u32::MAX + Duration::from_millis(10);
+        assert_eq!(histogram.mean(), Some(Duration::from_millis(10)));
+    }
+}
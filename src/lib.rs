@@ -35,22 +35,153 @@
 //! }
 //! ```
 //!
+//! ## Cargo Features
+//!
+//! - `native-tls` *(default)*: TLS via the platform's native library
+//! - `rustls-tls`: TLS via rustls
+//! - `chrono` *(default)*: chrono re-exports, typed date/time accessors and the `roster` module
+//! - `offline-dxcc`: a bundled DXCC entity snapshot and prefix table for lookups without network access, see the `offline` module
+//! - `iso3166`: ISO 3166-1 country codes through `isocountry`, e.g. `DxccInfo::country_alpha2`
+//! - `sun`: sunrise, sunset and solar noon at a station, see the `sun` module
+//! - `tz`: station time zones and local time through `chrono-tz`, see the `timezone` module
+//! - `csv`: flat CSV rows and writers for records and DXCC entities, see the `csv` module
+//! - `geojson`: export records and DXCC entities as GeoJSON point features
+//! - `schemars`: JSON Schema for `CallsignInfo`, `DxccInfo` and `SessionInfo` through `schemars`
+//! - `kml`: export records and DXCC entities as KML placemarks, see the `kml` module
+//! - `vcard`: export records as vCard 4.0 with `CallsignInfo::to_vcard`
+//! - `tracing` *(default)*: log messages and spans through `tracing`
+//! - `http2`, `charset`, `system-proxy` *(default)*: the matching reqwest features
+//! - `metrics`, `otel`, `request-signing`: optional integrations, see their modules
+//! - `strict`: strict parsing and `QrzXmlClient::probe_api` for detecting QRZ schema drift
+//! - `keyring`: store QRZ credentials in the OS keyring and build clients from them
+//! - `shared-session`: file locking so several processes can share one session, see `FileSessionStore::with_locking`
+//! - `test-util`: `clock::MockClock` for testing backoff and expiry without real waits
+//! - `bench-internal`: deterministic parser and cache benchmarks, see the `bench` module
+//!
+//! For a minimal build, e.g. for embedded ARM loggers, disable default features
+//! and pick a TLS backend: `default-features = false, features = ["rustls-tls"]`.
+//!
 //! ## Authentication
 //!
 //! You need a valid QRZ.com username and password. While any QRZ user can authenticate,
 //! most features require an active QRZ Logbook Data subscription.
 
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "bench-internal")]
+pub mod bench;
+pub mod builder;
+mod cache;
+pub mod callsign;
+mod charset;
 pub mod client;
+pub mod clock;
+#[cfg(feature = "keyring")]
+pub mod credentials;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod dedup;
+pub mod diagnostics;
 pub mod error;
+pub mod events;
+pub mod exchange;
+pub mod geo;
+pub mod governor;
+pub mod grid;
+pub mod heatmap;
+mod html;
+#[cfg(feature = "kml")]
+pub mod kml;
+pub mod latency;
+pub mod license;
+pub mod merge;
+#[cfg(feature = "offline-dxcc")]
+pub mod offline;
+pub mod pacing;
+pub mod parse;
+pub mod pool;
+pub mod prefix;
+pub mod prelude;
+#[cfg(feature = "strict")]
+pub mod probe;
+pub mod query;
+pub mod quota;
+mod retry;
+#[cfg(feature = "chrono")]
+pub mod roster;
+pub mod session_store;
+#[cfg(feature = "request-signing")]
+pub mod signing;
+pub mod station;
+pub mod stream;
+pub mod subdivision;
+pub mod summary;
+#[cfg(feature = "sun")]
+pub mod sun;
+mod telemetry;
+#[cfg(feature = "tz")]
+pub mod timezone;
+pub mod transliterate;
 pub mod types;
+pub mod user_agent;
+pub mod validate;
+#[cfg(feature = "vcard")]
+mod vcard;
+pub mod worker;
+pub mod zone;
 
-pub use client::QrzXmlClient;
+pub use builder::{CallsignInfoBuilder, DxccInfoBuilder};
+pub use callsign::{Callsign, CallsignNormalization};
+pub use client::{IpFamily, LookupOptions, QrzXmlClient};
+pub use diagnostics::Diagnostics;
 pub use error::{QrzXmlError, Result};
-pub use types::{ApiVersion, BiographyData, CallsignInfo, DxccInfo, SessionInfo};
+pub use events::ClientEvent;
+pub use exchange::ExchangeValidation;
+pub use governor::{GovernorConfig, GovernorPhase};
+pub use grid::GridSquare;
+pub use license::LicenseCodes;
+pub use merge::CacheMergePolicy;
+pub use pacing::PacingConfig;
+pub use parse::{ParseMode, ParseWarning};
+pub use pool::{CredentialPool, RotationStrategy};
+pub use prefix::{DxccPrefixTable, PrefixMatch};
+#[cfg(feature = "strict")]
+pub use probe::ApiProbeReport;
+pub use query::{QueryParams, QueryResult};
+pub use quota::Quota;
+pub use session_store::{
+    FileSessionStore, MemorySessionStore, SessionLock, SessionSnapshot, SessionStore, StoredSession,
+};
+pub use station::Station;
+pub use stream::DxccEntityStream;
+pub use subdivision::{Subdivision, UsState};
+#[cfg(feature = "sun")]
+pub use sun::SunTimes;
+#[cfg(feature = "tz")]
+pub use timezone::StationTimeZone;
+pub use transliterate::Transliteration;
+pub use types::{
+    ApiVersion, BiographyData, BiographyOutcome, CallsignInfo, ClubInfo, Continent, DxccInfo,
+    GeoLocationSource, ImageInfo, LicenseStatus, QslCapabilities, QslFlag, SessionInfo,
+    SubscriptionStatus,
+};
+pub use user_agent::UserAgent;
+pub use worker::{LookupResult, LookupWorker};
+pub use zone::{CqZone, ItuZone};
 
 /// Re-export commonly used types from chrono for convenience
+#[cfg(feature = "chrono")]
 pub use chrono::{DateTime, Utc};
 
+/// Re-export the GeoJSON types returned by `to_geojson_feature`
+#[cfg(feature = "geojson")]
+pub use geojson::{Feature, FeatureCollection};
+
+/// Re-export the ISO 3166-1 country type returned by country code accessors
+#[cfg(feature = "iso3166")]
+pub use isocountry::CountryCode;
+
 /// The default base URL for QRZ's XML API
 pub const DEFAULT_BASE_URL: &str = "https://xmldata.qrz.com/xml";
 
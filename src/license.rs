@@ -0,0 +1,108 @@
+//! FCC license type codes.
+//!
+//! For US callsigns QRZ copies the FCC's license type into `codes`, e.g. `HAI`:
+//! the radio service (`HA` amateur, `HV` vanity) followed by the kind of
+//! licensee (`I` individual, `B` club, `M` military recreation, `R` RACES).
+//! [`LicenseCodes`] decodes those letters.
+
+use serde::{Deserialize, Serialize};
+
+/// FCC radio service of a license
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RadioService {
+    /// `HA`: a sequentially issued amateur callsign
+    Amateur,
+    /// `HV`: a vanity callsign
+    Vanity,
+}
+
+/// Kind of licensee an FCC amateur license was issued to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LicenseeType {
+    /// `I`: an individual operator
+    Individual,
+    /// `B`: an amateur radio club
+    Club,
+    /// `M`: a military recreation station
+    MilitaryRecreation,
+    /// `R`: a Radio Amateur Civil Emergency Service station
+    Races,
+    /// Any other licensee code
+    Other(char),
+}
+
+/// Decoded FCC license type codes
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LicenseCodes {
+    /// Radio service, if the codes start with a known one
+    pub service: Option<RadioService>,
+    /// Kind of licensee, if the codes carry one
+    pub licensee: Option<LicenseeType>,
+}
+
+impl LicenseCodes {
+    /// Decode a `codes` value such as `HAI` or `HVB`
+    ///
+    /// Returns `None` for empty values and values without a known radio
+    /// service.
+    pub fn parse(codes: &str) -> Option<Self> {
+        let codes = codes.trim().to_ascii_uppercase();
+        let service = match codes.get(..2)? {
+            "HA" => RadioService::Amateur,
+            "HV" => RadioService::Vanity,
+            _ => return None,
+        };
+        let licensee = codes[2..].chars().next().map(|code| match code {
+            'I' => LicenseeType::Individual,
+            'B' => LicenseeType::Club,
+            'M' => LicenseeType::MilitaryRecreation,
+            'R' => LicenseeType::Races,
+            other => LicenseeType::Other(other),
+        });
+        Some(Self {
+            service: Some(service),
+            licensee,
+        })
+    }
+
+    /// The license was issued to a club
+    pub fn is_club_license(&self) -> bool {
+        self.licensee == Some(LicenseeType::Club)
+    }
+
+    /// The license was issued to an individual
+    pub fn is_individual_license(&self) -> bool {
+        self.licensee == Some(LicenseeType::Individual)
+    }
+
+    /// The callsign is a vanity callsign
+    pub fn is_vanity(&self) -> bool {
+        self.service == Some(RadioService::Vanity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_license_codes() {
+        let codes = LicenseCodes::parse("HAI").unwrap();
+        assert_eq!(codes.service, Some(RadioService::Amateur));
+        assert!(codes.is_individual_license());
+        assert!(!codes.is_club_license());
+        assert!(!codes.is_vanity());
+
+        let codes = LicenseCodes::parse(" hvb ").unwrap();
+        assert!(codes.is_vanity());
+        assert!(codes.is_club_license());
+
+        assert_eq!(LicenseCodes::parse("HA").unwrap().licensee, None);
+        assert_eq!(
+            LicenseCodes::parse("HAX").unwrap().licensee,
+            Some(LicenseeType::Other('X'))
+        );
+        assert_eq!(LicenseCodes::parse(""), None);
+        assert_eq!(LicenseCodes::parse("ZZI"), None);
+    }
+}
@@ -0,0 +1,216 @@
+//! Merging refreshed callsign records with their cached predecessors.
+//!
+//! A refresh does not always return more than the cache already holds: when a
+//! subscription lapses QRZ leaves out most fields, so overwriting the cached
+//! record would throw good data away. A [`CacheMergePolicy`] decides what a
+//! refreshed record is combined with before it is returned and cached again.
+
+use crate::types::CallsignInfo;
+
+/// How a refreshed callsign record is combined with the expired cached one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMergePolicy {
+    /// Use the refreshed record as it is
+    #[default]
+    PreferFresh,
+    /// Keep the cached record if it has more fields than the refreshed one
+    PreferComplete,
+    /// Take each field from the refreshed record, falling back to the cached
+    /// value for fields the refresh left out
+    FieldLevel,
+}
+
+impl CacheMergePolicy {
+    /// Combine a refreshed record with the cached one for the same callsign
+    pub fn merge(self, cached: &CallsignInfo, fresh: CallsignInfo) -> CallsignInfo {
+        match self {
+            CacheMergePolicy::PreferFresh => fresh,
+            CacheMergePolicy::PreferComplete => {
+                if populated_fields(cached) > populated_fields(&fresh) {
+                    cached.clone()
+                } else {
+                    fresh
+                }
+            }
+            CacheMergePolicy::FieldLevel => fill_missing(fresh, cached),
+        }
+    }
+}
+
+/// Apply `$m!` to the name of every optional `CallsignInfo` field
+///
+/// The remaining fields, `call` and `extra`, are handled separately.
+macro_rules! optional_fields {
+    ($m:ident) => {
+        $m!(
+            xref,
+            aliases,
+            dxcc,
+            fname,
+            name,
+            addr1,
+            addr2,
+            state,
+            zip,
+            country,
+            ccode,
+            lat,
+            lon,
+            grid,
+            county,
+            fips,
+            land,
+            efdate,
+            expdate,
+            p_call,
+            class,
+            codes,
+            qslmgr,
+            email,
+            url,
+            u_views,
+            bio,
+            biodate,
+            image,
+            imageinfo,
+            serial,
+            moddate,
+            msa,
+            area_code,
+            time_zone,
+            gmt_offset,
+            dst,
+            eqsl,
+            mqsl,
+            cqzone,
+            ituzone,
+            born,
+            user,
+            lotw,
+            iota,
+            geoloc,
+            attn,
+            nickname,
+            name_fmt,
+            queried_callsign
+        )
+    };
+}
+
+/// Number of optional and extra fields that are set
+fn populated_fields(record: &CallsignInfo) -> usize {
+    macro_rules! count {
+        ($($field:ident),*) => {
+            0 $(+ usize::from(record.$field.is_some()))*
+        };
+    }
+    optional_fields!(count) + record.extra.len()
+}
+
+/// Fill the fields `record` lacks from `fallback`
+fn fill_missing(mut record: CallsignInfo, fallback: &CallsignInfo) -> CallsignInfo {
+    macro_rules! fill {
+        ($($field:ident),*) => {
+            $(
+                if record.$field.is_none() {
+                    record.$field = fallback.$field.clone();
+                }
+            )*
+        };
+    }
+    optional_fields!(fill);
+    // Unknown elements are merged one by one, like the known fields
+    for (name, value) in &fallback.extra {
+        record
+            .extra
+            .entry(name.clone())
+            .or_insert_with(|| value.clone());
+    }
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached() -> CallsignInfo {
+        CallsignInfo {
+            call: "AA7BQ".to_string(),
+            fname: Some("FRED L".to_string()),
+            name: Some("LLOYD".to_string()),
+            addr2: Some("Phoenix".to_string()),
+            grid: Some("DM32af".to_string()),
+            email: Some("flloyd@qrz.com".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn lapsed() -> CallsignInfo {
+        CallsignInfo {
+            call: "AA7BQ".to_string(),
+            fname: Some("FRED".to_string()),
+            name: Some("LLOYD".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_optional_fields_cover_every_field() {
+        // Fails to compile when a field is added to CallsignInfo but not to the list
+        macro_rules! destructure {
+            ($($field:ident),*) => {
+                let CallsignInfo {
+                    call: _,
+                    extra: _,
+                    $($field: _),*
+                } = cached();
+            };
+        }
+        optional_fields!(destructure);
+    }
+
+    #[test]
+    fn test_field_level_merges_extra_fields() {
+        let mut cached = cached();
+        cached
+            .extra
+            .insert("newfield".to_string(), "cached".to_string());
+        cached
+            .extra
+            .insert("other".to_string(), "cached".to_string());
+        let mut fresh = lapsed();
+        fresh
+            .extra
+            .insert("newfield".to_string(), "fresh".to_string());
+
+        let merged = CacheMergePolicy::FieldLevel.merge(&cached, fresh);
+        assert_eq!(merged.extra["newfield"], "fresh");
+        assert_eq!(merged.extra["other"], "cached");
+    }
+
+    #[test]
+    fn test_merge_policies() {
+        let fresh = CacheMergePolicy::PreferFresh.merge(&cached(), lapsed());
+        assert_eq!(fresh.fname.as_deref(), Some("FRED"));
+        assert_eq!(fresh.grid, None);
+
+        let complete = CacheMergePolicy::PreferComplete.merge(&cached(), lapsed());
+        assert_eq!(complete.fname.as_deref(), Some("FRED L"));
+        assert_eq!(complete.grid.as_deref(), Some("DM32af"));
+
+        let more = CallsignInfo {
+            lat: Some(34.0),
+            lon: Some(-112.0),
+            addr1: Some("1 Main St".to_string()),
+            email: None,
+            ..cached()
+        };
+        let complete = CacheMergePolicy::PreferComplete.merge(&cached(), more);
+        assert_eq!(complete.email, None);
+
+        let field_level = CacheMergePolicy::FieldLevel.merge(&cached(), lapsed());
+        assert_eq!(field_level.fname.as_deref(), Some("FRED"));
+        assert_eq!(field_level.grid.as_deref(), Some("DM32af"));
+        assert_eq!(field_level.email.as_deref(), Some("flloyd@qrz.com"));
+    }
+}
@@ -0,0 +1,516 @@
+//! Bundled DXCC entity table.
+//!
+//! Field-day laptops and portable loggers often run without a network
+//! connection. The `offline-dxcc` feature embeds a snapshot of the QRZ
+//! `dxcc=all` response taken on [`SNAPSHOT_DATE`], so entities can be looked
+//! up by number with no request at all. Entities added or changed since then
+//! are missing or stale; [`QrzXmlClient::dxcc_entity_offline`] prefers
+//! entities the client has fetched live and falls back to the snapshot.
+//!
+//! The snapshot carries no prefixes, so the module also bundles the ITU
+//! prefix allocations of each current entity as a [`DxccPrefixTable`].
+//! [`QrzXmlClient::dxcc_for_callsign_offline`] resolves most callsigns with it
+//! and only asks QRZ about prefixes shared by several entities, such as `VP8`
+//! or `KG4`.
+//!
+//! [`QrzXmlClient::dxcc_entity_offline`]: crate::QrzXmlClient::dxcc_entity_offline
+//! [`QrzXmlClient::dxcc_for_callsign_offline`]: crate::QrzXmlClient::dxcc_for_callsign_offline
+
+use crate::parse::{self, ParseMode};
+use crate::prefix::DxccPrefixTable;
+use crate::types::DxccInfo;
+use std::sync::LazyLock;
+
+/// Date the bundled snapshot was taken, as `YYYY-MM-DD`
+pub const SNAPSHOT_DATE: &str = "2026-10-10";
+
+static ENTITIES: LazyLock<Vec<DxccInfo>> = LazyLock::new(|| {
+    let (response, _) =
+        parse::parse_dxcc_list(include_str!("../fixtures/dxcc_all.xml"), ParseMode::Lenient)
+            .expect("bundled DXCC snapshot parses");
+    let mut entities = response.entities;
+    entities.sort_by_key(|dxcc| dxcc.dxcc);
+    entities
+});
+
+/// Every entity in the snapshot, ordered by entity number
+pub fn entities() -> &'static [DxccInfo] {
+    &ENTITIES
+}
+
+/// The snapshot of an entity, by entity number
+pub fn entity(dxcc: u32) -> Option<&'static DxccInfo> {
+    let entities = entities();
+    entities
+        .binary_search_by_key(&dxcc, |entity| entity.dxcc)
+        .ok()
+        .map(|index| &entities[index])
+}
+
+/// Prefixes of each entity, apart from the series in [`LETTER_SERIES`] and
+/// [`RUSSIAN_SERIES`]
+const PREFIXES: &[(u32, &str)] = &[
+    (
+        1,
+        "VA VB VC VD VE VG VO VX VY CF CG CH CI CJ CK CY CZ XJ XK XL XM XN XO",
+    ),
+    (3, "YA T6"),
+    (4, "3B6 3B7"),
+    (5, "OH0"),
+    (6, "AL KL NL WL"),
+    (7, "ZA"),
+    (9, "KH8 AH8 NH8 WH8"),
+    (11, "VU4"),
+    (12, "VP2E"),
+    (13, "CE9 KC4AAA KC4AAC KC4USB KC4USV"),
+    (14, "EK"),
+    (16, "ZL9"),
+    (17, "YV0"),
+    (18, "4J 4K"),
+    (20, "KH1 AH1 NH1 WH1"),
+    (21, "EA6 EB6 EC6 ED6 EE6 EF6 EG6 EH6"),
+    (22, "T8"),
+    (27, "EU EV EW"),
+    (29, "EA8 EB8 EC8 ED8 EE8 EF8 EG8 EH8"),
+    (31, "T31"),
+    (32, "EA9 EB9 EC9 ED9 EE9 EF9 EG9 EH9"),
+    (33, "VQ9"),
+    (34, "ZL7"),
+    (35, "VK9X"),
+    (37, "TI9"),
+    (38, "VK9C"),
+    (40, "SV9 SW9 SX9 SY9 SZ9 J49"),
+    (43, "KP5 NP5 WP5"),
+    (45, "SV5 SW5 SX5 SY5 SZ5 J45"),
+    (46, "9M6 9M8 9W6 9W8"),
+    (48, "T32"),
+    (49, "3C"),
+    (
+        50,
+        "XA XB XC XD XE XF XG XH XI 4A 4B 4C 6D 6E 6F 6G 6H 6I 6J",
+    ),
+    (51, "E3"),
+    (52, "ES"),
+    (53, "ET 9E 9F"),
+    (60, "C6"),
+    (61, "R1FJ RI1FJ"),
+    (62, "8P"),
+    (63, "FY"),
+    (64, "VP9"),
+    (65, "VP2V"),
+    (66, "V3"),
+    (69, "ZF"),
+    (70, "CL CM CO T4"),
+    (71, "HC8 HD8"),
+    (72, "HI"),
+    (74, "YS HU"),
+    (75, "4L"),
+    (76, "TG TD"),
+    (77, "J3"),
+    (78, "HH 4V"),
+    (79, "FG"),
+    (80, "HQ HR"),
+    (82, "6Y"),
+    (84, "FM"),
+    (86, "YN H6 H7 HT"),
+    (88, "HO HP H3 H8 H9 3E 3F"),
+    (89, "VP5 VQ5"),
+    (90, "9Y 9Z"),
+    (91, "P4"),
+    (94, "V2"),
+    (95, "J7"),
+    (96, "VP2M"),
+    (97, "J6"),
+    (98, "J8"),
+    (
+        100,
+        "AY AZ LO LP LQ LR LS LT LU LV LW L2 L3 L4 L5 L6 L7 L8 L9",
+    ),
+    (103, "KH2 AH2 NH2 WH2"),
+    (104, "CP"),
+    (106, "GU GP MU MP 2U"),
+    (107, "3X"),
+    (108, "PP PQ PR PS PT PU PV PW PX PY ZV ZW ZX ZY ZZ"),
+    (109, "J5"),
+    (110, "KH6 KH7 AH6 AH7 NH6 NH7 WH6 WH7"),
+    (112, "CA CB CC CD CE XQ XR 3G"),
+    (114, "GD GT MD MT 2D"),
+    (116, "HJ HK 5J 5K"),
+    (117, "4U1I"),
+    (118, "JX"),
+    (120, "HC HD"),
+    (122, "GJ GH MJ MH 2J"),
+    (123, "KH3 AH3 NH3 WH3"),
+    (129, "8R"),
+    (130, "UN UO UP UQ"),
+    (132, "ZP"),
+    (133, "ZL8"),
+    (135, "EX"),
+    (136, "OA OB OC 4T"),
+    (137, "HL DS DT D7 D8 D9 6K 6L 6M 6N"),
+    (140, "PZ"),
+    (142, "VU7"),
+    (143, "XW"),
+    (144, "CV CW CX"),
+    (145, "YL"),
+    (146, "LY"),
+    (147, "VK9L"),
+    (148, "YV YW YX YY 4M"),
+    (149, "CU"),
+    (150, "VK AX VH VI VJ VL VM VN VZ"),
+    (152, "XX9"),
+    (157, "C2"),
+    (158, "YJ"),
+    (159, "8Q"),
+    (160, "A3"),
+    (162, "FK"),
+    (163, "P2"),
+    (165, "3B8"),
+    (166, "KH0 AH0 NH0 WH0"),
+    (167, "OJ0"),
+    (168, "V7"),
+    (169, "FH"),
+    (170, "ZL ZK ZM"),
+    (171, "VK9M"),
+    (172, "VP6"),
+    (173, "V6"),
+    (174, "KH4 AH4 NH4 WH4"),
+    (176, "3D2"),
+    (179, "ER"),
+    (181, "C8 C9"),
+    (182, "KP1 NP1 WP1"),
+    (185, "H4"),
+    (187, "5U"),
+    (188, "E6"),
+    (189, "VK9N"),
+    (190, "5W"),
+    (195, "3C0"),
+    (197, "KH5 AH5 NH5 WH5"),
+    (201, "ZS8"),
+    (202, "KP3 KP4 NP3 NP4 WP3 WP4"),
+    (203, "C3"),
+    (204, "XF4 4A4 4B4 4C4 6D4 6E4 6F4 6G4 6H4 6I4 6J4"),
+    (205, "ZD8"),
+    (206, "OE"),
+    (207, "3B9"),
+    (209, "ON OO OP OQ OR OS OT"),
+    (211, "CY0"),
+    (212, "LZ"),
+    (213, "FS"),
+    (214, "TK"),
+    (215, "5B C4 H2 P3"),
+    (219, "S9"),
+    (221, "OU OV OZ 5P 5Q"),
+    (222, "OY"),
+    (223, "G M 2E"),
+    (224, "OF OG OH OI"),
+    (225, "IS0 IM0"),
+    (227, "F TM"),
+    (230, "DA DB DC DD DF DG DH DJ DK DL DM DN DO DP DQ DR"),
+    (232, "6O T5"),
+    (233, "ZB ZG"),
+    (236, "SV SW SX SY SZ J4"),
+    (237, "OX XP"),
+    (239, "HA HG"),
+    (242, "TF"),
+    (245, "EI EJ"),
+    (246, "1A"),
+    (248, "I"),
+    (249, "V4"),
+    (250, "ZD7"),
+    (251, "HB0"),
+    (252, "CY9"),
+    (254, "LX"),
+    (256, "CT3 CQ3 CR3 CS3"),
+    (257, "9H"),
+    (259, "JW"),
+    (260, "3A"),
+    (262, "EY"),
+    (263, "PA PB PC PD PE PF PG PH PI"),
+    (265, "GI GN MI MN 2I"),
+    (266, "LA LB LC LD LE LF LG LH LI LJ LK LL LM LN"),
+    (269, "SN SO SP SQ SR 3Z HF"),
+    (270, "ZK3"),
+    (272, "CQ CR CS CT"),
+    (274, "ZD9"),
+    (275, "YO YP YQ YR"),
+    (277, "FP"),
+    (278, "T7"),
+    (279, "GM GS MM MS 2M"),
+    (280, "EZ"),
+    (281, "EA EB EC ED EE EF EG EH AM AN AO"),
+    (282, "T2"),
+    (283, "ZC4"),
+    (284, "SA SB SC SD SE SF SG SH SI SJ SK SL SM 7S 8S"),
+    (285, "KP2 NP2 WP2"),
+    (286, "5X"),
+    (287, "HB HE"),
+    (288, "UR US UT UU UV UW UX UY UZ EM EN EO"),
+    (289, "4U1U"),
+    (291, "K N W AA AB AC AD AE AF AG AI AJ AK"),
+    (292, "UJ UK UL UM"),
+    (293, "XV 3W"),
+    (294, "GW GC MW MC 2W"),
+    (295, "HV"),
+    (296, "YT YU"),
+    (297, "KH9 AH9 NH9 WH9"),
+    (298, "FW"),
+    (299, "9M2 9M4 9W2 9W4"),
+    (301, "T30"),
+    (302, "S0"),
+    (303, "VK9W"),
+    (304, "A9"),
+    (305, "S2 S3"),
+    (306, "A5"),
+    (308, "TE TI"),
+    (309, "XZ"),
+    (312, "XU"),
+    (315, "4P 4Q 4R 4S"),
+    (318, "B XS 3H 3I 3J 3K 3L 3M 3N 3O 3P 3Q 3R 3S 3T 3U"),
+    (321, "VR"),
+    (324, "VU AT AU AV AW 8T 8U 8V 8W 8X 8Y"),
+    (
+        327,
+        "YB YC YD YE YF YG YH PK PL PM PN PO 7A 7B 7C 7D 7E 7F 7G 7H 7I 8A 8B",
+    ),
+    (327, "8C 8D 8E 8F 8G 8H 8I"),
+    (330, "EP EQ 9B 9C 9D"),
+    (333, "YI HN"),
+    (336, "4X 4Z"),
+    (
+        339,
+        "JA JE JF JG JH JI JJ JK JL JM JN JO JP JQ JR JS 7J 7K 7L 7M 7N 8J 8K 8L 8M 8N",
+    ),
+    (342, "JY"),
+    (344, "HM P5 P6 P7 P8 P9"),
+    (345, "V8"),
+    (348, "9K"),
+    (354, "OD"),
+    (363, "JT JU JV"),
+    (369, "9N"),
+    (370, "A4"),
+    (372, "AP AQ AR AS 6P 6Q 6R 6S"),
+    (375, "DU DV DW DX DY DZ 4D 4E 4F 4G 4H 4I"),
+    (376, "A7"),
+    (378, "HZ 7Z 8Z"),
+    (379, "S7"),
+    (381, "9V S6"),
+    (382, "J2"),
+    (384, "YK 6C"),
+    (386, "BM BN BO BP BQ BU BV BW BX"),
+    (387, "HS E2"),
+    (390, "TA TB TC YM"),
+    (391, "A6"),
+    (400, "7R 7T 7U 7V 7W 7X 7Y"),
+    (401, "D2 D3"),
+    (402, "A2 8O"),
+    (404, "9U"),
+    (406, "TJ"),
+    (408, "TL"),
+    (409, "D4"),
+    (410, "TT"),
+    (411, "D6"),
+    (412, "TN"),
+    (414, "9O 9P 9Q 9R 9S 9T"),
+    (416, "TY"),
+    (420, "TR"),
+    (422, "C5"),
+    (424, "9G"),
+    (428, "TU"),
+    (430, "5Y 5Z"),
+    (432, "7P"),
+    (434, "EL A8 D5 5L 5M 6Z"),
+    (436, "5A"),
+    (438, "5R 5S 6X"),
+    (440, "7Q"),
+    (442, "TZ"),
+    (444, "5T"),
+    (446, "CN 5C 5D 5E 5F 5G"),
+    (450, "5N 5O"),
+    (452, "Z2"),
+    (453, "FR"),
+    (454, "9X"),
+    (456, "6V 6W"),
+    (458, "9L"),
+    (462, "ZR ZS ZT ZU"),
+    (464, "V5"),
+    (466, "ST 6T 6U"),
+    (468, "3DA"),
+    (470, "5H 5I"),
+    (474, "3V TS"),
+    (478, "SU 6A 6B"),
+    (480, "XT"),
+    (482, "9I 9J"),
+    (483, "5V"),
+    (490, "T33"),
+    (492, "7O"),
+    (497, "9A"),
+    (499, "S5"),
+    (501, "E7 T9"),
+    (502, "Z3"),
+    (503, "OK OL"),
+    (504, "OM"),
+    (505, "BV9P"),
+    (506, "BS7"),
+    (507, "H40"),
+    (510, "E4"),
+    (511, "4W"),
+    (514, "4O"),
+    (516, "FJ"),
+    (517, "PJ2"),
+    (518, "PJ7"),
+    (519, "PJ5 PJ6"),
+    (520, "PJ4"),
+    (521, "Z8"),
+    (522, "Z6"),
+];
+
+/// Prefixes shared by several entities, which only a live lookup can settle
+const AMBIGUOUS: &[&str] = &[
+    "KG4", "KC4", "KH7K", "VP8", "VP6D", "VK0", "VK9", "FO", "FT", "TX", "4U", "3Y", "E5", "JD1",
+    "HK0", "HJ0", "5J0", "5K0", "3D2C", "3D2R", "CE0", "CA0", "CB0", "CC0", "CD0", "XQ0", "XR0",
+    "3G0", "PP0", "PQ0", "PR0", "PS0", "PT0", "PU0", "PV0", "PW0", "PX0", "PY0", "ZV0", "ZW0",
+    "ZX0", "ZY0", "ZZ0",
+];
+
+/// Series where the letter after the district digit picks the entity, e.g.
+/// `FT5W` for Crozet and `CE0Y` for Easter Island
+const LETTER_SERIES: &[(&str, &[(char, u32)])] = &[
+    (
+        "FT",
+        &[
+            ('E', 124),
+            ('G', 99),
+            ('J', 124),
+            ('T', 276),
+            ('W', 41),
+            ('X', 131),
+            ('Z', 10),
+        ],
+    ),
+    (
+        "CE CA CB CC CD XQ XR 3G",
+        &[('X', 217), ('Y', 47), ('Z', 125)],
+    ),
+    (
+        "PP PQ PR PS PT PU PV PW PX PY ZV ZW ZX ZY ZZ",
+        &[('F', 56), ('S', 253), ('T', 273)],
+    ),
+];
+
+/// Russian prefixes, whose call district digit picks European Russia,
+/// Kaliningrad or Asiatic Russia
+const RUSSIAN_SERIES: &str = "R UA UB UC UD UE UF UG UH UI";
+
+/// DXCC entity of a Russian call district
+fn russian_district(digit: u32) -> u32 {
+    match digit {
+        2 => 126,
+        8 | 9 | 0 => 15,
+        _ => 54,
+    }
+}
+
+static PREFIX_TABLE: LazyLock<DxccPrefixTable> = LazyLock::new(|| {
+    let mut table = DxccPrefixTable::new();
+    for (dxcc, prefixes) in PREFIXES {
+        for prefix in prefixes.split_whitespace() {
+            table.insert_prefix(prefix, *dxcc);
+        }
+    }
+
+    let mut series: Vec<String> = ('A'..='Z').map(|letter| format!("R{}", letter)).collect();
+    series.extend(RUSSIAN_SERIES.split_whitespace().map(String::from));
+    for base in &series {
+        for digit in 0..10 {
+            table.insert_prefix(&format!("{}{}", base, digit), russian_district(digit));
+        }
+    }
+
+    for prefix in AMBIGUOUS {
+        table.insert_ambiguous(prefix);
+    }
+    for (bases, letters) in LETTER_SERIES {
+        for base in bases.split_whitespace() {
+            for digit in 0..10 {
+                for (letter, dxcc) in *letters {
+                    table.insert_prefix(&format!("{}{}{}", base, digit, letter), *dxcc);
+                }
+            }
+        }
+    }
+    // Exceptions inside the Russian series
+    for prefix in ["R1FJ", "RI1FJ"] {
+        table.insert_prefix(prefix, 61);
+    }
+
+    for dxcc in entities() {
+        table.insert_entity(dxcc.clone());
+    }
+    table
+});
+
+/// Prefix table for the entities in the snapshot
+///
+/// Prefixes shared by several entities are marked
+/// [ambiguous](DxccPrefixTable::insert_ambiguous), so
+/// [`DxccPrefixTable::match_callsign`] reports when a callsign needs a live
+/// lookup. Entity details come from the snapshot.
+pub fn prefix_table() -> &'static DxccPrefixTable {
+    &PREFIX_TABLE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::PrefixMatch;
+
+    #[test]
+    fn test_snapshot_lookup() {
+        assert_eq!(entities().len(), 340);
+        assert_eq!(
+            entity(291).map(|dxcc| dxcc.name.as_str()),
+            Some("United States")
+        );
+        assert_eq!(entity(339).and_then(|dxcc| dxcc.cqzone), Some(25));
+        // Deleted and unassigned entity numbers
+        assert_eq!(entity(0), None);
+        assert_eq!(entity(2), None);
+        assert_eq!(entity(523), None);
+    }
+
+    #[test]
+    fn test_prefix_table() {
+        let table = prefix_table();
+        assert_eq!(table.match_callsign("JA1ABC"), PrefixMatch::Entity(339));
+        assert_eq!(table.match_callsign("W1AW"), PrefixMatch::Entity(291));
+        assert_eq!(table.match_callsign("KH6ABC"), PrefixMatch::Entity(110));
+        assert_eq!(table.match_callsign("G4ABC"), PrefixMatch::Entity(223));
+        assert_eq!(table.match_callsign("GM3ABC"), PrefixMatch::Entity(279));
+        assert_eq!(table.match_callsign("FT5WQ"), PrefixMatch::Entity(41));
+        assert_eq!(table.match_callsign("CE0YAB"), PrefixMatch::Entity(47));
+        assert_eq!(table.match_callsign("PY0FF"), PrefixMatch::Entity(56));
+        assert_eq!(table.match_callsign("UA9ABC"), PrefixMatch::Entity(15));
+        assert_eq!(table.match_callsign("RA2FA"), PrefixMatch::Entity(126));
+        assert_eq!(table.match_callsign("R3ABC"), PrefixMatch::Entity(54));
+        assert_eq!(table.match_callsign("RI1FJA"), PrefixMatch::Entity(61));
+        assert_eq!(table.match_callsign("KC4AAA"), PrefixMatch::Entity(13));
+        assert_eq!(table.match_callsign("VP8ABC"), PrefixMatch::Ambiguous);
+        assert_eq!(table.match_callsign("KG4AA"), PrefixMatch::Ambiguous);
+        assert_eq!(table.match_callsign("PY0ZZ"), PrefixMatch::Ambiguous);
+        assert_eq!(
+            table.resolve_info("JA1ABC").map(|dxcc| dxcc.name.as_str()),
+            Some("Japan")
+        );
+    }
+
+    #[test]
+    fn test_prefixes_name_snapshot_entities() {
+        let letter_series = LETTER_SERIES
+            .iter()
+            .flat_map(|(_, letters)| letters.iter().map(|(_, dxcc)| *dxcc));
+        for dxcc in PREFIXES.iter().map(|(dxcc, _)| *dxcc).chain(letter_series) {
+            assert!(entity(dxcc).is_some(), "entity {} not in snapshot", dxcc);
+        }
+    }
+}
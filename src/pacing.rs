@@ -0,0 +1,114 @@
+//! Request pacing driven by the daily lookup count.
+//!
+//! Long bulk jobs should not run at full speed until QRZ cuts them off
+//! mid-run. The pacer spaces lookups by an interval that grows with the
+//! `Count` QRZ reports: no delay (or a fixed base delay) early in the day,
+//! stretching linearly towards a maximum as the count approaches a ceiling.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Configuration of count-based request pacing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacingConfig {
+    /// Daily lookup count at which the interval reaches `max_interval_ms`
+    pub ceiling: u32,
+    /// Percentage of the ceiling from which the interval starts to grow
+    pub slow_down_from_percent: u8,
+    /// Interval between lookups below the slow-down point, in milliseconds
+    pub base_interval_ms: u64,
+    /// Interval between lookups at and above the ceiling, in milliseconds
+    pub max_interval_ms: u64,
+}
+
+impl PacingConfig {
+    /// Pace lookups against the given daily ceiling, slowing down from 50% to 10 seconds apart
+    pub fn new(ceiling: u32) -> Self {
+        Self {
+            ceiling,
+            slow_down_from_percent: 50,
+            base_interval_ms: 0,
+            max_interval_ms: 10_000,
+        }
+    }
+
+    /// Interval between lookups at the given daily lookup count
+    pub fn interval(&self, count: u32) -> Duration {
+        let ceiling = u64::from(self.ceiling);
+        let start = (ceiling * u64::from(self.slow_down_from_percent.min(100))).div_ceil(100);
+        let count = u64::from(count);
+        let base = self.base_interval_ms;
+        let max = self.max_interval_ms.max(base);
+
+        let millis = if count <= start {
+            base
+        } else if count >= ceiling {
+            max
+        } else {
+            base + (max - base) * (count - start) / (ceiling - start)
+        };
+        Duration::from_millis(millis)
+    }
+}
+
+/// Hands out start times for lookups, spaced by the interval at the current count
+#[derive(Debug)]
+pub(crate) struct Pacer {
+    config: PacingConfig,
+    last_slot: Mutex<Option<Instant>>,
+}
+
+impl Pacer {
+    pub(crate) fn new(config: PacingConfig) -> Self {
+        Self {
+            config,
+            last_slot: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn interval(&self, count: u32) -> Duration {
+        self.config.interval(count)
+    }
+
+    /// Reserve the next start time at `now`, returning how long to wait for it
+    pub(crate) fn reserve(&self, now: Instant, count: u32) -> Duration {
+        let mut last_slot = self.last_slot.lock().unwrap_or_else(|e| e.into_inner());
+        let start = last_slot.map_or(now, |slot| (slot + self.interval(count)).max(now));
+        *last_slot = Some(start);
+        start - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_grows_towards_ceiling() {
+        let config = PacingConfig {
+            base_interval_ms: 100,
+            max_interval_ms: 1100,
+            ..PacingConfig::new(1000)
+        };
+        assert_eq!(config.interval(0), Duration::from_millis(100));
+        assert_eq!(config.interval(500), Duration::from_millis(100));
+        assert_eq!(config.interval(750), Duration::from_millis(600));
+        assert_eq!(config.interval(1000), Duration::from_millis(1100));
+        assert_eq!(config.interval(5000), Duration::from_millis(1100));
+    }
+
+    #[test]
+    fn test_reserve_spaces_lookups() {
+        let pacer = Pacer::new(PacingConfig::new(100));
+        let now = Instant::now();
+
+        assert_eq!(pacer.reserve(now, 10), Duration::ZERO);
+        assert_eq!(pacer.reserve(now, 10), Duration::ZERO);
+        assert_eq!(pacer.reserve(now, 100), Duration::from_secs(10));
+        assert_eq!(pacer.reserve(now, 100), Duration::from_secs(20));
+        assert_eq!(
+            pacer.reserve(now + Duration::from_secs(60), 100),
+            Duration::ZERO
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Lenient and strict parsing of XML responses.
+//!
+//! QRZ responses are not always tidy: elements show up twice, new elements
+//! appear without notice and records occasionally lack an element the schema
+//! calls required. Library integrators usually want the lookup to go through
+//! anyway, while test suites want to know about every deviation. The
+//! [`ParseMode`] set in
+//! [`QrzXmlClientConfig::parse_mode`](crate::client::QrzXmlClientConfig::parse_mode)
+//! picks between the two: lenient parsing keeps the first of duplicated elements
+//! and records a [`ParseWarning`] for each deviation, strict parsing fails with
+//! [`QrzXmlError::XmlParsing`] naming the offending elements.
+
+use crate::error::{QrzXmlError, Result};
+use crate::types::{DxccInfo, DxccListResponse, QrzXmlResponse};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+
+/// Elements expected directly inside `QRZDatabase`
+const ROOT_ELEMENTS: [&str; 3] = ["Session", "Callsign", "DXCC"];
+/// Elements expected inside `Session`
+const SESSION_ELEMENTS: [&str; 7] = [
+    "Key", "Count", "SubExp", "GMTime", "Message", "Error", "Remark",
+];
+
+/// How deviations from the expected response structure are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Tolerate deviations and record them as warnings
+    #[default]
+    Lenient,
+    /// Fail on the first response that deviates
+    Strict,
+}
+
+/// Kind of deviation found in a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseWarningKind {
+    /// An element the crate does not model
+    Unexpected,
+    /// A required element that was absent or empty
+    Missing,
+    /// An element that appeared more than once; only the first is used
+    Duplicate,
+}
+
+impl ParseWarningKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ParseWarningKind::Unexpected => "unexpected",
+            ParseWarningKind::Missing => "missing",
+            ParseWarningKind::Duplicate => "duplicate",
+        }
+    }
+}
+
+/// A deviation from the expected response structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// What was wrong
+    pub kind: ParseWarningKind,
+    /// Path of the element below the document root, e.g. `Callsign.fname`
+    pub path: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} element {}", self.kind.as_str(), self.path)
+    }
+}
+
+/// A response parser, returning the parsed response and its deviations
+pub(crate) type Parser<T> = fn(&str, ParseMode) -> Result<(T, Vec<ParseWarning>)>;
+
+/// Parse a response in the given mode, returning the deviations found
+///
+/// In strict mode any deviation is an error, so the returned list is empty.
+pub fn parse_response(xml: &str, mode: ParseMode) -> Result<(QrzXmlResponse, Vec<ParseWarning>)> {
+    let (mut warnings, duplicates) = scan(xml, &[])?;
+    let response: QrzXmlResponse = deserialize(xml, &duplicates)?;
+
+    if let Some(callsign) = &response.callsign {
+        if callsign.call.trim().is_empty() {
+            warnings.push(warning(ParseWarningKind::Missing, "Callsign.call"));
+        }
+        warnings.extend(extra_warnings("Callsign", callsign.extra.keys()));
+    }
+    if let Some(dxcc) = &response.dxcc {
+        warnings.extend(dxcc_warnings(dxcc));
+    }
+    finish(response, warnings, mode)
+}
+
+/// Parse a `dxcc=all` response, which repeats the `DXCC` element once per entity
+///
+/// Deviations shared by many entities, such as a new element, are reported once.
+pub fn parse_dxcc_list(
+    xml: &str,
+    mode: ParseMode,
+) -> Result<(DxccListResponse, Vec<ParseWarning>)> {
+    let (mut warnings, duplicates) = scan(xml, &["DXCC"])?;
+    let response: DxccListResponse = deserialize(xml, &duplicates)?;
+
+    for warning in response.entities.iter().flat_map(dxcc_warnings) {
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+    finish(response, warnings, mode)
+}
+
+fn deserialize<T: DeserializeOwned>(xml: &str, duplicates: &[Range<usize>]) -> Result<T> {
+    Ok(if duplicates.is_empty() {
+        quick_xml::de::from_str(xml)?
+    } else {
+        quick_xml::de::from_str(&without_ranges(xml, duplicates))?
+    })
+}
+
+fn dxcc_warnings(dxcc: &DxccInfo) -> Vec<ParseWarning> {
+    let mut warnings = Vec::new();
+    if dxcc.dxcc == 0 {
+        warnings.push(warning(ParseWarningKind::Missing, "DXCC.dxcc"));
+    }
+    if dxcc.name.trim().is_empty() {
+        warnings.push(warning(ParseWarningKind::Missing, "DXCC.name"));
+    }
+    warnings.extend(extra_warnings("DXCC", dxcc.extra.keys()));
+    warnings
+}
+
+/// Fail in strict mode if there are any warnings
+fn finish<T>(
+    response: T,
+    warnings: Vec<ParseWarning>,
+    mode: ParseMode,
+) -> Result<(T, Vec<ParseWarning>)> {
+    if mode == ParseMode::Strict && !warnings.is_empty() {
+        let issues: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+        return Err(QrzXmlError::XmlParsing(quick_xml::DeError::Custom(
+            issues.join(", "),
+        )));
+    }
+    Ok((response, warnings))
+}
+
+/// Walk the document, reporting unexpected and duplicate elements and
+/// returning the byte ranges of the duplicates
+///
+/// Root elements named in `repeatable` may appear any number of times.
+fn scan(xml: &str, repeatable: &[&str]) -> Result<(Vec<ParseWarning>, Vec<Range<usize>>)> {
+    let mut reader = Reader::from_str(xml);
+    let mut warnings = Vec::new();
+    let mut duplicates = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut seen: Vec<HashSet<String>> = vec![HashSet::new()];
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let (element, is_empty) = match reader.read_event().map_err(quick_xml::DeError::from)? {
+            Event::Start(element) => (element, false),
+            Event::Empty(element) => (element, true),
+            Event::End(_) => {
+                path.pop();
+                seen.pop();
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+        let element_path = path
+            .iter()
+            .skip(1)
+            .chain(std::iter::once(&name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(".");
+
+        let children = seen.last_mut().expect("document level is never popped");
+        let repeated = path.len() == 1 && repeatable.contains(&name.as_str());
+        if !children.insert(name.clone()) && !repeated {
+            warnings.push(warning(ParseWarningKind::Duplicate, &element_path));
+            if !is_empty {
+                reader
+                    .read_to_end(element.name())
+                    .map_err(quick_xml::DeError::from)?;
+            }
+            duplicates.push(start..reader.buffer_position() as usize);
+            continue;
+        }
+
+        let expected = match path.len() {
+            1 => ROOT_ELEMENTS.contains(&name.as_str()),
+            2 if path[1] == "Session" => SESSION_ELEMENTS.contains(&name.as_str()),
+            _ => true,
+        };
+        if !expected {
+            warnings.push(warning(ParseWarningKind::Unexpected, &element_path));
+        }
+
+        if !is_empty {
+            path.push(name);
+            seen.push(HashSet::new());
+        }
+    }
+
+    Ok((warnings, duplicates))
+}
+
+fn without_ranges(xml: &str, ranges: &[Range<usize>]) -> String {
+    let mut kept = String::with_capacity(xml.len());
+    let mut from = 0;
+    for range in ranges {
+        kept.push_str(&xml[from..range.start]);
+        from = range.end;
+    }
+    kept.push_str(&xml[from..]);
+    kept
+}
+
+fn extra_warnings<'a>(
+    parent: &'a str,
+    names: impl Iterator<Item = &'a String>,
+) -> impl Iterator<Item = ParseWarning> + 'a {
+    let mut names: Vec<&String> = names.collect();
+    names.sort();
+    names.into_iter().map(move |name| {
+        warning(
+            ParseWarningKind::Unexpected,
+            &format!("{}.{}", parent, name),
+        )
+    })
+}
+
+fn warning(kind: ParseWarningKind, path: &str) -> ParseWarning {
+    ParseWarning {
+        kind,
+        path: path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = r#"<?xml version="1.0" ?>
+<QRZDatabase version="1.34">
+  <Callsign>
+    <call>AA7BQ</call>
+    <fname>FRED L</fname>
+    <fname>FREDDY</fname>
+    <shoesize>11</shoesize>
+  </Callsign>
+  <Session>
+    <Key>test_key</Key>
+    <Mood>good</Mood>
+  </Session>
+</QRZDatabase>"#;
+
+    #[test]
+    fn test_lenient_parse_records_warnings() {
+        let (response, warnings) = parse_response(MESSY, ParseMode::Lenient).unwrap();
+        let callsign = response.callsign.unwrap();
+        assert_eq!(callsign.fname.as_deref(), Some("FRED L"));
+        assert_eq!(response.session.key.as_deref(), Some("test_key"));
+
+        let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "duplicate element Callsign.fname",
+                "unexpected element Session.Mood",
+                "unexpected element Callsign.shoesize",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strict_parse_fails_with_paths() {
+        let err = parse_response(MESSY, ParseMode::Strict).unwrap_err();
+        assert!(matches!(err, QrzXmlError::XmlParsing(_)));
+        assert!(err.to_string().contains("duplicate element Callsign.fname"));
+        assert!(err.to_string().contains("Session.Mood"));
+
+        let missing = "<QRZDatabase><DXCC><dxcc>291</dxcc></DXCC><Session/></QRZDatabase>";
+        let err = parse_response(missing, ParseMode::Strict).unwrap_err();
+        assert!(err.to_string().contains("missing element DXCC.name"));
+        let (response, warnings) = parse_response(missing, ParseMode::Lenient).unwrap();
+        assert_eq!(response.dxcc.unwrap().dxcc, 291);
+        assert_eq!(warnings.len(), 1);
+
+        // Real responses carry a Session remark
+        let clean = include_str!("../fixtures/callsign.xml");
+        let (response, warnings) = parse_response(clean, ParseMode::Strict).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(response.session.remark.as_deref(), Some("cpu: 0.016s"));
+    }
+
+    #[test]
+    fn test_parse_dxcc_list() {
+        let xml = include_str!("../fixtures/dxcc_all.xml");
+        let (response, warnings) = parse_dxcc_list(xml, ParseMode::Strict).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(response.entities.len(), 340);
+        assert_eq!(response.session.count, Some(125));
+        let numbers: HashSet<u32> = response.entities.iter().map(|dxcc| dxcc.dxcc).collect();
+        assert_eq!(numbers.len(), 340);
+        let japan = response
+            .entities
+            .iter()
+            .find(|dxcc| dxcc.dxcc == 339)
+            .unwrap();
+        assert_eq!(japan.name, "Japan");
+        assert!(response
+            .entities
+            .iter()
+            .any(|dxcc| dxcc.name == "Trinidad & Tobago"));
+
+        // Deviations repeated across entities are reported once
+        let messy = "<QRZDatabase>\
+            <DXCC><dxcc>1</dxcc><name>Canada</name><flag>ca</flag></DXCC>\
+            <DXCC><dxcc>3</dxcc><name>Afghanistan</name><flag>af</flag><name>X</name></DXCC>\
+            <Session/></QRZDatabase>";
+        let (response, warnings) = parse_dxcc_list(messy, ParseMode::Lenient).unwrap();
+        assert_eq!(response.entities[1].name, "Afghanistan");
+        let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "duplicate element DXCC.name",
+                "unexpected element DXCC.flag"
+            ]
+        );
+    }
+}
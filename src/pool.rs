@@ -0,0 +1,187 @@
+//! Multi-account credential pools.
+//!
+//! Services holding several QRZ accounts, such as a club station or a shared
+//! logging server, can spread lookups over them with a [`CredentialPool`]. Each
+//! account is backed by its own [`QrzXmlClient`], so session state, quota
+//! tracking and events stay separate per account, while the pool picks which
+//! account serves each lookup and fails over when one is unusable.
+
+use crate::client::{QrzXmlClient, QrzXmlClientConfig};
+use crate::error::{QrzXmlError, Result};
+use crate::query::{QueryParams, QueryResult};
+use crate::quota::Quota;
+use crate::types::{ApiVersion, BiographyData, CallsignInfo, DxccInfo};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+type LookupFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// How a [`CredentialPool`] picks the account for a lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RotationStrategy {
+    /// Spread lookups evenly, starting each lookup with the next account
+    #[default]
+    RoundRobin,
+    /// Use one account until it fails, then move on to the next
+    Failover,
+}
+
+/// A set of QRZ accounts used as one logical client
+pub struct CredentialPool {
+    clients: Vec<QrzXmlClient>,
+    strategy: RotationStrategy,
+    /// Next account for round-robin, or the active account for failover
+    cursor: AtomicUsize,
+}
+
+impl CredentialPool {
+    /// Create a pool from `(username, password)` pairs sharing one configuration
+    pub fn new<U, P>(
+        accounts: impl IntoIterator<Item = (U, P)>,
+        api_version: ApiVersion,
+        config: QrzXmlClientConfig,
+        strategy: RotationStrategy,
+    ) -> Result<Self>
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        let clients = accounts
+            .into_iter()
+            .map(|(username, password)| {
+                QrzXmlClient::with_config(username, password, api_version.clone(), config.clone())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::from_clients(clients, strategy)
+    }
+
+    /// Create a pool from individually configured clients
+    pub fn from_clients(clients: Vec<QrzXmlClient>, strategy: RotationStrategy) -> Result<Self> {
+        if clients.is_empty() {
+            return Err(QrzXmlError::invalid_input(
+                "A credential pool needs at least one account",
+            ));
+        }
+
+        Ok(Self {
+            clients,
+            strategy,
+            cursor: AtomicUsize::new(0),
+        })
+    }
+
+    /// The clients backing the pool, one per account
+    pub fn clients(&self) -> &[QrzXmlClient] {
+        &self.clients
+    }
+
+    /// Lookup usage of every account, in pool order
+    pub fn quotas(&self) -> Vec<(&str, Quota)> {
+        self.clients
+            .iter()
+            .map(|client| (client.username(), client.quota()))
+            .collect()
+    }
+
+    /// Look up information for a callsign
+    pub async fn lookup_callsign(&self, callsign: &str) -> Result<CallsignInfo> {
+        self.run(|client| Box::pin(client.lookup_callsign(callsign)))
+            .await
+    }
+
+    /// Fetch biography/HTML data for a callsign
+    pub async fn lookup_biography(&self, callsign: &str) -> Result<BiographyData> {
+        self.run(|client| Box::pin(client.lookup_biography(callsign)))
+            .await
+    }
+
+    /// Look up DXCC entity by entity number
+    pub async fn lookup_dxcc_entity(&self, entity: u32) -> Result<DxccInfo> {
+        self.run(|client| Box::pin(client.lookup_dxcc_entity(entity)))
+            .await
+    }
+
+    /// Look up DXCC entity by callsign prefix matching
+    pub async fn lookup_dxcc_by_callsign(&self, callsign: &str) -> Result<DxccInfo> {
+        self.run(|client| Box::pin(client.lookup_dxcc_by_callsign(callsign)))
+            .await
+    }
+
+    /// Run an arbitrary QRZ query
+    pub async fn query(&self, params: &QueryParams) -> Result<QueryResult> {
+        self.run(|client| Box::pin(client.query(params))).await
+    }
+
+    /// Run a lookup on the accounts in rotation order until one can serve it
+    async fn run<'a, T, F>(&'a self, lookup: F) -> Result<T>
+    where
+        F: Fn(&'a QrzXmlClient) -> LookupFuture<'a, T>,
+    {
+        let count = self.clients.len();
+        let start = match self.strategy {
+            RotationStrategy::RoundRobin => self.cursor.fetch_add(1, Ordering::Relaxed),
+            RotationStrategy::Failover => self.cursor.load(Ordering::Relaxed),
+        } % count;
+
+        let mut last_error = None;
+        for offset in 0..count {
+            let index = (start + offset) % count;
+            let client = &self.clients[index];
+            match lookup(client).await {
+                Err(e) if should_fail_over(&e) => {
+                    warn!("Account {} cannot serve lookups: {}", client.username(), e);
+                    if self.strategy == RotationStrategy::Failover {
+                        let _ = self.cursor.compare_exchange(
+                            index,
+                            (index + 1) % count,
+                            Ordering::Relaxed,
+                            Ordering::Relaxed,
+                        );
+                    }
+                    last_error = Some(e);
+                }
+                other => return other,
+            }
+        }
+
+        Err(last_error.unwrap_or(QrzXmlError::NoSessionKey))
+    }
+}
+
+/// Errors tied to one account rather than to the lookup itself
+fn should_fail_over(error: &QrzXmlError) -> bool {
+    matches!(
+        error,
+        QrzXmlError::AuthenticationFailed { .. }
+            | QrzXmlError::LoginLockedOut { .. }
+            | QrzXmlError::LoginCoolingDown { .. }
+            | QrzXmlError::QuotaExceeded { .. }
+            | QrzXmlError::TooManySessions
+            | QrzXmlError::SubscriptionRequired
+            | QrzXmlError::ConnectionRefused { .. }
+            | QrzXmlError::SessionExpired
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_requires_accounts() {
+        let result = CredentialPool::new(
+            Vec::<(String, String)>::new(),
+            ApiVersion::Current,
+            QrzXmlClientConfig::default(),
+            RotationStrategy::RoundRobin,
+        );
+        assert!(matches!(result, Err(QrzXmlError::InvalidInput { .. })));
+    }
+
+    #[test]
+    fn test_fail_over_errors() {
+        assert!(should_fail_over(&QrzXmlError::TooManySessions));
+        assert!(!should_fail_over(&QrzXmlError::callsign_not_found("XX1XX")));
+    }
+}
@@ -0,0 +1,275 @@
+//! Offline DXCC prefix resolution.
+//!
+//! Enriching large volumes of spots (e.g. from the Reverse Beacon Network) one
+//! QRZ lookup at a time is far too slow. [`DxccPrefixTable`] compiles callsign
+//! prefixes into a trie and resolves a callsign to its DXCC entity by longest
+//! prefix match, with exact-callsign exceptions taking precedence, entirely
+//! without network calls. Prefixes shared by several entities can be marked
+//! ambiguous, so callers know when only a live lookup can settle a callsign.
+
+use crate::types::DxccInfo;
+use std::collections::HashMap;
+
+/// Suffixes that describe operating conditions rather than a location
+const OPERATING_SUFFIXES: [&str; 5] = ["P", "M", "QRP", "A", "LH"];
+
+/// Suffixes for stations that do not count for any DXCC entity
+const NO_ENTITY_SUFFIXES: [&str; 2] = ["MM", "AM"];
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Children sorted by byte for binary search
+    children: Vec<(u8, usize)>,
+    entity: Option<u32>,
+    ambiguous: bool,
+}
+
+/// Outcome of matching a callsign against a [`DxccPrefixTable`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixMatch {
+    /// The callsign belongs to a single entity
+    Entity(u32),
+    /// The longest matching prefix is shared by several entities
+    Ambiguous,
+    /// No prefix matches, or the station counts for no entity
+    Unmatched,
+}
+
+/// Compiled prefix trie mapping callsigns to DXCC entity numbers
+#[derive(Debug, Clone)]
+pub struct DxccPrefixTable {
+    nodes: Vec<Node>,
+    exceptions: HashMap<String, u32>,
+    entities: HashMap<u32, DxccInfo>,
+}
+
+impl Default for DxccPrefixTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DxccPrefixTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+            exceptions: HashMap::new(),
+            entities: HashMap::new(),
+        }
+    }
+
+    /// Map a callsign prefix to a DXCC entity, replacing any previous mapping
+    pub fn insert_prefix(&mut self, prefix: &str, dxcc: u32) {
+        let node = self.node_for(prefix);
+        self.nodes[node].entity = Some(dxcc);
+        self.nodes[node].ambiguous = false;
+    }
+
+    /// Mark a callsign prefix as shared by several entities, replacing any
+    /// previous mapping
+    ///
+    /// Callsigns whose longest matching prefix is ambiguous resolve to no
+    /// entity, e.g. `VP8` covers the Falklands and several South Atlantic
+    /// islands. Longer prefixes and exceptions still take precedence.
+    pub fn insert_ambiguous(&mut self, prefix: &str) {
+        let node = self.node_for(prefix);
+        self.nodes[node].entity = None;
+        self.nodes[node].ambiguous = true;
+    }
+
+    /// Index of the node for a prefix, created if missing
+    fn node_for(&mut self, prefix: &str) -> usize {
+        let mut node = 0;
+        for byte in normalize(prefix).bytes() {
+            node = match self.nodes[node]
+                .children
+                .binary_search_by_key(&byte, |(b, _)| *b)
+            {
+                Ok(index) => self.nodes[node].children[index].1,
+                Err(index) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.insert(index, (byte, child));
+                    child
+                }
+            };
+        }
+        node
+    }
+
+    /// Map a full callsign to a DXCC entity regardless of its prefix
+    ///
+    /// Exceptions cover stations whose prefix belongs to another entity, such as
+    /// KG4 calls with two-letter suffixes on Guantanamo Bay.
+    pub fn insert_exception(&mut self, callsign: &str, dxcc: u32) {
+        self.exceptions.insert(normalize(callsign), dxcc);
+    }
+
+    /// Attach entity details returned by [`resolve_info`](Self::resolve_info)
+    pub fn insert_entity(&mut self, info: DxccInfo) {
+        self.entities.insert(info.dxcc, info);
+    }
+
+    /// Number of prefixes, including ambiguous ones, and exceptions in the table
+    pub fn len(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|n| n.entity.is_some() || n.ambiguous)
+            .count()
+            + self.exceptions.len()
+    }
+
+    /// Check if the table has no prefixes or exceptions
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Resolve a callsign to a DXCC entity number
+    ///
+    /// Portable designators are honored: `W1AW/KH6` and `KH6/W1AW` resolve by
+    /// `KH6`, operating suffixes like `/P` are ignored, and maritime or
+    /// aeronautical mobile stations (`/MM`, `/AM`) resolve to no entity.
+    /// Callsigns under an [ambiguous](Self::insert_ambiguous) prefix resolve
+    /// to no entity as well; [`match_callsign`](Self::match_callsign) tells
+    /// the two cases apart.
+    pub fn resolve(&self, callsign: &str) -> Option<u32> {
+        match self.match_callsign(callsign) {
+            PrefixMatch::Entity(dxcc) => Some(dxcc),
+            PrefixMatch::Ambiguous | PrefixMatch::Unmatched => None,
+        }
+    }
+
+    /// Match a callsign against the table, reporting ambiguous prefixes
+    pub fn match_callsign(&self, callsign: &str) -> PrefixMatch {
+        let callsign = normalize(callsign);
+        if let Some(dxcc) = self.exceptions.get(&callsign) {
+            return PrefixMatch::Entity(*dxcc);
+        }
+
+        let Some(base) = location_part(&callsign) else {
+            return PrefixMatch::Unmatched;
+        };
+        if let Some(dxcc) = self.exceptions.get(base) {
+            return PrefixMatch::Entity(*dxcc);
+        }
+        self.longest_prefix(base)
+    }
+
+    /// Resolve a callsign to the entity details attached with [`insert_entity`](Self::insert_entity)
+    pub fn resolve_info(&self, callsign: &str) -> Option<&DxccInfo> {
+        self.resolve(callsign)
+            .and_then(|dxcc| self.entities.get(&dxcc))
+    }
+
+    fn longest_prefix(&self, callsign: &str) -> PrefixMatch {
+        let mut node = &self.nodes[0];
+        let mut best = PrefixMatch::Unmatched;
+        for byte in callsign.bytes() {
+            match node.children.binary_search_by_key(&byte, |(b, _)| *b) {
+                Ok(index) => node = &self.nodes[node.children[index].1],
+                Err(_) => break,
+            }
+            if let Some(dxcc) = node.entity {
+                best = PrefixMatch::Entity(dxcc);
+            } else if node.ambiguous {
+                best = PrefixMatch::Ambiguous;
+            }
+        }
+        best
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<(S, u32)> for DxccPrefixTable {
+    fn from_iter<T: IntoIterator<Item = (S, u32)>>(iter: T) -> Self {
+        let mut table = Self::new();
+        for (prefix, dxcc) in iter {
+            table.insert_prefix(prefix.as_ref(), dxcc);
+        }
+        table
+    }
+}
+
+fn normalize(callsign: &str) -> String {
+    callsign.trim().to_ascii_uppercase()
+}
+
+/// Pick the part of a possibly portable callsign that determines its location
+fn location_part(callsign: &str) -> Option<&str> {
+    let mut parts: Vec<&str> = callsign.split('/').filter(|p| !p.is_empty()).collect();
+
+    if let Some(last) = parts.last() {
+        if parts.len() > 1 && NO_ENTITY_SUFFIXES.contains(last) {
+            return None;
+        }
+    }
+    while parts.len() > 1 {
+        let last = parts[parts.len() - 1];
+        if OPERATING_SUFFIXES.contains(&last)
+            || (last.len() == 1 && last.as_bytes()[0].is_ascii_digit())
+        {
+            parts.pop();
+        } else {
+            break;
+        }
+    }
+
+    match parts.as_slice() {
+        [] => None,
+        [call] => Some(call),
+        // The shorter part is the location designator, e.g. KH6/W1AW or W1AW/KH6
+        [first, second, ..] => Some(if second.len() < first.len() {
+            second
+        } else {
+            first
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> DxccPrefixTable {
+        let mut table: DxccPrefixTable = [("K", 291), ("W", 291), ("KH6", 110), ("KG4", 105)]
+            .into_iter()
+            .collect();
+        table.insert_exception("KG4ABC", 291);
+        table
+    }
+
+    #[test]
+    fn test_longest_prefix_match() {
+        let table = table();
+        assert_eq!(table.resolve("w1aw"), Some(291));
+        assert_eq!(table.resolve("KH6ABC"), Some(110));
+        assert_eq!(table.resolve("KG4AA"), Some(105));
+        assert_eq!(table.resolve("KG4ABC"), Some(291));
+        assert_eq!(table.resolve("JA1ABC"), None);
+        assert_eq!(table.len(), 5);
+    }
+
+    #[test]
+    fn test_portable_designators() {
+        let table = table();
+        assert_eq!(table.resolve("W1AW/KH6"), Some(110));
+        assert_eq!(table.resolve("KH6/W1AW"), Some(110));
+        assert_eq!(table.resolve("KH6ABC/P"), Some(110));
+        assert_eq!(table.resolve("W1AW/MM"), None);
+    }
+
+    #[test]
+    fn test_ambiguous_prefixes() {
+        let mut table = table();
+        table.insert_prefix("VP8", 141);
+        table.insert_ambiguous("VP8");
+        table.insert_prefix("VP8F", 141);
+        assert_eq!(table.match_callsign("VP8ABC"), PrefixMatch::Ambiguous);
+        assert_eq!(table.resolve("VP8ABC"), None);
+        assert_eq!(table.match_callsign("VP8FAB"), PrefixMatch::Entity(141));
+        assert_eq!(table.match_callsign("KG4AA"), PrefixMatch::Entity(105));
+        assert_eq!(table.match_callsign("JA1ABC"), PrefixMatch::Unmatched);
+        assert_eq!(table.match_callsign("W1AW/AM"), PrefixMatch::Unmatched);
+        assert_eq!(table.len(), 7);
+    }
+}
@@ -0,0 +1,43 @@
+//! Convenience re-exports for applications.
+//!
+//! ```rust,no_run
+//! use qrz_xml::prelude::*;
+//!
+//! # async fn run() -> Result<()> {
+//! let config = QrzXmlClientConfig::default().with_user_agent(UserAgent::for_app("mylogger", "2.1"));
+//! let client = QrzXmlClient::with_config("username", "password", ApiVersion::Current, config)?;
+//! let station = client.station("AA7BQ").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::callsign::{Callsign, CallsignNormalization};
+pub use crate::client::{IpFamily, LookupOptions, QrzXmlClient, QrzXmlClientConfig};
+pub use crate::diagnostics::Diagnostics;
+pub use crate::error::{QrzXmlError, Result};
+pub use crate::events::ClientEvent;
+pub use crate::exchange::ExchangeValidation;
+pub use crate::governor::{GovernorConfig, GovernorPhase};
+pub use crate::grid::GridSquare;
+pub use crate::license::LicenseCodes;
+pub use crate::merge::CacheMergePolicy;
+pub use crate::pacing::PacingConfig;
+pub use crate::parse::{ParseMode, ParseWarning};
+pub use crate::pool::{CredentialPool, RotationStrategy};
+pub use crate::prefix::DxccPrefixTable;
+pub use crate::query::{QueryParams, QueryResult};
+pub use crate::quota::Quota;
+pub use crate::session_store::{
+    FileSessionStore, MemorySessionStore, SessionSnapshot, SessionStore, StoredSession,
+};
+pub use crate::station::{CoordinateSource, Station};
+pub use crate::subdivision::{Subdivision, UsState};
+pub use crate::transliterate::Transliteration;
+pub use crate::types::{
+    ApiVersion, BiographyData, BiographyOutcome, CallsignInfo, Continent, DxccInfo,
+    GeoLocationSource, ImageInfo, LicenseStatus, QslCapabilities, QslFlag, SessionInfo,
+    SubscriptionStatus,
+};
+pub use crate::user_agent::UserAgent;
+pub use crate::worker::{LookupResult, LookupWorker};
+pub use crate::zone::{CqZone, ItuZone};
@@ -0,0 +1,115 @@
+//! Strict parsing and API schema probes.
+//!
+//! QRZ adds fields to its XML responses from time to time, and the regular
+//! parser keeps unmodeled callsign and DXCC elements in their `extra` maps and
+//! ignores anything else the crate does not model. With the `strict`
+//! feature, [`parse_strict`] rejects such responses the way
+//! `#[serde(deny_unknown_fields)]` would, which is useful in test suites, and
+//! [`QrzXmlClient::probe_api`](crate::QrzXmlClient::probe_api) reports every
+//! unmodeled field in a live response so schema drift is noticed early.
+
+use crate::error::{QrzXmlError, Result};
+use crate::types::QrzXmlResponse;
+
+/// Callsign used by [`QrzXmlClient::probe_api`](crate::QrzXmlClient::probe_api); it is
+/// QRZ's own and present in every version of the database
+pub const PROBE_CALLSIGN: &str = "AA7BQ";
+
+/// Result of probing the QRZ API for fields the crate does not model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiProbeReport {
+    /// API version reported by the server
+    pub api_version: Option<String>,
+    /// Paths of unmodeled fields, e.g. `Callsign.newfield`
+    ///
+    /// Callsign and DXCC fields captured in their `extra` maps come first,
+    /// sorted by name, followed by other ignored fields in document order.
+    pub unknown_fields: Vec<String>,
+}
+
+impl ApiProbeReport {
+    /// Check if the response contained fields the crate does not model
+    pub fn has_drift(&self) -> bool {
+        !self.unknown_fields.is_empty()
+    }
+}
+
+/// Parse a response, collecting the paths of all fields that were ignored
+pub fn parse_with_unknown_fields(xml: &str) -> Result<(QrzXmlResponse, Vec<String>)> {
+    let mut unknown_fields = Vec::new();
+    let mut deserializer = quick_xml::de::Deserializer::from_str(xml);
+    let response: QrzXmlResponse = serde_ignored::deserialize(&mut deserializer, |path| {
+        // Optional fields add a `?` segment that means nothing to users
+        let path = path
+            .to_string()
+            .split('.')
+            .filter(|segment| *segment != "?")
+            .collect::<Vec<_>>()
+            .join(".");
+        if !unknown_fields.contains(&path) {
+            unknown_fields.push(path);
+        }
+    })?;
+
+    let mut extra_fields: Vec<String> = response
+        .callsign
+        .iter()
+        .flat_map(|info| info.extra.keys().map(|name| format!("Callsign.{}", name)))
+        .chain(
+            response
+                .dxcc
+                .iter()
+                .flat_map(|info| info.extra.keys().map(|name| format!("DXCC.{}", name))),
+        )
+        .collect();
+    extra_fields.sort();
+    extra_fields.extend(unknown_fields);
+    Ok((response, extra_fields))
+}
+
+/// Parse a response, failing if it contains any field the crate does not model
+pub fn parse_strict(xml: &str) -> Result<QrzXmlResponse> {
+    let (response, unknown_fields) = parse_with_unknown_fields(xml)?;
+    if unknown_fields.is_empty() {
+        Ok(response)
+    } else {
+        Err(QrzXmlError::unexpected_response(format!(
+            "Unknown fields: {}",
+            unknown_fields.join(", ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"<?xml version="1.0" ?>
+<QRZDatabase version="1.34">
+  <Callsign>
+    <call>AA7BQ</call>
+    <fname>FRED L</fname>
+    <shoesize>11</shoesize>
+  </Callsign>
+  <Session>
+    <Key>test_key</Key>
+    <Mood>good</Mood>
+  </Session>
+</QRZDatabase>"#;
+
+    #[test]
+    fn test_unknown_fields() {
+        let (response, unknown) = parse_with_unknown_fields(RESPONSE).unwrap();
+        assert_eq!(response.callsign.unwrap().call, "AA7BQ");
+        assert_eq!(unknown, vec!["Callsign.shoesize", "Session.Mood"]);
+
+        let err = parse_strict(RESPONSE).unwrap_err();
+        assert!(err.to_string().contains("Callsign.shoesize"));
+
+        let known = RESPONSE
+            .replace("<shoesize>11</shoesize>", "")
+            .replace("<Mood>good</Mood>", "");
+        assert!(parse_strict(&known).is_ok());
+        assert!(parse_strict(include_str!("../fixtures/callsign.xml")).is_ok());
+    }
+}
@@ -0,0 +1,140 @@
+//! Generic key-value queries with typed response dispatch.
+//!
+//! [`QrzXmlClient::query`](crate::QrzXmlClient::query) accepts arbitrary QRZ
+//! parameters, so new QRZ query types can be used before the crate grows a
+//! first-class method for them. The response is dispatched into whichever
+//! typed record it contains.
+
+use crate::types::{CallsignInfo, DxccInfo, QrzXmlResponse, SessionInfo};
+
+/// Ordered set of query parameters for a generic QRZ request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryParams {
+    params: Vec<(String, String)>,
+}
+
+impl QueryParams {
+    /// Create an empty parameter set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter, replacing any existing value for the same key
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Insert a parameter, replacing any existing value for the same key
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        let value = value.into();
+        match self.params.iter_mut().find(|(k, _)| *k == key) {
+            Some(existing) => existing.1 = value,
+            None => self.params.push((key, value)),
+        }
+    }
+
+    /// Get the value for a key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Check if a key is present
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Iterate over the parameter names
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.params.iter().map(|(k, _)| k.as_str())
+    }
+
+    /// Check if there are no parameters
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    pub(crate) fn as_pairs(&self) -> Vec<(&str, &str)> {
+        self.params
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for QueryParams {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut params = Self::new();
+        for (k, v) in iter {
+            params.insert(k, v);
+        }
+        params
+    }
+}
+
+/// Typed result of a generic query
+#[derive(Debug, Clone)]
+pub enum QueryResult {
+    /// The response contained a callsign record
+    Callsign(Box<CallsignInfo>),
+    /// The response contained a DXCC record
+    Dxcc(DxccInfo),
+    /// The response only contained session information
+    SessionOnly(SessionInfo),
+}
+
+impl QueryResult {
+    pub(crate) fn from_response(response: QrzXmlResponse) -> Self {
+        if let Some(callsign) = response.callsign {
+            QueryResult::Callsign(Box::new(callsign))
+        } else if let Some(dxcc) = response.dxcc {
+            QueryResult::Dxcc(dxcc)
+        } else {
+            QueryResult::SessionOnly(response.session)
+        }
+    }
+
+    /// Get the callsign record, if this is a callsign result
+    pub fn as_callsign(&self) -> Option<&CallsignInfo> {
+        match self {
+            QueryResult::Callsign(info) => Some(info.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Get the DXCC record, if this is a DXCC result
+    pub fn as_dxcc(&self) -> Option<&DxccInfo> {
+        match self {
+            QueryResult::Dxcc(info) => Some(info),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_params() {
+        let params = QueryParams::new()
+            .param("callsign", "W1AW")
+            .param("foo", "bar")
+            .param("callsign", "AA7BQ");
+
+        assert_eq!(params.get("callsign"), Some("AA7BQ"));
+        assert_eq!(params.keys().collect::<Vec<_>>(), vec!["callsign", "foo"]);
+        assert_eq!(
+            params.as_pairs(),
+            vec![("callsign", "AA7BQ"), ("foo", "bar")]
+        );
+
+        let collected: QueryParams = [("dxcc", "291")].into_iter().collect();
+        assert!(collected.contains_key("dxcc"));
+        assert!(QueryParams::new().is_empty());
+    }
+}
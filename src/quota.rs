@@ -0,0 +1,279 @@
+//! Daily lookup quota tracking.
+//!
+//! QRZ reports the number of lookups performed in the current 24-hour window
+//! with every response. The client keeps the latest count, exposes it through
+//! [`QrzXmlClient::quota`](crate::QrzXmlClient::quota) and emits a
+//! [`ClientEvent::QuotaWarning`](crate::ClientEvent::QuotaWarning) the first
+//! time each configured threshold is reached, so bulk tools can pace themselves.
+//! The limit can also be enforced, failing lookups with
+//! [`QrzXmlError::QuotaExceeded`](crate::QrzXmlError::QuotaExceeded) once it is used up.
+//!
+//! When the reported count drops to half or less of the previous one, or 24
+//! hours after the window was first seen, QRZ has started a new window; smaller
+//! drops are responses of concurrent lookups arriving out of order. The
+//! client then emits [`ClientEvent::QuotaReset`](crate::ClientEvent::QuotaReset),
+//! re-arms the warning thresholds and, unless configured otherwise, drops its own
+//! count so the estimate follows QRZ's new window right away.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Length of QRZ's lookup counting window
+const WINDOW: Duration = Duration::from_secs(24 * 3600);
+
+/// Lookup usage in the current 24-hour window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Quota {
+    /// Lookups performed in the current 24-hour window, as last reported by QRZ
+    /// or counted by this client, whichever is higher
+    pub used: Option<u32>,
+    /// The configured daily lookup limit
+    pub limit: Option<u32>,
+}
+
+impl Quota {
+    /// Lookups left before the daily limit is reached
+    pub fn remaining(&self) -> Option<u32> {
+        Some(self.limit?.saturating_sub(self.used.unwrap_or(0)))
+    }
+
+    /// Fraction of the daily limit used, from 0.0 upwards
+    pub fn fraction_used(&self) -> Option<f64> {
+        match self.limit? {
+            0 => None,
+            limit => Some(f64::from(self.used.unwrap_or(0)) / f64::from(limit)),
+        }
+    }
+
+    /// Check if the daily limit has been reached
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == Some(0)
+    }
+}
+
+/// What a newly reported count means for the quota
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct CountObservation {
+    /// Warning thresholds reached for the first time in this window
+    pub(crate) thresholds: Vec<u32>,
+    /// The previous count, if the new one started a new window
+    pub(crate) rolled_over_from: Option<u32>,
+}
+
+/// Tracks the reported count and which warning thresholds it crossed
+#[derive(Debug)]
+pub(crate) struct QuotaWatcher {
+    thresholds: Vec<u32>,
+    /// Drop the local count when QRZ starts a new window
+    resync_on_rollover: bool,
+    /// Highest count reported in the current window, and when the window was first seen
+    last_count: Mutex<Option<(u32, Instant)>>,
+    /// Lookups sent by this client since the start of its own 24-hour window
+    local: Mutex<Option<(Instant, u32)>>,
+}
+
+impl QuotaWatcher {
+    /// Watch percentages of the daily limit, if there is one
+    pub(crate) fn new(limit: Option<u32>, percentages: &[u8], resync_on_rollover: bool) -> Self {
+        let mut thresholds: Vec<u32> = match limit {
+            Some(limit) => percentages
+                .iter()
+                .map(|p| (u64::from(limit) * u64::from(*p)).div_ceil(100) as u32)
+                .collect(),
+            None => Vec::new(),
+        };
+        thresholds.sort_unstable();
+        thresholds.dedup();
+
+        Self {
+            thresholds,
+            resync_on_rollover,
+            last_count: Mutex::new(None),
+            local: Mutex::new(None),
+        }
+    }
+
+    /// Lookups used in the current window at `now`: the reported count or this
+    /// client's own count, whichever is higher
+    ///
+    /// Either count is forgotten once its 24-hour window has passed, so a used
+    /// up budget frees up again even if QRZ reports no new count.
+    pub(crate) fn count(&self, now: Instant) -> Option<u32> {
+        let reported = self
+            .last_count
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .filter(|(_, start)| now.duration_since(*start) < WINDOW)
+            .map(|(count, _)| count);
+        let local = self
+            .local
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .filter(|(start, _)| now.duration_since(*start) < WINDOW)
+            .map(|(_, count)| count);
+        reported.max(local)
+    }
+
+    /// Count a lookup sent by this client at `now`
+    pub(crate) fn record_lookup(&self, now: Instant) {
+        let mut local = self.local.lock().unwrap_or_else(|e| e.into_inner());
+        *local = match *local {
+            Some((start, count)) if now.duration_since(start) < WINDOW => Some((start, count + 1)),
+            _ => Some((now, 1)),
+        };
+    }
+
+    /// Record a count reported at `now`, returning the thresholds it reached
+    /// for the first time
+    ///
+    /// When the count shows a new 24-hour window started, all thresholds are
+    /// armed again and, if resynchronizing, the local count is dropped. Other
+    /// lower counts come from responses overtaken by later ones and are ignored.
+    pub(crate) fn observe(&self, count: u32, now: Instant) -> CountObservation {
+        let mut last_count = self.last_count.lock().unwrap_or_else(|e| e.into_inner());
+        let (previous, rolled_over_from) = match *last_count {
+            Some((previous, start)) if previous <= count => {
+                // A count reported after a full window belongs to a later one
+                let start = if now.duration_since(start) < WINDOW {
+                    start
+                } else {
+                    now
+                };
+                *last_count = Some((count, start));
+                (Some(previous), None)
+            }
+            Some((previous, start)) if is_rollover(previous, count, now.duration_since(start)) => {
+                *last_count = Some((count, now));
+                (None, Some(previous))
+            }
+            Some((previous, _)) => (Some(previous), None),
+            None => {
+                *last_count = Some((count, now));
+                (None, None)
+            }
+        };
+
+        if rolled_over_from.is_some() && self.resync_on_rollover {
+            *self.local.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+
+        let thresholds = self
+            .thresholds
+            .iter()
+            .copied()
+            .filter(|t| *t <= count && previous.is_none_or(|p| p < *t))
+            .collect();
+        CountObservation {
+            thresholds,
+            rolled_over_from,
+        }
+    }
+}
+
+/// Check if a drop from `previous` to `count` means QRZ started a new window
+/// `elapsed` after the current one was first seen
+///
+/// Concurrent lookups return counts a few apart in any order, so only a drop
+/// to half or less of the previous count, or any drop once a full window has
+/// passed, counts.
+fn is_rollover(previous: u32, count: u32, elapsed: Duration) -> bool {
+    count < previous && (count <= previous / 2 || elapsed >= WINDOW)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota() {
+        let quota = Quota {
+            used: Some(80),
+            limit: Some(100),
+        };
+        assert_eq!(quota.remaining(), Some(20));
+        assert_eq!(quota.fraction_used(), Some(0.8));
+        assert!(!quota.is_exhausted());
+        assert_eq!(Quota::default().remaining(), None);
+    }
+
+    #[test]
+    fn test_thresholds_fire_once_per_window() {
+        let watcher = QuotaWatcher::new(Some(100), &[50, 80, 100], true);
+        let now = Instant::now();
+        let thresholds = |count| watcher.observe(count, now).thresholds;
+
+        assert_eq!(thresholds(10), Vec::<u32>::new());
+        assert_eq!(thresholds(85), vec![50, 80]);
+        assert_eq!(thresholds(90), Vec::<u32>::new());
+        assert_eq!(thresholds(100), vec![100]);
+        assert_eq!(watcher.count(Instant::now()), Some(100));
+
+        // A new 24-hour window re-arms the thresholds
+        assert_eq!(thresholds(1), Vec::<u32>::new());
+        assert_eq!(thresholds(60), vec![50]);
+    }
+
+    #[test]
+    fn test_local_lookup_count() {
+        let watcher = QuotaWatcher::new(None, &[], true);
+        let start = Instant::now();
+        assert_eq!(watcher.count(start), None);
+
+        watcher.record_lookup(start);
+        watcher.record_lookup(start);
+        assert_eq!(watcher.count(start), Some(2));
+
+        // The reported count wins when it is higher
+        watcher.observe(10, start);
+        assert_eq!(watcher.count(start), Some(10));
+
+        // Both counts expire after 24 hours and the local window restarts
+        let later = start + WINDOW;
+        assert_eq!(watcher.count(later), None);
+        watcher.record_lookup(later);
+        assert_eq!(watcher.count(later), Some(1));
+
+        // A count reported then starts a new window of its own
+        watcher.observe(12, later);
+        assert_eq!(watcher.count(later + WINDOW / 2), Some(12));
+    }
+
+    #[test]
+    fn test_rollover_resyncs_local_count() {
+        let now = Instant::now();
+        for resync in [true, false] {
+            let watcher = QuotaWatcher::new(None, &[], resync);
+            assert_eq!(watcher.observe(90, now).rolled_over_from, None);
+            for _ in 0..95 {
+                watcher.record_lookup(now);
+            }
+            assert_eq!(watcher.count(now), Some(95));
+
+            assert_eq!(watcher.observe(3, now).rolled_over_from, Some(90));
+            let expected = if resync { 3 } else { 95 };
+            assert_eq!(watcher.count(now), Some(expected));
+            assert_eq!(watcher.observe(4, now).rolled_over_from, None);
+        }
+    }
+
+    #[test]
+    fn test_out_of_order_counts_are_not_rollovers() {
+        let watcher = QuotaWatcher::new(Some(100), &[90], true);
+        let start = Instant::now();
+        for _ in 0..91 {
+            watcher.record_lookup(start);
+        }
+        assert_eq!(watcher.observe(91, start).thresholds, vec![90]);
+
+        // Concurrent lookups answered out of order
+        let observation = watcher.observe(89, start);
+        assert_eq!(observation, CountObservation::default());
+        assert_eq!(watcher.count(start), Some(91));
+        assert_eq!(watcher.observe(92, start), CountObservation::default());
+
+        // A small drop after a full window is a new window after all
+        let later = start + WINDOW;
+        assert_eq!(watcher.observe(80, later).rolled_over_from, Some(92));
+        assert_eq!(watcher.observe(95, later).thresholds, vec![90]);
+    }
+}
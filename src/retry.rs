@@ -0,0 +1,74 @@
+//! Retry budget shared by all extra requests a client makes.
+//!
+//! Re-authentication retries and hedged requests both cost QRZ an additional
+//! request. The budget caps how many of those a client may issue per minute, so
+//! a struggling server is not hammered by the very mechanisms meant to hide its
+//! slowness.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Sliding one-minute budget of extra requests
+#[derive(Debug)]
+pub(crate) struct RetryBudget {
+    limit: Option<u32>,
+    spent: Mutex<VecDeque<Instant>>,
+}
+
+impl RetryBudget {
+    /// Create a budget allowing `limit` extra requests per minute, or unlimited if `None`
+    pub(crate) fn new(limit: Option<u32>) -> Self {
+        Self {
+            limit,
+            spent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Spend one extra request at `now` if the budget allows it
+    pub(crate) fn try_acquire(&self, now: Instant) -> bool {
+        let Some(limit) = self.limit else {
+            return true;
+        };
+
+        let mut spent = self.spent.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(oldest) = spent.front() {
+            if now.duration_since(*oldest) >= WINDOW {
+                spent.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if spent.len() < limit as usize {
+            spent.push_back(now);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_window() {
+        let budget = RetryBudget::new(Some(2));
+        let start = Instant::now();
+
+        assert!(budget.try_acquire(start));
+        assert!(budget.try_acquire(start + Duration::from_secs(30)));
+        assert!(!budget.try_acquire(start + Duration::from_secs(31)));
+
+        // The first spend falls out of the window after a minute
+        assert!(budget.try_acquire(start + Duration::from_secs(61)));
+        assert!(!budget.try_acquire(start + Duration::from_secs(61)));
+
+        let unlimited = RetryBudget::new(None);
+        assert!((0..100).all(|_| unlimited.try_acquire(start)));
+    }
+}
@@ -0,0 +1,348 @@
+//! Change reports between two lookups of a club roster.
+//!
+//! Newsletter editors typically look up the whole membership list every month
+//! and want to know what changed since the previous run: new licensees, class
+//! upgrades, licenses that expired and members who moved. Keep the previous
+//! run's records (they serialize with serde) and its date, and compare them
+//! with a fresh run.
+
+use crate::types::CallsignInfo;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// US license classes as reported in `class`, from lowest to highest
+const CLASS_ORDER: [&str; 5] = ["N", "T", "G", "A", "E"];
+
+/// What changed for a callsign between two runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ChangeKind {
+    /// The callsign is new to the roster
+    NewLicense,
+    /// The member now holds a new callsign; `before` is the previous call
+    CallsignChanged,
+    /// The license class went up
+    ClassUpgraded,
+    /// The license class changed in any other way
+    ClassChanged,
+    /// The license lapsed between the previous run and this one
+    LicenseExpired,
+    /// The mailing address changed
+    AddressChanged,
+    /// The callsign was in the previous run but could not be found this time
+    NoLongerListed,
+}
+
+impl ChangeKind {
+    /// Label used in CSV output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::NewLicense => "new_license",
+            ChangeKind::CallsignChanged => "callsign_changed",
+            ChangeKind::ClassUpgraded => "class_upgraded",
+            ChangeKind::ClassChanged => "class_changed",
+            ChangeKind::LicenseExpired => "license_expired",
+            ChangeKind::AddressChanged => "address_changed",
+            ChangeKind::NoLongerListed => "no_longer_listed",
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single change to a roster entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosterChange {
+    /// Callsign the change applies to, as of the current run
+    pub call: String,
+    /// What changed
+    pub kind: ChangeKind,
+    /// Value in the previous run, if the change has one
+    pub before: Option<String>,
+    /// Value in the current run, if the change has one
+    pub after: Option<String>,
+}
+
+/// All changes between two runs, ordered by callsign
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosterReport {
+    /// The changes found
+    pub changes: Vec<RosterChange>,
+}
+
+impl RosterReport {
+    /// Changes of one kind
+    pub fn of_kind(&self, kind: ChangeKind) -> impl Iterator<Item = &RosterChange> {
+        self.changes.iter().filter(move |c| c.kind == kind)
+    }
+
+    /// Check if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Render the report as CSV with a `call,change,before,after` header
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("call,change,before,after\n");
+        for change in &self.changes {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                csv_field(&change.call),
+                change.kind,
+                csv_field(change.before.as_deref().unwrap_or("")),
+                csv_field(change.after.as_deref().unwrap_or("")),
+            ));
+        }
+        csv
+    }
+}
+
+/// Compare a previous run of a roster with a fresh one.
+///
+/// Records are matched by callsign. A new callsign whose `p_call` is a
+/// callsign that disappeared from the roster is reported as a callsign change
+/// rather than a new license. A license counts as expired when its current
+/// `expdate` falls between the two runs, `previous_as_of <= expdate < as_of`,
+/// so lapses are reported even when the date itself did not change; missing
+/// and placeholder dates such as `0000-00-00` never count.
+pub fn compare_rosters(
+    previous: &[CallsignInfo],
+    current: &[CallsignInfo],
+    previous_as_of: NaiveDate,
+    as_of: NaiveDate,
+) -> RosterReport {
+    let previous = by_call(previous);
+    let current = by_call(current);
+
+    let mut changes = Vec::new();
+    let mut renamed = Vec::new();
+
+    for (call, record) in &current {
+        let Some(old) = previous.get(call) else {
+            let old_call = record
+                .p_call
+                .as_deref()
+                .map(|p| p.trim().to_ascii_uppercase())
+                .filter(|p| previous.contains_key(p) && !current.contains_key(p));
+            match old_call {
+                Some(old_call) => {
+                    changes.push(change(
+                        call,
+                        ChangeKind::CallsignChanged,
+                        Some(&old_call),
+                        Some(call),
+                    ));
+                    renamed.push(old_call);
+                }
+                None => changes.push(change(call, ChangeKind::NewLicense, None, None)),
+            }
+            continue;
+        };
+
+        let (old_class, new_class) = (non_empty(&old.class), non_empty(&record.class));
+        if !same(old_class, new_class) {
+            let kind = match (class_rank(old_class), class_rank(new_class)) {
+                (Some(from), Some(to)) if to > from => ChangeKind::ClassUpgraded,
+                _ => ChangeKind::ClassChanged,
+            };
+            changes.push(change(call, kind, old_class, new_class));
+        }
+
+        let lapsed = record
+            .expdate_parsed()
+            .is_some_and(|expdate| previous_as_of <= expdate && expdate < as_of);
+        if lapsed {
+            let expdate = non_empty(&record.expdate);
+            changes.push(change(call, ChangeKind::LicenseExpired, None, expdate));
+        }
+
+        let (old_address, new_address) = (address(old), address(record));
+        if !same(old_address.as_deref(), new_address.as_deref()) {
+            changes.push(change(
+                call,
+                ChangeKind::AddressChanged,
+                old_address.as_deref(),
+                new_address.as_deref(),
+            ));
+        }
+    }
+
+    for call in previous.keys() {
+        if !current.contains_key(call) && !renamed.contains(call) {
+            changes.push(change(call, ChangeKind::NoLongerListed, None, None));
+        }
+    }
+
+    changes.sort_by(|a, b| a.call.cmp(&b.call).then(a.kind.cmp(&b.kind)));
+    RosterReport { changes }
+}
+
+fn by_call(records: &[CallsignInfo]) -> BTreeMap<String, &CallsignInfo> {
+    records
+        .iter()
+        .map(|r| (r.call.trim().to_ascii_uppercase(), r))
+        .collect()
+}
+
+fn change(call: &str, kind: ChangeKind, before: Option<&str>, after: Option<&str>) -> RosterChange {
+    RosterChange {
+        call: call.to_string(),
+        kind,
+        before: before.map(str::to_string),
+        after: after.map(str::to_string),
+    }
+}
+
+fn non_empty(field: &Option<String>) -> Option<&str> {
+    field.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Compare two values ignoring case and runs of whitespace
+fn same(a: Option<&str>, b: Option<&str>) -> bool {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .map(|w| w.to_ascii_uppercase())
+            .collect::<Vec<_>>()
+    };
+    a.map(normalize) == b.map(normalize)
+}
+
+fn class_rank(class: Option<&str>) -> Option<usize> {
+    let class = class?;
+    CLASS_ORDER
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(class))
+}
+
+/// One-line mailing address, or `None` if no address fields are set
+fn address(record: &CallsignInfo) -> Option<String> {
+    let parts: Vec<&str> = [
+        &record.addr1,
+        &record.addr2,
+        &record.state,
+        &record.zip,
+        &record.country,
+    ]
+    .into_iter()
+    .filter_map(non_empty)
+    .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(call: &str) -> CallsignInfo {
+        CallsignInfo {
+            call: call.to_string(),
+            class: Some("T".to_string()),
+            addr1: Some("1 Main St".to_string()),
+            addr2: Some("Springfield".to_string()),
+            expdate: Some("2030-01-01".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_roster_changes() {
+        // W1AW lapses between the runs without its expdate changing
+        let w1aw = CallsignInfo {
+            expdate: Some("2024-12-15".to_string()),
+            ..record("W1AW")
+        };
+        let previous = vec![
+            record("AA7BQ"),
+            record("K7OLD"),
+            w1aw.clone(),
+            record("N0GONE"),
+        ];
+        let current = vec![
+            CallsignInfo {
+                class: Some("E".to_string()),
+                addr1: Some("22 Oak Ave".to_string()),
+                ..record("AA7BQ")
+            },
+            CallsignInfo {
+                p_call: Some("K7OLD".to_string()),
+                ..record("K7NEW")
+            },
+            w1aw,
+            record("KD2NEW"),
+        ];
+
+        let (previous_as_of, as_of) = (date("2024-12-01"), date("2025-01-01"));
+        let report = compare_rosters(&previous, &current, previous_as_of, as_of);
+        let kinds: Vec<(&str, ChangeKind)> = report
+            .changes
+            .iter()
+            .map(|c| (c.call.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("AA7BQ", ChangeKind::ClassUpgraded),
+                ("AA7BQ", ChangeKind::AddressChanged),
+                ("K7NEW", ChangeKind::CallsignChanged),
+                ("KD2NEW", ChangeKind::NewLicense),
+                ("N0GONE", ChangeKind::NoLongerListed),
+                ("W1AW", ChangeKind::LicenseExpired),
+            ]
+        );
+        assert_eq!(report.of_kind(ChangeKind::NewLicense).count(), 1);
+        assert!(
+            compare_rosters(&previous, &current, as_of, date("2025-02-01"))
+                .of_kind(ChangeKind::LicenseExpired)
+                .next()
+                .is_none()
+        );
+
+        let csv = report.to_csv();
+        assert!(csv.starts_with("call,change,before,after\n"));
+        assert!(csv.contains("AA7BQ,class_upgraded,T,E\n"));
+        assert!(csv.contains(
+            "AA7BQ,address_changed,\"1 Main St, Springfield\",\"22 Oak Ave, Springfield\"\n"
+        ));
+        assert!(csv.contains("K7NEW,callsign_changed,K7OLD,K7NEW\n"));
+    }
+
+    #[test]
+    fn test_expiry_ignores_placeholders_and_earlier_lapses() {
+        let (previous_as_of, as_of) = (date("2024-12-01"), date("2025-01-01"));
+        for expdate in ["0000-00-00", "", "2024-11-30", "2025-01-01"] {
+            let roster = vec![CallsignInfo {
+                expdate: Some(expdate.to_string()),
+                ..record("W1AW")
+            }];
+            assert!(
+                compare_rosters(&roster, &roster, previous_as_of, as_of).is_empty(),
+                "expdate {:?}",
+                expdate
+            );
+        }
+        assert!(
+            compare_rosters(&[record("W1AW")], &[record("W1AW")], previous_as_of, as_of).is_empty()
+        );
+    }
+}
@@ -0,0 +1,617 @@
+//! Pluggable session persistence.
+//!
+//! QRZ session keys stay valid for about a day, and logging in again on every
+//! process start both slows startup and counts against QRZ's session limits. A
+//! [`SessionStore`] attached with
+//! [`QrzXmlClient::with_session_store`](crate::QrzXmlClient::with_session_store)
+//! lets the client pick up a previous session first-class: it is loaded when the
+//! client first needs a key, saved after every login and cleared when QRZ
+//! reports it expired.
+//!
+//! Stores shared between processes can also hand out a [`SessionLock`]. The
+//! client holds it while restoring a session or logging in, so several
+//! short-lived processes using one account wait for a single login and then
+//! reuse its session instead of each logging in and invalidating the others.
+
+use crate::error::{QrzXmlError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Stored sessions older than this are treated as expired without asking QRZ
+pub const SESSION_MAX_AGE: Duration = Duration::from_secs(23 * 3600);
+
+/// A persisted QRZ session
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "StoredSession")]
+pub struct StoredSession {
+    /// QRZ username the session belongs to
+    pub username: String,
+    /// Session key
+    pub key: String,
+    /// Lookups performed in the current 24-hour period when the session was saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// Subscription expiration date or "non-subscriber"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub_exp: Option<String>,
+    /// When the session was saved, in seconds since the Unix epoch
+    pub saved_at: u64,
+}
+
+impl fmt::Debug for StoredSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoredSession")
+            .field("username", &self.username)
+            .field("key", &"<redacted>")
+            .field("count", &self.count)
+            .field("sub_exp", &self.sub_exp)
+            .field("saved_at", &self.saved_at)
+            .finish()
+    }
+}
+
+impl StoredSession {
+    /// Create a session record saved now
+    pub fn new(username: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            key: key.into(),
+            count: None,
+            sub_exp: None,
+            saved_at: unix_now(),
+        }
+    }
+
+    /// Check if the session is older than [`SESSION_MAX_AGE`]
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SystemTime::now())
+    }
+
+    /// Check if the session is older than [`SESSION_MAX_AGE`] at the given time
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        unix_seconds(now).saturating_sub(self.saved_at) > SESSION_MAX_AGE.as_secs()
+    }
+}
+
+/// A portable copy of a client's session, for storage the crate does not know about
+///
+/// Obtained with [`QrzXmlClient::export_session`](crate::QrzXmlClient::export_session)
+/// and handed back with [`QrzXmlClient::import_session`](crate::QrzXmlClient::import_session),
+/// e.g. after keeping it in Redis, a database or browser storage.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// Session key
+    pub key: String,
+    /// Lookups performed in the current 24-hour period
+    pub count: Option<u32>,
+    /// Subscription expiration date or "non-subscriber"
+    pub sub_exp: Option<String>,
+    /// When the session was established, in seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+impl fmt::Debug for SessionSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionSnapshot")
+            .field("key", &"<redacted>")
+            .field("count", &self.count)
+            .field("sub_exp", &self.sub_exp)
+            .field("created_at", &self.created_at)
+            .finish()
+    }
+}
+
+impl SessionSnapshot {
+    /// Check if the session is older than [`SESSION_MAX_AGE`]
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SystemTime::now())
+    }
+
+    /// Check if the session is older than [`SESSION_MAX_AGE`] at the given time
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        unix_seconds(now).saturating_sub(self.created_at) > SESSION_MAX_AGE.as_secs()
+    }
+}
+
+/// Storage backend for QRZ sessions
+pub trait SessionStore: Send + Sync {
+    /// Load the stored session for a user, if any
+    fn load(&self, username: &str) -> Result<Option<StoredSession>>;
+
+    /// Save a session, replacing any previous one for the same user
+    fn save(&self, session: &StoredSession) -> Result<()>;
+
+    /// Remove the stored session for a user
+    fn clear(&self, username: &str) -> Result<()>;
+
+    /// Load when QRZ may be contacted again after refusing connections, if a refusal is stored
+    ///
+    /// Times are in seconds since the Unix epoch. The default implementation
+    /// stores nothing.
+    fn load_refused_until(&self, username: &str) -> Result<Option<u64>> {
+        let _ = username;
+        Ok(None)
+    }
+
+    /// Save a connection refusal for a user, or clear it with `None`
+    fn save_refused_until(&self, username: &str, until: Option<u64>) -> Result<()> {
+        let _ = (username, until);
+        Ok(())
+    }
+
+    /// Take an exclusive lock on a user's stored session, blocking until it is free
+    ///
+    /// Stores shared between processes return a lock held until the returned
+    /// guard is dropped. The default implementation does not lock.
+    fn lock(&self, username: &str) -> Result<Option<SessionLock>> {
+        let _ = username;
+        Ok(None)
+    }
+}
+
+/// An exclusive lock on a stored session, released when dropped
+pub struct SessionLock {
+    _guard: Box<dyn Send + Sync>,
+}
+
+impl SessionLock {
+    /// Wrap a guard whose drop releases the lock
+    pub fn new(guard: impl Send + Sync + 'static) -> Self {
+        Self {
+            _guard: Box::new(guard),
+        }
+    }
+}
+
+impl fmt::Debug for SessionLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionLock").finish_non_exhaustive()
+    }
+}
+
+/// Session store keeping sessions in memory, e.g. shared between clients in one process
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    sessions: Mutex<HashMap<String, StoredSession>>,
+    refusals: Mutex<HashMap<String, u64>>,
+}
+
+impl MemorySessionStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn load(&self, username: &str) -> Result<Option<StoredSession>> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(sessions.get(username).cloned())
+    }
+
+    fn save(&self, session: &StoredSession) -> Result<()> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.insert(session.username.clone(), session.clone());
+        Ok(())
+    }
+
+    fn clear(&self, username: &str) -> Result<()> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.remove(username);
+        Ok(())
+    }
+
+    fn load_refused_until(&self, username: &str) -> Result<Option<u64>> {
+        let refusals = self.refusals.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(refusals.get(username).copied())
+    }
+
+    fn save_refused_until(&self, username: &str, until: Option<u64>) -> Result<()> {
+        let mut refusals = self.refusals.lock().unwrap_or_else(|e| e.into_inner());
+        match until {
+            Some(until) => refusals.insert(username.to_string(), until),
+            None => refusals.remove(username),
+        };
+        Ok(())
+    }
+}
+
+/// Session store keeping one XML file per user in a directory
+///
+/// Files are replaced atomically, so a concurrent reader never sees a partly
+/// written session.
+#[derive(Debug, Clone)]
+pub struct FileSessionStore {
+    dir: PathBuf,
+    #[cfg(feature = "shared-session")]
+    locking: bool,
+}
+
+impl FileSessionStore {
+    /// Store sessions in the given directory, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| {
+            QrzXmlError::session_store(format!("Cannot create {}: {}", dir.display(), e))
+        })?;
+        Ok(Self {
+            dir,
+            #[cfg(feature = "shared-session")]
+            locking: false,
+        })
+    }
+
+    /// Share sessions between processes, serializing logins with an advisory file lock
+    ///
+    /// Each user gets a `.lock` file next to the session file, locked while a
+    /// client restores a session or logs in.
+    #[cfg(feature = "shared-session")]
+    pub fn with_locking(mut self) -> Self {
+        self.locking = true;
+        self
+    }
+
+    /// Store sessions in the XDG cache directory (`$XDG_CACHE_HOME/qrz-xml` or `~/.cache/qrz-xml`)
+    pub fn xdg() -> Result<Self> {
+        let cache_dir = if let Some(xdg_cache) = std::env::var_os("XDG_CACHE_HOME") {
+            PathBuf::from(xdg_cache)
+        } else if let Some(home) = std::env::var_os("HOME") {
+            PathBuf::from(home).join(".cache")
+        } else {
+            return Err(QrzXmlError::session_store(
+                "Cannot determine cache directory",
+            ));
+        };
+
+        Self::new(cache_dir.join("qrz-xml"))
+    }
+
+    /// Directory the sessions are stored in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the session file for a user
+    pub fn session_file_path(&self, username: &str) -> PathBuf {
+        self.dir
+            .join(format!("session_{}.xml", safe_file_name(username)))
+    }
+
+    /// Path of the file recording a connection refusal for a user
+    pub fn refusal_file_path(&self, username: &str) -> PathBuf {
+        self.dir
+            .join(format!("refused_{}", safe_file_name(username)))
+    }
+}
+
+impl SessionStore for FileSessionStore {
+    fn load(&self, username: &str) -> Result<Option<StoredSession>> {
+        let path = self.session_file_path(username);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(QrzXmlError::session_store(format!(
+                    "Cannot read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        let session: StoredSession = quick_xml::de::from_str(&content)?;
+        Ok(Some(session))
+    }
+
+    fn save(&self, session: &StoredSession) -> Result<()> {
+        let path = self.session_file_path(&session.username);
+        let content = quick_xml::se::to_string(session)
+            .map_err(|e| QrzXmlError::session_store(format!("Cannot serialize session: {}", e)))?;
+        write_private(&path, &content).map_err(|e| {
+            QrzXmlError::session_store(format!("Cannot write {}: {}", path.display(), e))
+        })
+    }
+
+    fn clear(&self, username: &str) -> Result<()> {
+        let path = self.session_file_path(username);
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(QrzXmlError::session_store(format!(
+                "Cannot remove {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    fn load_refused_until(&self, username: &str) -> Result<Option<u64>> {
+        let path = self.refusal_file_path(username);
+        match fs::read_to_string(&path) {
+            Ok(content) => Ok(content.trim().parse().ok()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(QrzXmlError::session_store(format!(
+                "Cannot read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    fn save_refused_until(&self, username: &str, until: Option<u64>) -> Result<()> {
+        let path = self.refusal_file_path(username);
+        let result = match until {
+            Some(until) => write_private(&path, &until.to_string()),
+            None => match fs::remove_file(&path) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                other => other,
+            },
+        };
+        result.map_err(|e| {
+            QrzXmlError::session_store(format!("Cannot write {}: {}", path.display(), e))
+        })
+    }
+
+    #[cfg(feature = "shared-session")]
+    fn lock(&self, username: &str) -> Result<Option<SessionLock>> {
+        if !self.locking {
+            return Ok(None);
+        }
+
+        let path = self.session_file_path(username).with_extension("lock");
+        let lock_error = |e: std::io::Error| {
+            QrzXmlError::session_store(format!("Cannot lock {}: {}", path.display(), e))
+        };
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(lock_error)?;
+        fs4::fs_std::FileExt::lock_exclusive(&file).map_err(lock_error)?;
+        Ok(Some(SessionLock::new(file)))
+    }
+}
+
+/// Replace a file with `content`, readable only by its owner on Unix
+///
+/// The content goes to a fresh temporary file first, so readers never see a
+/// partial file and the session key is never briefly world-readable.
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    // Unique per write, so concurrent saves in one process never share a temp file
+    static NEXT_TEMP_FILE: AtomicU64 = AtomicU64::new(0);
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp_path = PathBuf::from(temp_path);
+
+    // A leftover from a crashed process with the same id may have wider permissions
+    let _ = fs::remove_file(&temp_path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let result = options
+        .open(&temp_path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .and_then(|()| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Percent-encode every byte that is not an ASCII letter or digit
+///
+/// The encoding is reversible, so distinct usernames such as `test.user` and
+/// `test_user` never share a file.
+fn safe_file_name(username: &str) -> String {
+    use std::fmt::Write;
+
+    let mut name = String::with_capacity(username.len());
+    for byte in username.bytes() {
+        if byte.is_ascii_alphanumeric() {
+            name.push(char::from(byte));
+        } else {
+            let _ = write!(name, "%{:02X}", byte);
+        }
+    }
+    name
+}
+
+pub(crate) fn unix_now() -> u64 {
+    unix_seconds(SystemTime::now())
+}
+
+/// Seconds since the Unix epoch, zero for earlier times
+pub(crate) fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_session_expiry() {
+        let session = StoredSession::new("testuser", "test_key");
+        assert!(!session.is_expired());
+
+        let old = StoredSession {
+            saved_at: 0,
+            ..session
+        };
+        assert!(old.is_expired());
+        assert!(!format!("{:?}", old).contains("test_key"));
+    }
+
+    #[test]
+    fn test_session_snapshot_serde() {
+        let snapshot = SessionSnapshot {
+            key: "test_key".to_string(),
+            count: Some(42),
+            sub_exp: None,
+            created_at: unix_now(),
+        };
+        assert!(!snapshot.is_expired());
+        assert!(!format!("{:?}", snapshot).contains("test_key"));
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: SessionSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot);
+        assert!(SessionSnapshot {
+            created_at: 0,
+            ..parsed
+        }
+        .is_expired());
+    }
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = FileSessionStore::new(temp_dir.path()).unwrap();
+
+        let session = StoredSession {
+            count: Some(42),
+            sub_exp: Some("Wed Jan 1 12:34:03 2025".to_string()),
+            ..StoredSession::new("test.user", "test_key")
+        };
+
+        store.save(&session).unwrap();
+        assert_eq!(store.load("test.user").unwrap(), Some(session));
+
+        store.clear("test.user").unwrap();
+        assert_eq!(store.load("test.user").unwrap(), None);
+        store.clear("test.user").unwrap();
+
+        store.save_refused_until("test.user", Some(1234)).unwrap();
+        assert_eq!(store.load_refused_until("test.user").unwrap(), Some(1234));
+        store.save_refused_until("test.user", None).unwrap();
+        assert_eq!(store.load_refused_until("test.user").unwrap(), None);
+        store.save_refused_until("test.user", None).unwrap();
+    }
+
+    #[test]
+    fn test_file_store_keeps_similar_usernames_apart() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = FileSessionStore::new(temp_dir.path()).unwrap();
+
+        assert_eq!(safe_file_name("test.user"), "test%2Euser");
+        assert_ne!(
+            store.session_file_path("test.user"),
+            store.session_file_path("test_user")
+        );
+
+        store
+            .save(&StoredSession::new("test.user", "key_1"))
+            .unwrap();
+        store
+            .save(&StoredSession::new("test_user", "key_2"))
+            .unwrap();
+        store.save_refused_until("test.user", Some(1234)).unwrap();
+
+        assert_eq!(store.load_refused_until("test_user").unwrap(), None);
+        store.clear("test_user").unwrap();
+        assert_eq!(store.load("test.user").unwrap().unwrap().key, "key_1");
+        assert!(store.load("test_user").unwrap().is_none());
+        assert_eq!(store.load_refused_until("test.user").unwrap(), Some(1234));
+    }
+
+    #[test]
+    fn test_file_store_concurrent_saves() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = FileSessionStore::new(temp_dir.path()).unwrap();
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let store = &store;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        let key = format!("key_{}", i);
+                        store.save(&StoredSession::new("test.user", key)).unwrap();
+                        store.save_refused_until("test.user", Some(i)).unwrap();
+                    }
+                });
+            }
+        });
+
+        assert!(store.load("test.user").unwrap().is_some());
+        assert!(store.load_refused_until("test.user").unwrap().is_some());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_store_files_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = FileSessionStore::new(temp_dir.path()).unwrap();
+        store
+            .save(&StoredSession::new("test.user", "test_key"))
+            .unwrap();
+        store.save_refused_until("test.user", Some(1234)).unwrap();
+
+        for path in [
+            store.session_file_path("test.user"),
+            store.refusal_file_path("test.user"),
+        ] {
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{}", path.display());
+        }
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[cfg(feature = "shared-session")]
+    #[test]
+    fn test_file_store_lock_is_exclusive() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = FileSessionStore::new(temp_dir.path())
+            .unwrap()
+            .with_locking();
+        let first = store.lock("testuser").unwrap().unwrap();
+
+        let acquired = Arc::new(AtomicBool::new(false));
+        let waiter = {
+            let store = store.clone();
+            let acquired = Arc::clone(&acquired);
+            std::thread::spawn(move || {
+                let _second = store.lock("testuser").unwrap().unwrap();
+                acquired.store(true, Ordering::SeqCst);
+            })
+        };
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!acquired.load(Ordering::SeqCst));
+        drop(first);
+        waiter.join().unwrap();
+        assert!(acquired.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemorySessionStore::new();
+        store.save(&StoredSession::new("a", "key_a")).unwrap();
+        assert_eq!(store.load("a").unwrap().unwrap().key, "key_a");
+        assert!(store.load("b").unwrap().is_none());
+        store.clear("a").unwrap();
+        assert!(store.load("a").unwrap().is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! Unified station model.
+//!
+//! Most applications want more than a raw callsign record: the DXCC entity the
+//! station counts for, a usable location even when the operator did not set one,
+//! and the distance and bearing from their own station. [`Station`] composes all
+//! of that and records where each piece came from, and
+//! [`QrzXmlClient::station`](crate::QrzXmlClient::station) builds one in a single call.
+
+#[cfg(feature = "tz")]
+use crate::timezone::StationTimeZone;
+use crate::types::{CallsignInfo, Continent, DxccInfo};
+#[cfg(feature = "tz")]
+use chrono::{DateTime, FixedOffset, Utc};
+
+pub use crate::geo::{bearing, coordinates_to_grid, distance_km, grid_to_coordinates};
+
+/// Where a station's coordinates came from, from most to least precise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateSource {
+    /// Latitude and longitude in the callsign record
+    Record,
+    /// Center of the grid square in the callsign record
+    Grid,
+    /// Approximate center of the DXCC entity
+    Dxcc,
+}
+
+/// Provenance of the derived parts of a [`Station`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Provenance {
+    /// Source of the coordinates, if any were found
+    pub coordinates: Option<CoordinateSource>,
+    /// The grid square was computed from the coordinates rather than taken from the record
+    pub grid_computed: bool,
+    /// The DXCC entity record was resolved
+    pub dxcc_resolved: bool,
+}
+
+/// A callsign record combined with its DXCC entity and derived location
+#[derive(Debug, Clone)]
+pub struct Station {
+    /// The callsign record
+    pub callsign: CallsignInfo,
+    /// The DXCC entity the station counts for
+    pub dxcc: Option<DxccInfo>,
+    /// Best known coordinates as (lat, lon)
+    pub coordinates: Option<(f64, f64)>,
+    /// Maidenhead grid square
+    pub grid: Option<String>,
+    /// Where the derived values came from
+    pub provenance: Provenance,
+}
+
+impl Station {
+    /// Combine a callsign record with its DXCC entity, deriving location data
+    pub fn new(callsign: CallsignInfo, dxcc: Option<DxccInfo>) -> Self {
+        let record_grid = callsign
+            .grid
+            .as_deref()
+            .map(str::trim)
+            .filter(|grid| grid_to_coordinates(grid).is_some())
+            .map(str::to_string);
+
+        let (coordinates, source) = if let Some(coordinates) = callsign.coordinates() {
+            (Some(coordinates), Some(CoordinateSource::Record))
+        } else if let Some(coordinates) = record_grid.as_deref().and_then(grid_to_coordinates) {
+            (Some(coordinates), Some(CoordinateSource::Grid))
+        } else if let Some(coordinates) = dxcc.as_ref().and_then(DxccInfo::coordinates) {
+            (Some(coordinates), Some(CoordinateSource::Dxcc))
+        } else {
+            (None, None)
+        };
+
+        // A grid computed from an entity center would be misleadingly precise
+        let computed_grid = match (&record_grid, coordinates, source) {
+            (None, Some((lat, lon)), Some(CoordinateSource::Record)) => {
+                coordinates_to_grid(lat, lon)
+            }
+            _ => None,
+        };
+
+        Self {
+            provenance: Provenance {
+                coordinates: source,
+                grid_computed: computed_grid.is_some(),
+                dxcc_resolved: dxcc.is_some(),
+            },
+            grid: record_grid.or(computed_grid),
+            coordinates,
+            dxcc,
+            callsign,
+        }
+    }
+
+    /// Primary callsign
+    pub fn call(&self) -> &str {
+        &self.callsign.call
+    }
+
+    /// Continent of the station's DXCC entity, if it was resolved
+    pub fn continent(&self) -> Option<Continent> {
+        self.dxcc.as_ref().and_then(DxccInfo::continent_parsed)
+    }
+
+    /// Great-circle distance in kilometers from the given (lat, lon)
+    pub fn distance_km_from(&self, origin: (f64, f64)) -> Option<f64> {
+        self.coordinates.map(|to| distance_km(origin, to))
+    }
+
+    /// Initial bearing in degrees (0-360, clockwise from north) from the given (lat, lon)
+    pub fn bearing_from(&self, origin: (f64, f64)) -> Option<f64> {
+        self.coordinates.map(|to| bearing(origin, to))
+    }
+
+    /// Time zone of the station, falling back to the DXCC entity's offset
+    ///
+    /// The record's `TimeZone`, `GMTOffset` and `DST` fields take precedence,
+    /// see [`StationTimeZone::from_record`].
+    #[cfg(feature = "tz")]
+    pub fn time_zone(&self) -> Option<StationTimeZone> {
+        StationTimeZone::from_record(&self.callsign)
+            .or_else(|| self.dxcc.as_ref().and_then(StationTimeZone::from_dxcc))
+    }
+
+    /// Current local time at the station, if its time zone is known
+    #[cfg(feature = "tz")]
+    pub fn local_time_now(&self) -> Option<DateTime<FixedOffset>> {
+        Some(self.time_zone()?.local_time(Utc::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_station_provenance() {
+        let info: CallsignInfo =
+            quick_xml::de::from_str("<Callsign><call>W1AW</call><grid>FN31pr</grid></Callsign>")
+                .unwrap();
+        let station = Station::new(info, None);
+        assert_eq!(station.call(), "W1AW");
+        assert_eq!(station.grid.as_deref(), Some("FN31pr"));
+        assert_eq!(station.provenance.coordinates, Some(CoordinateSource::Grid));
+        assert!(!station.provenance.grid_computed);
+        assert!(!station.provenance.dxcc_resolved);
+
+        let info: CallsignInfo = quick_xml::de::from_str(
+            "<Callsign><call>W1AW</call><lat>41.714775</lat><lon>-72.727260</lon></Callsign>",
+        )
+        .unwrap();
+        let station = Station::new(info, None);
+        assert_eq!(station.grid.as_deref(), Some("FN31pr"));
+        assert_eq!(
+            station.provenance.coordinates,
+            Some(CoordinateSource::Record)
+        );
+        assert!(station.provenance.grid_computed);
+        assert_eq!(station.distance_km_from((41.714775, -72.727260)), Some(0.0));
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn test_time_zone_falls_back_to_dxcc() {
+        let nepal = DxccInfo::builder().dxcc(369).timezone("545").build();
+        let station = Station::new(CallsignInfo::builder().call("9N1AA").build(), Some(nepal));
+        assert_eq!(
+            station.time_zone(),
+            FixedOffset::east_opt(5 * 3600 + 45 * 60).map(StationTimeZone::Fixed)
+        );
+
+        let info = CallsignInfo::builder()
+            .call("9N1AA")
+            .gmt_offset("6")
+            .build();
+        let station = Station::new(info, station.dxcc);
+        assert_eq!(
+            station.time_zone(),
+            FixedOffset::east_opt(6 * 3600).map(StationTimeZone::Fixed)
+        );
+        assert!(station.local_time_now().is_some());
+    }
+}
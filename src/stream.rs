@@ -0,0 +1,301 @@
+//! Streaming DXCC entity list.
+//!
+//! The `dxcc=all` response lists every DXCC entity in one large document.
+//! [`QrzXmlClient::dxcc_entities_stream`] returns a [`DxccEntityStream`] that
+//! parses the body with quick-xml's [`Reader`] as it arrives and yields each
+//! entity as soon as its element is complete. Only the element being read is
+//! held in memory, and dropping the stream stops reading the response.
+
+use crate::charset;
+use crate::client::QrzXmlClient;
+use crate::error::{QrzXmlError, Result};
+use crate::governor::GovernorPermit;
+use crate::types::{DxccInfo, SessionInfo};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// DXCC entities of a `dxcc=all` response, parsed as the body arrives
+///
+/// ```no_run
+/// # async fn example(client: qrz_xml::QrzXmlClient) -> qrz_xml::Result<()> {
+/// let mut entities = client.dxcc_entities_stream().await?;
+/// while let Some(dxcc) = entities.next().await {
+///     let dxcc = dxcc?;
+///     println!("{} {}", dxcc.dxcc, dxcc.name);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct DxccEntityStream<'a> {
+    client: &'a QrzXmlClient,
+    response: reqwest::Response,
+    reader: ListReader,
+    body_done: bool,
+    finished: bool,
+    session_seen: bool,
+    entities_read: usize,
+    _permit: Option<GovernorPermit<'a>>,
+}
+
+impl<'a> DxccEntityStream<'a> {
+    pub(crate) fn new(
+        client: &'a QrzXmlClient,
+        response: reqwest::Response,
+        content_type: Option<String>,
+        permit: Option<GovernorPermit<'a>>,
+    ) -> Self {
+        Self {
+            client,
+            response,
+            reader: ListReader::new(content_type),
+            body_done: false,
+            finished: false,
+            session_seen: false,
+            entities_read: 0,
+            _permit: permit,
+        }
+    }
+
+    /// The next entity, or `None` once the response is exhausted
+    ///
+    /// The session is checked when the `Session` element is reached, so an
+    /// expired session ends the stream with [`QrzXmlError::SessionExpired`];
+    /// the next request logs in again. Nothing is returned after an error.
+    pub async fn next(&mut self) -> Option<Result<DxccInfo>> {
+        if self.finished {
+            return None;
+        }
+        match self.advance().await {
+            Ok(Some(dxcc)) => {
+                self.entities_read += 1;
+                Some(Ok(dxcc))
+            }
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+
+    /// Number of entities returned so far
+    pub fn entities_read(&self) -> usize {
+        self.entities_read
+    }
+
+    async fn advance(&mut self) -> Result<Option<DxccInfo>> {
+        loop {
+            while let Some(element) = self.reader.next_element(self.body_done)? {
+                match element {
+                    Element::Dxcc(dxcc) => return Ok(Some(dxcc)),
+                    Element::Session(session) => {
+                        self.session_seen = true;
+                        self.client.check_session_info(&session).await?;
+                    }
+                }
+            }
+
+            if self.body_done {
+                return if !self.reader.root_closed {
+                    Err(QrzXmlError::unexpected_response(
+                        "DXCC list ended before the closing QRZDatabase tag".to_string(),
+                    ))
+                } else if !self.session_seen {
+                    Err(QrzXmlError::unexpected_response(
+                        "No session data in DXCC list".to_string(),
+                    ))
+                } else {
+                    Ok(None)
+                };
+            }
+            match self.response.chunk().await? {
+                Some(chunk) => self.reader.feed(&chunk),
+                None => self.body_done = true,
+            }
+        }
+    }
+}
+
+/// An element of the `dxcc=all` response
+#[derive(Debug)]
+enum Element {
+    Dxcc(DxccInfo),
+    Session(SessionInfo),
+}
+
+impl Element {
+    /// Deserialize an element by name, `None` for elements of other names
+    fn parse(name: &[u8], xml: &str) -> Result<Option<Self>> {
+        Ok(match name {
+            b"DXCC" => Some(Element::Dxcc(quick_xml::de::from_str(xml)?)),
+            b"Session" => Some(Element::Session(quick_xml::de::from_str(xml)?)),
+            _ => None,
+        })
+    }
+}
+
+/// Incremental reader for the elements directly below `QRZDatabase`
+#[derive(Debug)]
+struct ListReader {
+    content_type: Option<String>,
+    /// Received bytes not yet parsed into an element
+    buffer: Vec<u8>,
+    /// Whether the body is ISO-8859-1, known once the XML declaration or the
+    /// first element arrived
+    latin1: Option<bool>,
+    in_root: bool,
+    root_closed: bool,
+}
+
+impl ListReader {
+    fn new(content_type: Option<String>) -> Self {
+        Self {
+            content_type,
+            buffer: Vec::new(),
+            latin1: None,
+            in_root: false,
+            root_closed: false,
+        }
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// The next complete element, or `None` if more of the body is needed
+    ///
+    /// Elements other than `DXCC` and `Session` are skipped. Incomplete markup
+    /// at the end of the buffer is only an error once `body_done` is set.
+    fn next_element(&mut self, body_done: bool) -> Result<Option<Element>> {
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+        let latin1 = match self.latin1 {
+            Some(latin1) => latin1,
+            None if body_done || charset::declaration_complete(&self.buffer) => {
+                *self.latin1.insert(charset::declares_latin1(
+                    &self.buffer,
+                    self.content_type.as_deref(),
+                ))
+            }
+            None => return Ok(None),
+        };
+
+        let mut reader = Reader::from_reader(self.buffer.as_slice());
+        // The root start tag may have been consumed by an earlier reader
+        reader.config_mut().allow_unmatched_ends = true;
+        let mut consumed = 0;
+        let element = loop {
+            let incomplete = |e: quick_xml::Error| {
+                if body_done {
+                    Err(QrzXmlError::XmlParsing(quick_xml::DeError::from(e)))
+                } else {
+                    Ok(None)
+                }
+            };
+            let event = match reader.read_event() {
+                Ok(event) => event,
+                Err(e) => break incomplete(e),
+            };
+            let is_empty = matches!(event, Event::Empty(_));
+
+            match event {
+                Event::Eof => break Ok(None),
+                Event::Start(_) if !self.in_root => self.in_root = true,
+                Event::Start(start) | Event::Empty(start) => {
+                    if !is_empty {
+                        if let Err(e) = reader.read_to_end(start.name()) {
+                            break incomplete(e);
+                        }
+                    }
+                    let end = reader.buffer_position() as usize;
+                    let xml = charset::decode(&self.buffer[consumed..end], latin1);
+                    consumed = end;
+                    if let Some(element) = Element::parse(start.local_name().as_ref(), &xml)? {
+                        break Ok(Some(element));
+                    }
+                    continue;
+                }
+                Event::End(_) if self.in_root => {
+                    self.in_root = false;
+                    self.root_closed = true;
+                }
+                _ => {}
+            }
+            consumed = reader.buffer_position() as usize;
+        };
+
+        self.buffer.drain(..consumed);
+        element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(reader: &mut ListReader, xml: &[u8], chunk_size: usize) -> Vec<Element> {
+        let mut elements = Vec::new();
+        for chunk in xml.chunks(chunk_size) {
+            reader.feed(chunk);
+            while let Some(element) = reader.next_element(false).unwrap() {
+                elements.push(element);
+            }
+        }
+        while let Some(element) = reader.next_element(true).unwrap() {
+            elements.push(element);
+        }
+        elements
+    }
+
+    #[test]
+    fn test_elements_split_across_chunks() {
+        let xml = include_str!("../fixtures/dxcc_all.xml").as_bytes();
+        for chunk_size in [1, 7, 4096] {
+            let mut reader = ListReader::new(None);
+            let elements = read_all(&mut reader, xml, chunk_size);
+            assert_eq!(elements.len(), 341, "chunks of {}", chunk_size);
+            assert!(matches!(&elements[0], Element::Dxcc(dxcc) if dxcc.name == "Canada"));
+            assert!(
+                matches!(&elements[340], Element::Session(session) if session.count == Some(125))
+            );
+            assert!(reader.root_closed);
+            assert!(reader.buffer.len() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_encoding_waits_for_declaration() {
+        let mut reader = ListReader::new(None);
+        reader.feed(b"\n<?x");
+        assert!(reader.next_element(false).unwrap().is_none());
+        assert_eq!(reader.latin1, None);
+        reader.feed(b"ml version=\"1.0\" encoding=\"ISO-8859-1\"?>");
+        assert!(reader.next_element(false).unwrap().is_none());
+        assert_eq!(reader.latin1, Some(true));
+
+        let mut reader = ListReader::new(None);
+        reader.feed(b"<QRZ");
+        assert!(reader.next_element(false).unwrap().is_none());
+        assert_eq!(reader.latin1, Some(false));
+    }
+
+    #[test]
+    fn test_latin1_and_truncated_lists() {
+        let xml = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n<QRZDatabase>\
+            <Extra/><DXCC><dxcc>227</dxcc><name>R\xe9union</name></DXCC><Session/></QRZDatabase>";
+        let mut reader = ListReader::new(None);
+        let elements = read_all(&mut reader, xml, 5);
+        assert_eq!(reader.latin1, Some(true));
+        assert_eq!(elements.len(), 2);
+        assert!(matches!(&elements[0], Element::Dxcc(dxcc) if dxcc.name == "Réunion"));
+
+        let mut reader = ListReader::new(None);
+        reader.feed(b"<QRZDatabase><DXCC><dxcc>1</dxcc><na");
+        assert!(reader.next_element(false).unwrap().is_none());
+        assert!(reader.next_element(true).is_err());
+    }
+}
@@ -0,0 +1,501 @@
+//! Administrative subdivisions (states, provinces, prefectures).
+//!
+//! QRZ stores the primary subdivision of a station in the `state` field for
+//! many entities besides the US. [`CallsignInfo::subdivision`](crate::CallsignInfo::subdivision)
+//! normalizes that value against a per-DXCC table, so award programs such as
+//! WAS, RAC Canadaward or WAJA can be tracked with stable codes. US states
+//! also have an enum of their own, [`UsState`], for exhaustive matching.
+
+use crate::error::{QrzXmlError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// DXCC entity numbers with a subdivision table
+pub mod entities {
+    /// Canada
+    pub const CANADA: u32 = 1;
+    /// Alaska
+    pub const ALASKA: u32 = 6;
+    /// Hawaii
+    pub const HAWAII: u32 = 110;
+    /// Australia
+    pub const AUSTRALIA: u32 = 150;
+    /// United States of America
+    pub const UNITED_STATES: u32 = 291;
+    /// Japan
+    pub const JAPAN: u32 = 339;
+}
+
+/// A primary administrative subdivision of a DXCC entity
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Subdivision {
+    /// DXCC entity the subdivision belongs to
+    pub dxcc: u32,
+    /// Normalized code, e.g. "AZ", "ON" or the WAJA prefecture number "13"
+    pub code: String,
+    /// Full name, if the entity has a subdivision table
+    pub name: Option<&'static str>,
+}
+
+impl Subdivision {
+    /// Resolve a raw `state` value for a DXCC entity
+    ///
+    /// Codes and full names are both accepted, case-insensitively. For entities
+    /// with a table, unknown values yield `None`; for other entities the value
+    /// is kept as an uppercase code without a name.
+    pub fn resolve(dxcc: u32, value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        match table(dxcc) {
+            Some(table) => table
+                .iter()
+                .find(|(code, name)| {
+                    code.eq_ignore_ascii_case(value) || name.eq_ignore_ascii_case(value)
+                })
+                .map(|(code, name)| Subdivision {
+                    dxcc,
+                    code: code.to_string(),
+                    name: Some(name),
+                }),
+            None => Some(Subdivision {
+                dxcc,
+                code: value.to_uppercase(),
+                name: None,
+            }),
+        }
+    }
+
+    /// All known subdivisions of a DXCC entity, e.g. to show award progress
+    pub fn all(dxcc: u32) -> Vec<Subdivision> {
+        table(dxcc)
+            .unwrap_or_default()
+            .iter()
+            .map(|(code, name)| Subdivision {
+                dxcc,
+                code: code.to_string(),
+                name: Some(name),
+            })
+            .collect()
+    }
+}
+
+/// A US state or the District of Columbia
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "&'static str")]
+pub enum UsState {
+    /// AL
+    Alabama,
+    /// AK
+    Alaska,
+    /// AZ
+    Arizona,
+    /// AR
+    Arkansas,
+    /// CA
+    California,
+    /// CO
+    Colorado,
+    /// CT
+    Connecticut,
+    /// DE
+    Delaware,
+    /// DC
+    DistrictOfColumbia,
+    /// FL
+    Florida,
+    /// GA
+    Georgia,
+    /// HI
+    Hawaii,
+    /// ID
+    Idaho,
+    /// IL
+    Illinois,
+    /// IN
+    Indiana,
+    /// IA
+    Iowa,
+    /// KS
+    Kansas,
+    /// KY
+    Kentucky,
+    /// LA
+    Louisiana,
+    /// ME
+    Maine,
+    /// MD
+    Maryland,
+    /// MA
+    Massachusetts,
+    /// MI
+    Michigan,
+    /// MN
+    Minnesota,
+    /// MS
+    Mississippi,
+    /// MO
+    Missouri,
+    /// MT
+    Montana,
+    /// NE
+    Nebraska,
+    /// NV
+    Nevada,
+    /// NH
+    NewHampshire,
+    /// NJ
+    NewJersey,
+    /// NM
+    NewMexico,
+    /// NY
+    NewYork,
+    /// NC
+    NorthCarolina,
+    /// ND
+    NorthDakota,
+    /// OH
+    Ohio,
+    /// OK
+    Oklahoma,
+    /// OR
+    Oregon,
+    /// PA
+    Pennsylvania,
+    /// RI
+    RhodeIsland,
+    /// SC
+    SouthCarolina,
+    /// SD
+    SouthDakota,
+    /// TN
+    Tennessee,
+    /// TX
+    Texas,
+    /// UT
+    Utah,
+    /// VT
+    Vermont,
+    /// VA
+    Virginia,
+    /// WA
+    Washington,
+    /// WV
+    WestVirginia,
+    /// WI
+    Wisconsin,
+    /// WY
+    Wyoming,
+}
+
+impl UsState {
+    /// All states and the District of Columbia, in the order of their names
+    pub const ALL: [UsState; 51] = [
+        UsState::Alabama,
+        UsState::Alaska,
+        UsState::Arizona,
+        UsState::Arkansas,
+        UsState::California,
+        UsState::Colorado,
+        UsState::Connecticut,
+        UsState::Delaware,
+        UsState::DistrictOfColumbia,
+        UsState::Florida,
+        UsState::Georgia,
+        UsState::Hawaii,
+        UsState::Idaho,
+        UsState::Illinois,
+        UsState::Indiana,
+        UsState::Iowa,
+        UsState::Kansas,
+        UsState::Kentucky,
+        UsState::Louisiana,
+        UsState::Maine,
+        UsState::Maryland,
+        UsState::Massachusetts,
+        UsState::Michigan,
+        UsState::Minnesota,
+        UsState::Mississippi,
+        UsState::Missouri,
+        UsState::Montana,
+        UsState::Nebraska,
+        UsState::Nevada,
+        UsState::NewHampshire,
+        UsState::NewJersey,
+        UsState::NewMexico,
+        UsState::NewYork,
+        UsState::NorthCarolina,
+        UsState::NorthDakota,
+        UsState::Ohio,
+        UsState::Oklahoma,
+        UsState::Oregon,
+        UsState::Pennsylvania,
+        UsState::RhodeIsland,
+        UsState::SouthCarolina,
+        UsState::SouthDakota,
+        UsState::Tennessee,
+        UsState::Texas,
+        UsState::Utah,
+        UsState::Vermont,
+        UsState::Virginia,
+        UsState::Washington,
+        UsState::WestVirginia,
+        UsState::Wisconsin,
+        UsState::Wyoming,
+    ];
+
+    /// Two-letter postal abbreviation, e.g. "AZ"
+    pub fn abbreviation(&self) -> &'static str {
+        UNITED_STATES[*self as usize].0
+    }
+
+    /// Full name, e.g. "Arizona"
+    pub fn name(&self) -> &'static str {
+        UNITED_STATES[*self as usize].1
+    }
+
+    /// DXCC entity the state counts for; Alaska and Hawaii are entities of their own
+    pub fn dxcc(&self) -> u32 {
+        match self {
+            UsState::Alaska => entities::ALASKA,
+            UsState::Hawaii => entities::HAWAII,
+            _ => entities::UNITED_STATES,
+        }
+    }
+
+    /// The state counts towards Worked All States, which excludes DC
+    pub fn counts_for_was(&self) -> bool {
+        *self != UsState::DistrictOfColumbia
+    }
+}
+
+impl FromStr for UsState {
+    type Err = QrzXmlError;
+
+    /// Parse an abbreviation or full name, ignoring case
+    fn from_str(s: &str) -> Result<Self> {
+        let value = s.trim();
+        UNITED_STATES
+            .iter()
+            .position(|(code, name)| {
+                code.eq_ignore_ascii_case(value) || name.eq_ignore_ascii_case(value)
+            })
+            .map(|index| UsState::ALL[index])
+            .ok_or_else(|| QrzXmlError::invalid_input(format!("Unknown US state: {}", s)))
+    }
+}
+
+impl TryFrom<String> for UsState {
+    type Error = QrzXmlError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<UsState> for &'static str {
+    fn from(state: UsState) -> Self {
+        state.abbreviation()
+    }
+}
+
+impl fmt::Display for UsState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.abbreviation())
+    }
+}
+
+fn table(dxcc: u32) -> Option<&'static [(&'static str, &'static str)]> {
+    match dxcc {
+        entities::CANADA => Some(CANADA),
+        entities::AUSTRALIA => Some(AUSTRALIA),
+        entities::UNITED_STATES => Some(UNITED_STATES),
+        entities::JAPAN => Some(JAPAN),
+        _ => None,
+    }
+}
+
+const CANADA: &[(&str, &str)] = &[
+    ("AB", "Alberta"),
+    ("BC", "British Columbia"),
+    ("MB", "Manitoba"),
+    ("NB", "New Brunswick"),
+    ("NL", "Newfoundland and Labrador"),
+    ("NS", "Nova Scotia"),
+    ("NT", "Northwest Territories"),
+    ("NU", "Nunavut"),
+    ("ON", "Ontario"),
+    ("PE", "Prince Edward Island"),
+    ("QC", "Quebec"),
+    ("SK", "Saskatchewan"),
+    ("YT", "Yukon"),
+];
+
+const AUSTRALIA: &[(&str, &str)] = &[
+    ("ACT", "Australian Capital Territory"),
+    ("NSW", "New South Wales"),
+    ("NT", "Northern Territory"),
+    ("QLD", "Queensland"),
+    ("SA", "South Australia"),
+    ("TAS", "Tasmania"),
+    ("VIC", "Victoria"),
+    ("WA", "Western Australia"),
+];
+
+const UNITED_STATES: &[(&str, &str)] = &[
+    ("AL", "Alabama"),
+    ("AK", "Alaska"),
+    ("AZ", "Arizona"),
+    ("AR", "Arkansas"),
+    ("CA", "California"),
+    ("CO", "Colorado"),
+    ("CT", "Connecticut"),
+    ("DE", "Delaware"),
+    ("DC", "District of Columbia"),
+    ("FL", "Florida"),
+    ("GA", "Georgia"),
+    ("HI", "Hawaii"),
+    ("ID", "Idaho"),
+    ("IL", "Illinois"),
+    ("IN", "Indiana"),
+    ("IA", "Iowa"),
+    ("KS", "Kansas"),
+    ("KY", "Kentucky"),
+    ("LA", "Louisiana"),
+    ("ME", "Maine"),
+    ("MD", "Maryland"),
+    ("MA", "Massachusetts"),
+    ("MI", "Michigan"),
+    ("MN", "Minnesota"),
+    ("MS", "Mississippi"),
+    ("MO", "Missouri"),
+    ("MT", "Montana"),
+    ("NE", "Nebraska"),
+    ("NV", "Nevada"),
+    ("NH", "New Hampshire"),
+    ("NJ", "New Jersey"),
+    ("NM", "New Mexico"),
+    ("NY", "New York"),
+    ("NC", "North Carolina"),
+    ("ND", "North Dakota"),
+    ("OH", "Ohio"),
+    ("OK", "Oklahoma"),
+    ("OR", "Oregon"),
+    ("PA", "Pennsylvania"),
+    ("RI", "Rhode Island"),
+    ("SC", "South Carolina"),
+    ("SD", "South Dakota"),
+    ("TN", "Tennessee"),
+    ("TX", "Texas"),
+    ("UT", "Utah"),
+    ("VT", "Vermont"),
+    ("VA", "Virginia"),
+    ("WA", "Washington"),
+    ("WV", "West Virginia"),
+    ("WI", "Wisconsin"),
+    ("WY", "Wyoming"),
+];
+
+/// Prefectures keyed by their WAJA number
+const JAPAN: &[(&str, &str)] = &[
+    ("01", "Hokkaido"),
+    ("02", "Aomori"),
+    ("03", "Iwate"),
+    ("04", "Akita"),
+    ("05", "Yamagata"),
+    ("06", "Miyagi"),
+    ("07", "Fukushima"),
+    ("08", "Niigata"),
+    ("09", "Nagano"),
+    ("10", "Tokyo"),
+    ("11", "Kanagawa"),
+    ("12", "Chiba"),
+    ("13", "Saitama"),
+    ("14", "Ibaraki"),
+    ("15", "Tochigi"),
+    ("16", "Gunma"),
+    ("17", "Yamanashi"),
+    ("18", "Shizuoka"),
+    ("19", "Gifu"),
+    ("20", "Aichi"),
+    ("21", "Mie"),
+    ("22", "Kyoto"),
+    ("23", "Shiga"),
+    ("24", "Nara"),
+    ("25", "Osaka"),
+    ("26", "Wakayama"),
+    ("27", "Hyogo"),
+    ("28", "Toyama"),
+    ("29", "Fukui"),
+    ("30", "Ishikawa"),
+    ("31", "Okayama"),
+    ("32", "Shimane"),
+    ("33", "Yamaguchi"),
+    ("34", "Tottori"),
+    ("35", "Hiroshima"),
+    ("36", "Kagawa"),
+    ("37", "Tokushima"),
+    ("38", "Ehime"),
+    ("39", "Kochi"),
+    ("40", "Fukuoka"),
+    ("41", "Saga"),
+    ("42", "Nagasaki"),
+    ("43", "Kumamoto"),
+    ("44", "Oita"),
+    ("45", "Miyazaki"),
+    ("46", "Kagoshima"),
+    ("47", "Okinawa"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_subdivision() {
+        let ontario = Subdivision::resolve(entities::CANADA, "on").unwrap();
+        assert_eq!(ontario.code, "ON");
+        assert_eq!(ontario.name, Some("Ontario"));
+
+        let tokyo = Subdivision::resolve(entities::JAPAN, "Tokyo").unwrap();
+        assert_eq!(tokyo.code, "10");
+
+        assert_eq!(Subdivision::resolve(entities::UNITED_STATES, "XX"), None);
+        assert_eq!(
+            Subdivision::resolve(230, "nrw"),
+            Some(Subdivision {
+                dxcc: 230,
+                code: "NRW".to_string(),
+                name: None
+            })
+        );
+
+        assert_eq!(Subdivision::all(entities::UNITED_STATES).len(), 51);
+        assert_eq!(Subdivision::all(entities::JAPAN).len(), 47);
+        assert!(Subdivision::all(230).is_empty());
+    }
+
+    #[test]
+    fn test_us_state() {
+        assert_eq!("az".parse::<UsState>().unwrap(), UsState::Arizona);
+        assert_eq!(
+            "district of columbia".parse::<UsState>().unwrap(),
+            UsState::DistrictOfColumbia
+        );
+        assert!("ON".parse::<UsState>().is_err());
+
+        for state in UsState::ALL {
+            assert_eq!(state.abbreviation().parse::<UsState>().unwrap(), state);
+            assert_eq!(state.name().parse::<UsState>().unwrap(), state);
+        }
+        assert_eq!(
+            UsState::ALL.iter().filter(|s| s.counts_for_was()).count(),
+            50
+        );
+        assert_eq!(UsState::Hawaii.dxcc(), entities::HAWAII);
+        assert_eq!(serde_json::to_string(&UsState::NewYork).unwrap(), "\"NY\"");
+    }
+}
@@ -0,0 +1,221 @@
+//! Rollup summaries of lookup results by DXCC entity, continent and CQ zone.
+//!
+//! These are intended for after-action reports of DXpeditions and special event
+//! stations, where a log of worked callsigns is looked up and then summarized.
+
+use crate::types::{CallsignInfo, DxccInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Fields a record must expose to be included in an entity summary
+pub trait EntityRecord {
+    /// DXCC entity number
+    fn dxcc_entity(&self) -> Option<u32>;
+
+    /// 2-letter continent designator
+    fn continent(&self) -> Option<&str>;
+
+    /// CQ zone
+    fn cq_zone(&self) -> Option<u32>;
+}
+
+impl EntityRecord for CallsignInfo {
+    fn dxcc_entity(&self) -> Option<u32> {
+        self.dxcc
+    }
+
+    /// Callsign records carry no continent designator, so it comes from the
+    /// bundled entity list with the `offline-dxcc` feature and is `None` without
+    fn continent(&self) -> Option<&str> {
+        #[cfg(feature = "offline-dxcc")]
+        {
+            crate::offline::entity(self.dxcc?)?.continent.as_deref()
+        }
+        #[cfg(not(feature = "offline-dxcc"))]
+        {
+            None
+        }
+    }
+
+    fn cq_zone(&self) -> Option<u32> {
+        self.cqzone
+    }
+}
+
+impl EntityRecord for DxccInfo {
+    fn dxcc_entity(&self) -> Option<u32> {
+        Some(self.dxcc)
+    }
+
+    fn continent(&self) -> Option<&str> {
+        self.continent.as_deref()
+    }
+
+    fn cq_zone(&self) -> Option<u32> {
+        self.cqzone
+    }
+}
+
+/// A single row in a rollup: a key, how often it occurred and its share of the total
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bucket<K> {
+    /// The grouping key (entity number, continent, zone)
+    pub key: K,
+    /// Number of records with this key
+    pub count: usize,
+    /// Share of all summarized records, from 0.0 to 100.0
+    pub percentage: f64,
+}
+
+/// Counts and percentages per DXCC entity, continent and CQ zone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySummary {
+    /// Total number of records summarized
+    pub total: usize,
+    /// Breakdown by DXCC entity number
+    pub by_dxcc: Vec<Bucket<u32>>,
+    /// Breakdown by continent designator
+    pub by_continent: Vec<Bucket<String>>,
+    /// Breakdown by CQ zone
+    pub by_cq_zone: Vec<Bucket<u32>>,
+}
+
+impl EntitySummary {
+    /// Number of distinct DXCC entities
+    pub fn entity_count(&self) -> usize {
+        self.by_dxcc.len()
+    }
+}
+
+/// Summarize results per DXCC entity, continent and CQ zone.
+///
+/// Buckets are sorted by descending count, ties broken by key. Records missing
+/// a field are left out of that breakdown but still count towards `total`, so
+/// percentages within a breakdown may add up to less than 100.
+pub fn by_entity<'a, R, I>(results: I) -> EntitySummary
+where
+    R: EntityRecord + 'a,
+    I: IntoIterator<Item = &'a R>,
+{
+    let mut total = 0;
+    let mut dxcc = BTreeMap::new();
+    let mut continent = BTreeMap::new();
+    let mut cq_zone = BTreeMap::new();
+
+    for record in results {
+        total += 1;
+        if let Some(entity) = record.dxcc_entity() {
+            *dxcc.entry(entity).or_insert(0) += 1;
+        }
+        if let Some(cont) = record.continent() {
+            *continent.entry(cont.to_ascii_uppercase()).or_insert(0) += 1;
+        }
+        if let Some(zone) = record.cq_zone() {
+            *cq_zone.entry(zone).or_insert(0) += 1;
+        }
+    }
+
+    EntitySummary {
+        total,
+        by_dxcc: buckets(dxcc, total),
+        by_continent: buckets(continent, total),
+        by_cq_zone: buckets(cq_zone, total),
+    }
+}
+
+fn buckets<K: Ord>(counts: BTreeMap<K, usize>, total: usize) -> Vec<Bucket<K>> {
+    let mut buckets: Vec<Bucket<K>> = counts
+        .into_iter()
+        .map(|(key, count)| Bucket {
+            key,
+            count,
+            percentage: if total == 0 {
+                0.0
+            } else {
+                count as f64 * 100.0 / total as f64
+            },
+        })
+        .collect();
+
+    // BTreeMap iteration is already key-ordered, so a stable sort keeps ties by key
+    buckets.sort_by_key(|b| std::cmp::Reverse(b.count));
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_entity_counts() {
+        let records = vec![
+            CallsignInfo {
+                call: "W1AW".to_string(),
+                dxcc: Some(291),
+                cqzone: Some(5),
+                ..Default::default()
+            },
+            CallsignInfo {
+                call: "AA7BQ".to_string(),
+                dxcc: Some(291),
+                cqzone: Some(3),
+                ..Default::default()
+            },
+            CallsignInfo {
+                call: "G4ABC".to_string(),
+                dxcc: Some(223),
+                cqzone: Some(14),
+                ..Default::default()
+            },
+            CallsignInfo {
+                call: "X0XX".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let summary = by_entity(&records);
+        assert_eq!(summary.total, 4);
+        assert_eq!(summary.entity_count(), 2);
+        assert_eq!(summary.by_dxcc[0].key, 291);
+        assert_eq!(summary.by_dxcc[0].count, 2);
+        assert_eq!(summary.by_dxcc[0].percentage, 50.0);
+        assert_eq!(summary.by_cq_zone.len(), 3);
+
+        #[cfg(feature = "offline-dxcc")]
+        {
+            let continents: Vec<(&str, usize)> = summary
+                .by_continent
+                .iter()
+                .map(|b| (b.key.as_str(), b.count))
+                .collect();
+            assert_eq!(continents, vec![("NA", 2), ("EU", 1)]);
+        }
+        #[cfg(not(feature = "offline-dxcc"))]
+        assert!(summary.by_continent.is_empty());
+    }
+
+    #[test]
+    fn test_summary_serialization() {
+        let entities = vec![
+            DxccInfo {
+                dxcc: 291,
+                continent: Some("NA".to_string()),
+                ..Default::default()
+            },
+            DxccInfo {
+                dxcc: 1,
+                continent: Some("na".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let summary = by_entity(&entities);
+        assert_eq!(summary.by_continent[0].key, "NA");
+        assert_eq!(summary.by_continent[0].count, 2);
+
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(json.contains("\"by_continent\""));
+        let parsed: EntitySummary = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, summary);
+    }
+}
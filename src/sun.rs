@@ -0,0 +1,112 @@
+//! Sunrise and sunset.
+//!
+//! Grey-line propagation peaks around sunrise and sunset at either end of a
+//! path. [`SunTimes::at`] computes the times for a location and date with the
+//! [sunrise equation](https://en.wikipedia.org/wiki/Sunrise_equation), which
+//! is accurate to a minute or two outside the polar regions.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Julian date of the J2000 epoch
+const J2000: f64 = 2_451_545.0;
+
+/// Julian date of the Unix epoch
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+/// Sun altitude at sunrise and sunset in degrees, allowing for refraction
+/// and the size of the solar disc
+const HORIZON_DEGREES: f64 = -0.833;
+
+/// Obliquity of the ecliptic in degrees
+const OBLIQUITY_DEGREES: f64 = 23.4397;
+
+/// Sunrise, sunset and solar noon at a location on one day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SunTimes {
+    /// Sunrise, `None` during polar day or night
+    pub sunrise: Option<DateTime<Utc>>,
+    /// Sunset, `None` during polar day or night
+    pub sunset: Option<DateTime<Utc>>,
+    /// Solar noon, when the sun is highest
+    pub solar_noon: DateTime<Utc>,
+    /// The sun stays above the horizon all day
+    pub polar_day: bool,
+}
+
+impl SunTimes {
+    /// Sun times at a (lat, lon) location on a date
+    pub fn at((lat, lon): (f64, f64), date: NaiveDate) -> Self {
+        let days = date.signed_duration_since(NaiveDate::from_ymd_opt(2000, 1, 1).unwrap());
+        let mean_solar_time = days.num_days() as f64 + 0.0008 - lon / 360.0;
+
+        let anomaly = (357.5291 + 0.98560028 * mean_solar_time).rem_euclid(360.0);
+        let m = anomaly.to_radians();
+        let center = 1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+        let longitude = (anomaly + center + 180.0 + 102.9372)
+            .rem_euclid(360.0)
+            .to_radians();
+        let transit = J2000 + mean_solar_time + 0.0053 * m.sin() - 0.0069 * (2.0 * longitude).sin();
+
+        let declination = (longitude.sin() * OBLIQUITY_DEGREES.to_radians().sin()).asin();
+        let lat = lat.to_radians();
+        let cos_hour_angle = (HORIZON_DEGREES.to_radians().sin() - lat.sin() * declination.sin())
+            / (lat.cos() * declination.cos());
+
+        let (sunrise, sunset) = if (-1.0..=1.0).contains(&cos_hour_angle) {
+            let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+            (
+                julian_to_utc(transit - half_day),
+                julian_to_utc(transit + half_day),
+            )
+        } else {
+            (None, None)
+        };
+
+        Self {
+            sunrise,
+            sunset,
+            solar_noon: julian_to_utc(transit).unwrap_or_default(),
+            polar_day: cos_hour_angle < -1.0,
+        }
+    }
+}
+
+fn julian_to_utc(julian: f64) -> Option<DateTime<Utc>> {
+    let millis = ((julian - UNIX_EPOCH_JD) * 86_400_000.0).round() as i64;
+    DateTime::from_timestamp_millis(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes_from(time: Option<DateTime<Utc>>, expected: &str) -> i64 {
+        let expected: DateTime<Utc> = expected.parse().unwrap();
+        (time.unwrap() - expected).num_minutes().abs()
+    }
+
+    #[test]
+    fn test_greenwich_solstice() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let sun = SunTimes::at((51.4769, 0.0), date);
+        assert!(minutes_from(sun.sunrise, "2024-06-21T03:43:00Z") <= 2);
+        assert!(minutes_from(sun.sunset, "2024-06-21T20:21:00Z") <= 2);
+        assert!(minutes_from(Some(sun.solar_noon), "2024-06-21T12:02:00Z") <= 2);
+        assert!(!sun.polar_day);
+    }
+
+    #[test]
+    fn test_western_longitude_and_polar_regions() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let newington = SunTimes::at((41.714775, -72.72726), date);
+        assert!(minutes_from(newington.sunrise, "2024-01-15T12:16:00Z") <= 3);
+        assert!(minutes_from(newington.sunset, "2024-01-15T21:46:00Z") <= 3);
+
+        let svalbard = SunTimes::at((78.22, 15.65), date);
+        assert_eq!((svalbard.sunrise, svalbard.sunset), (None, None));
+        assert!(!svalbard.polar_day);
+
+        let june = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        assert!(SunTimes::at((78.22, 15.65), june).polar_day);
+    }
+}
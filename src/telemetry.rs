@@ -0,0 +1,269 @@
+//! Tracing spans and optional metrics instrumentation.
+//!
+//! With the `tracing` feature (on by default), every lookup runs inside a `qrz.lookup` span, every HTTP round trip inside a
+//! `qrz.request` span carrying OpenTelemetry semantic HTTP attributes
+//! (`http.request.method`, `url.full`, `server.address`,
+//! `http.response.status_code`), and re-authentication after session expiry
+//! inside a `qrz.reauthenticate` span nested under the lookup that triggered it.
+//! Session lifecycle steps (logins, session reuse, expiry, forced
+//! re-authentication) are logged as events with the `qrz_xml::session` target
+//! and a `qrz.session.event` field, so log pipelines can filter on them.
+//! With a `tracing-opentelemetry` layer installed these show up directly in your
+//! traces. The `otel` feature additionally injects the current trace context
+//! into outgoing request headers using the globally configured propagator, so
+//! caching proxies in front of QRZ can join the trace.
+//!
+//! When the `metrics` feature is enabled, the client emits the following through
+//! the [`metrics`](https://docs.rs/metrics) facade. Install any compatible
+//! recorder (Prometheus, StatsD, ...) to collect them.
+//!
+//! - `qrz_lookups_total{kind, outcome}` - counter of lookups by kind
+//!   (`callsign`, `biography`, `dxcc`) and outcome (`success`, `not_found`, `error`)
+//! - `qrz_request_duration_seconds{outcome}` - histogram of HTTP round-trip
+//!   durations, failed attempts included, by outcome (`success`, `error`)
+//! - `qrz_reauth_total` - counter of re-authentications after session expiry
+//! - `qrz_session_events_total{event}` - counter of session lifecycle events
+//!   (`login_success`, `login_failure`, `session_reused`, `session_expired`,
+//!   `forced_reauth`), for alerting on re-authentication storms
+//!
+//! Without the feature the metric functions here compile to no-ops.
+
+use crate::error::{QrzXmlError, Result};
+use reqwest::header::HeaderMap;
+use std::future::Future;
+use std::time::Duration;
+#[cfg(feature = "tracing")]
+use tracing::{field, info_span, Instrument, Span};
+
+/// Run a lookup future inside a lookup span and record its outcome
+pub(crate) async fn instrument_lookup<T>(
+    kind: &'static str,
+    lookup: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    #[cfg(feature = "tracing")]
+    let (span, result) = {
+        let span = info_span!(
+            "qrz.lookup",
+            otel.name = %format_args!("qrz.lookup {}", kind),
+            qrz.lookup.kind = kind,
+            qrz.lookup.outcome = field::Empty,
+        );
+        let result = lookup.instrument(span.clone()).await;
+        (span, result)
+    };
+    #[cfg(not(feature = "tracing"))]
+    let result = lookup.await;
+
+    let outcome = outcome(&result);
+    #[cfg(feature = "tracing")]
+    span.record("qrz.lookup.outcome", outcome);
+    record_lookup(kind, outcome);
+    result
+}
+
+/// Run a single HTTP round trip inside a request span; `url` must not contain the query string
+#[cfg(feature = "tracing")]
+pub(crate) async fn in_request_span<T>(url: &str, request: impl Future<Output = T>) -> T {
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let span = info_span!(
+        "qrz.request",
+        otel.name = "GET",
+        otel.kind = "client",
+        otel.status_code = field::Empty,
+        http.request.method = "GET",
+        url.full = url,
+        server.address = %host,
+        http.response.status_code = field::Empty,
+    );
+    request.instrument(span).await
+}
+
+/// Run a single HTTP round trip inside a request span; `url` must not contain the query string
+#[cfg(not(feature = "tracing"))]
+pub(crate) async fn in_request_span<T>(_url: &str, request: impl Future<Output = T>) -> T {
+    request.await
+}
+
+/// Record the HTTP status code on the current request span
+#[cfg(feature = "tracing")]
+pub(crate) fn record_status(status: u16) {
+    let span = Span::current();
+    span.record("http.response.status_code", status);
+    if status >= 400 {
+        span.record("otel.status_code", "ERROR");
+    }
+}
+
+/// Record the HTTP status code on the current request span
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn record_status(_status: u16) {}
+
+/// Run re-authentication after the session expired mid-lookup inside its own span
+#[cfg(feature = "tracing")]
+pub(crate) async fn in_reauth_span<T>(reauth: impl Future<Output = T>) -> T {
+    reauth
+        .instrument(info_span!(
+            "qrz.reauthenticate",
+            otel.name = "qrz.reauthenticate"
+        ))
+        .await
+}
+
+/// Run re-authentication after the session expired mid-lookup inside its own span
+#[cfg(not(feature = "tracing"))]
+pub(crate) async fn in_reauth_span<T>(reauth: impl Future<Output = T>) -> T {
+    reauth.await
+}
+
+/// Trace context headers for the current span, if propagation is enabled
+#[cfg(feature = "otel")]
+pub(crate) fn propagation_headers() -> Option<HeaderMap> {
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+    impl opentelemetry::propagation::Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                reqwest::header::HeaderName::from_bytes(key.as_bytes()),
+                reqwest::header::HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    let context = Span::current().context();
+    let mut headers = HeaderMap::new();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers));
+    });
+
+    if headers.is_empty() {
+        None
+    } else {
+        Some(headers)
+    }
+}
+
+/// Trace context headers for the current span, if propagation is enabled
+#[cfg(not(feature = "otel"))]
+pub(crate) fn propagation_headers() -> Option<HeaderMap> {
+    None
+}
+
+fn outcome<T>(result: &Result<T>) -> &'static str {
+    match result {
+        Ok(_) => "success",
+        Err(QrzXmlError::CallsignNotFound { .. } | QrzXmlError::DxccNotFound { .. }) => "not_found",
+        Err(_) => "error",
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn record_lookup(kind: &'static str, outcome: &'static str) {
+    metrics::counter!("qrz_lookups_total", "kind" => kind, "outcome" => outcome).increment(1);
+}
+
+#[cfg(not(feature = "metrics"))]
+fn record_lookup(_kind: &'static str, _outcome: &'static str) {}
+
+/// Record the duration of a single HTTP request, whether it succeeded or not
+#[cfg(feature = "metrics")]
+pub(crate) fn record_request_duration<T>(duration: Duration, result: &Result<T>) {
+    metrics::histogram!("qrz_request_duration_seconds", "outcome" => outcome(result))
+        .record(duration.as_secs_f64());
+}
+
+/// Record the duration of a single HTTP request, whether it succeeded or not
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_request_duration<T>(_duration: Duration, _result: &Result<T>) {}
+
+/// Record a re-authentication caused by an expired session
+#[cfg(feature = "metrics")]
+pub(crate) fn record_reauth() {
+    metrics::counter!("qrz_reauth_total").increment(1);
+}
+
+/// Record a re-authentication caused by an expired session
+#[cfg(not(feature = "metrics"))]
+pub(crate) fn record_reauth() {}
+
+/// A step in the life of a QRZ session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SessionEvent {
+    /// A login returned a new session
+    LoginSucceeded,
+    /// A login failed
+    LoginFailed,
+    /// A stored or previous session was picked up instead of logging in
+    Reused,
+    /// QRZ rejected the session in use
+    Expired,
+    /// The application asked for a new session
+    ForcedReauth,
+}
+
+impl SessionEvent {
+    fn label(self) -> &'static str {
+        match self {
+            SessionEvent::LoginSucceeded => "login_success",
+            SessionEvent::LoginFailed => "login_failure",
+            SessionEvent::Reused => "session_reused",
+            SessionEvent::Expired => "session_expired",
+            SessionEvent::ForcedReauth => "forced_reauth",
+        }
+    }
+}
+
+/// Log a session lifecycle event and count it
+pub(crate) fn record_session_event(event: SessionEvent) {
+    let label = event.label();
+    #[cfg(feature = "tracing")]
+    match event {
+        SessionEvent::LoginFailed | SessionEvent::Expired => tracing::event!(
+            target: "qrz_xml::session",
+            tracing::Level::WARN,
+            qrz.session.event = label,
+            "QRZ session event: {}",
+            label
+        ),
+        _ => tracing::event!(
+            target: "qrz_xml::session",
+            tracing::Level::INFO,
+            qrz.session.event = label,
+            "QRZ session event: {}",
+            label
+        ),
+    }
+    #[cfg(feature = "metrics")]
+    metrics::counter!("qrz_session_events_total", "event" => label).increment(1);
+    #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+    let _ = label;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_labels() {
+        assert_eq!(outcome(&Ok(())), "success");
+        assert_eq!(
+            outcome::<()>(&Err(QrzXmlError::callsign_not_found("X"))),
+            "not_found"
+        );
+        assert_eq!(outcome::<()>(&Err(QrzXmlError::SessionExpired)), "error");
+    }
+
+    #[test]
+    fn test_session_event_labels() {
+        assert_eq!(SessionEvent::LoginSucceeded.label(), "login_success");
+        assert_eq!(SessionEvent::ForcedReauth.label(), "forced_reauth");
+        record_session_event(SessionEvent::Reused);
+    }
+}
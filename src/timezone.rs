@@ -0,0 +1,132 @@
+//! Station time zones.
+//!
+//! For US stations QRZ gives a zone name such as `Mountain` in `TimeZone`,
+//! and for most stations a standard offset in `GMTOffset` and whether daylight
+//! saving time is observed in `DST`. [`StationTimeZone::from_record`] combines
+//! them into an IANA zone from `chrono-tz` where possible, so daylight saving
+//! transitions are honored, and a fixed offset otherwise.
+
+use crate::types::{CallsignInfo, DxccInfo};
+use chrono::{DateTime, FixedOffset, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// US zone names used by QRZ, with the IANA zone for stations that observe
+/// daylight saving time and the standard offset in hours
+const US_ZONES: &[(&str, Tz, i32)] = &[
+    ("Atlantic", Tz::America__Puerto_Rico, -4),
+    ("Eastern", Tz::America__New_York, -5),
+    ("Central", Tz::America__Chicago, -6),
+    ("Mountain", Tz::America__Denver, -7),
+    ("Pacific", Tz::America__Los_Angeles, -8),
+    ("Alaska", Tz::America__Anchorage, -9),
+    ("Hawaii", Tz::Pacific__Honolulu, -10),
+    ("Hawaii-Aleutian", Tz::Pacific__Honolulu, -10),
+    ("Samoa", Tz::Pacific__Pago_Pago, -11),
+    ("Chamorro", Tz::Pacific__Guam, 10),
+];
+
+/// Time zone of a station
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StationTimeZone {
+    /// An IANA zone, with its daylight saving rules
+    Named(Tz),
+    /// A fixed offset from UTC
+    Fixed(FixedOffset),
+}
+
+impl StationTimeZone {
+    /// Determine the time zone of a callsign record
+    ///
+    /// A US zone name is mapped to its IANA zone, or to its standard offset
+    /// when the record says daylight saving time is not observed (Arizona
+    /// maps to `America/Phoenix`). Without a known zone name, `GMTOffset`
+    /// is used as a fixed offset.
+    pub fn from_record(info: &CallsignInfo) -> Option<Self> {
+        let zone = info.time_zone.as_deref().map(str::trim).and_then(|name| {
+            US_ZONES
+                .iter()
+                .find(|(zone, _, _)| zone.eq_ignore_ascii_case(name))
+        });
+
+        match (zone, info.observes_dst()) {
+            (Some((name, _, _)), Some(false)) if name.eq_ignore_ascii_case("Mountain") => {
+                Some(StationTimeZone::Named(Tz::America__Phoenix))
+            }
+            (Some((_, _, hours)), Some(false)) => {
+                FixedOffset::east_opt(hours * 3600).map(StationTimeZone::Fixed)
+            }
+            (Some((_, tz, _)), _) => Some(StationTimeZone::Named(*tz)),
+            (None, _) => {
+                let seconds = (info.utc_offset_hours()? * 3600.0).round() as i32;
+                FixedOffset::east_opt(seconds).map(StationTimeZone::Fixed)
+            }
+        }
+    }
+
+    /// Fixed time zone of a DXCC entity, from its `timezone` offset
+    pub fn from_dxcc(dxcc: &DxccInfo) -> Option<Self> {
+        dxcc.utc_offset().map(StationTimeZone::Fixed)
+    }
+
+    /// Offset from UTC in effect at the given instant
+    pub fn offset_at(&self, at: DateTime<Utc>) -> FixedOffset {
+        match self {
+            StationTimeZone::Named(tz) => tz.offset_from_utc_datetime(&at.naive_utc()).fix(),
+            StationTimeZone::Fixed(offset) => *offset,
+        }
+    }
+
+    /// Local time at the given instant
+    pub fn local_time(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        at.with_timezone(&self.offset_at(at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        time_zone: Option<&str>,
+        gmt_offset: Option<&str>,
+        dst: Option<&str>,
+    ) -> CallsignInfo {
+        CallsignInfo {
+            time_zone: time_zone.map(str::to_string),
+            gmt_offset: gmt_offset.map(str::to_string),
+            dst: dst.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_from_record() {
+        let summer = Utc.with_ymd_and_hms(2025, 7, 1, 18, 0, 0).unwrap();
+        let winter = Utc.with_ymd_and_hms(2025, 1, 1, 18, 0, 0).unwrap();
+        let hours = |tz: StationTimeZone, at| tz.offset_at(at).local_minus_utc() / 3600;
+
+        let denver =
+            StationTimeZone::from_record(&record(Some("Mountain"), Some("-7"), Some("Y"))).unwrap();
+        assert_eq!(denver, StationTimeZone::Named(Tz::America__Denver));
+        assert_eq!((hours(denver, summer), hours(denver, winter)), (-6, -7));
+
+        let phoenix =
+            StationTimeZone::from_record(&record(Some("mountain"), None, Some("N"))).unwrap();
+        assert_eq!((hours(phoenix, summer), hours(phoenix, winter)), (-7, -7));
+
+        let indiana =
+            StationTimeZone::from_record(&record(Some("Eastern"), None, Some("N"))).unwrap();
+        assert_eq!(hours(indiana, summer), -5);
+
+        let berlin = StationTimeZone::from_record(&record(None, Some("1"), Some("Y"))).unwrap();
+        assert_eq!(
+            berlin.local_time(winter).to_rfc3339(),
+            "2025-01-01T19:00:00+01:00"
+        );
+
+        assert_eq!(
+            StationTimeZone::from_record(&record(Some("Lunar"), None, None)),
+            None
+        );
+    }
+}
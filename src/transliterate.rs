@@ -0,0 +1,459 @@
+//! Transliteration of names and addresses to ASCII.
+//!
+//! Many QSL label printers only handle ASCII, while QRZ records carry names and
+//! addresses in the operator's own script. [`Transliteration`] converts Latin
+//! letters with diacritics, Cyrillic, Greek and Japanese kana to plain Latin
+//! letters, and [`CallsignInfo::transliterate`](crate::CallsignInfo::transliterate)
+//! applies it to the address fields of a record while keeping the originals.
+//!
+//! The conversion is a character-level approximation meant for mailing
+//! labels, not a linguistic romanization. Kanji and other scripts without a
+//! reading table become the fallback character.
+
+use crate::types::CallsignInfo;
+
+/// Which scripts to transliterate, and how to handle everything else
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transliteration {
+    /// Reduce Latin letters with diacritics to base letters, e.g. "Müller" to "Muller"
+    pub latin_diacritics: bool,
+    /// Transliterate Cyrillic, e.g. "Москва" to "Moskva"
+    pub cyrillic: bool,
+    /// Transliterate Greek, e.g. "Αθήνα" to "Athina"
+    pub greek: bool,
+    /// Romanize hiragana and katakana (Hepburn, without macrons)
+    pub kana: bool,
+    /// Replacement for characters that cannot be transliterated; `None` drops them
+    pub fallback: Option<char>,
+}
+
+impl Default for Transliteration {
+    fn default() -> Self {
+        Self {
+            latin_diacritics: true,
+            cyrillic: true,
+            greek: true,
+            kana: true,
+            fallback: Some('?'),
+        }
+    }
+}
+
+impl Transliteration {
+    /// Convert text to ASCII
+    pub fn apply(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::with_capacity(text.len());
+        // Set by a small tsu, doubling the consonant of the next kana
+        let mut double_next = false;
+
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            i += 1;
+
+            if c.is_ascii() {
+                out.push(c);
+                continue;
+            }
+            if c.is_whitespace() {
+                out.push(' ');
+                continue;
+            }
+            if let Some(ascii) = fullwidth_ascii(c) {
+                out.push(ascii);
+                continue;
+            }
+
+            if self.kana {
+                if let Some(hiragana) = to_hiragana(c) {
+                    match hiragana {
+                        'っ' => double_next = true,
+                        'ー' => {
+                            if let Some(vowel) = out.chars().last().filter(|v| "aeiou".contains(*v))
+                            {
+                                out.push(vowel);
+                            }
+                        }
+                        _ => {
+                            let next = chars.get(i).copied().and_then(to_hiragana);
+                            let (romaji, combined) = kana_syllable(hiragana, next);
+                            if combined {
+                                i += 1;
+                            }
+                            if double_next {
+                                out.push(if romaji.starts_with("ch") {
+                                    't'
+                                } else {
+                                    romaji.chars().next().unwrap_or_default()
+                                });
+                                double_next = false;
+                            }
+                            out.push_str(&romaji);
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            let tables: [(bool, ScriptTable); 3] = [
+                (self.latin_diacritics, latin),
+                (self.cyrillic, cyrillic),
+                (self.greek, greek),
+            ];
+            let mapped = tables
+                .iter()
+                .filter(|(enabled, _)| *enabled)
+                .find_map(|(_, table)| table(lower));
+
+            match mapped {
+                Some(mapped) if c.is_uppercase() => {
+                    let neighbour_upper = chars.get(i).is_some_and(|n| n.is_uppercase())
+                        || (i >= 2 && chars[i - 2].is_uppercase());
+                    if neighbour_upper {
+                        out.push_str(&mapped.to_uppercase());
+                    } else {
+                        let mut letters = mapped.chars();
+                        if let Some(first) = letters.next() {
+                            out.push(first.to_ascii_uppercase());
+                            out.extend(letters);
+                        }
+                    }
+                }
+                Some(mapped) => out.push_str(mapped),
+                None => out.extend(self.fallback),
+            }
+        }
+
+        out
+    }
+}
+
+/// A field value in ASCII together with the original
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransliteratedText {
+    /// The value as stored on QRZ
+    pub original: String,
+    /// The value converted to ASCII
+    pub ascii: String,
+}
+
+impl TransliteratedText {
+    fn new(original: &str, transliteration: &Transliteration) -> Self {
+        Self {
+            original: original.to_string(),
+            ascii: transliteration.apply(original),
+        }
+    }
+
+    /// Check if transliteration changed the value
+    pub fn is_changed(&self) -> bool {
+        self.original != self.ascii
+    }
+}
+
+/// Name and address fields of a callsign record, converted for label printing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransliteratedAddress {
+    /// The callsign
+    pub call: String,
+    /// Full name
+    pub name: Option<TransliteratedText>,
+    /// Attention line
+    pub attn: Option<TransliteratedText>,
+    /// Street address
+    pub addr1: Option<TransliteratedText>,
+    /// City
+    pub addr2: Option<TransliteratedText>,
+    /// State or province
+    pub state: Option<TransliteratedText>,
+    /// Postal code
+    pub zip: Option<TransliteratedText>,
+    /// Country name
+    pub country: Option<TransliteratedText>,
+}
+
+impl TransliteratedAddress {
+    pub(crate) fn new(info: &CallsignInfo, transliteration: &Transliteration) -> Self {
+        let field = |value: Option<&str>| {
+            value
+                .filter(|v| !v.trim().is_empty())
+                .map(|v| TransliteratedText::new(v, transliteration))
+        };
+
+        Self {
+            call: info.call.clone(),
+            name: field(info.full_name().as_deref()),
+            attn: field(info.attn.as_deref()),
+            addr1: field(info.addr1.as_deref()),
+            addr2: field(info.addr2.as_deref()),
+            state: field(info.state.as_deref()),
+            zip: field(info.zip.as_deref()),
+            country: field(info.country.as_deref()),
+        }
+    }
+}
+
+/// Maps a lowercase letter of one script to Latin letters
+type ScriptTable = fn(char) -> Option<&'static str>;
+
+/// ASCII equivalent of a fullwidth form, common in Japanese addresses
+fn fullwidth_ascii(c: char) -> Option<char> {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0),
+        _ => None,
+    }
+}
+
+/// Fold katakana onto hiragana so one table covers both
+fn to_hiragana(c: char) -> Option<char> {
+    match c {
+        '\u{3041}'..='\u{3096}' | 'ー' => Some(c),
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60),
+        _ => None,
+    }
+}
+
+/// Romanize a kana, combining it with a following small ya/yu/yo
+fn kana_syllable(kana: char, next: Option<char>) -> (String, bool) {
+    let base = hiragana(kana).unwrap_or_default();
+    let glide = match next {
+        Some('ゃ') => Some('a'),
+        Some('ゅ') => Some('u'),
+        Some('ょ') => Some('o'),
+        _ => None,
+    };
+
+    match (glide, base.strip_suffix('i')) {
+        (Some(vowel), Some(stem)) if !stem.is_empty() => {
+            let glide = if matches!(stem, "sh" | "ch" | "j") {
+                ""
+            } else {
+                "y"
+            };
+            (format!("{}{}{}", stem, glide, vowel), true)
+        }
+        _ => (base.to_string(), false),
+    }
+}
+
+fn hiragana(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ぁ' | 'あ' => "a",
+        'ぃ' | 'い' | 'ゐ' => "i",
+        'ぅ' | 'う' => "u",
+        'ぇ' | 'え' | 'ゑ' => "e",
+        'ぉ' | 'お' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'が' => "ga",
+        'き' => "ki",
+        'ぎ' => "gi",
+        'く' => "ku",
+        'ぐ' => "gu",
+        'け' | 'ゖ' => "ke",
+        'げ' => "ge",
+        'こ' => "ko",
+        'ご' => "go",
+        'さ' => "sa",
+        'ざ' => "za",
+        'し' => "shi",
+        'じ' | 'ぢ' => "ji",
+        'す' => "su",
+        'ず' | 'づ' => "zu",
+        'せ' => "se",
+        'ぜ' => "ze",
+        'そ' => "so",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'だ' => "da",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'で' => "de",
+        'と' => "to",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ば' => "ba",
+        'ぱ' => "pa",
+        'ひ' => "hi",
+        'び' => "bi",
+        'ぴ' => "pi",
+        'ふ' => "fu",
+        'ぶ' => "bu",
+        'ぷ' => "pu",
+        'へ' => "he",
+        'べ' => "be",
+        'ぺ' => "pe",
+        'ほ' => "ho",
+        'ぼ' => "bo",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'ゃ' | 'や' => "ya",
+        'ゅ' | 'ゆ' => "yu",
+        'ょ' | 'よ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'ゎ' | 'わ' => "wa",
+        'ん' => "n",
+        'ゔ' => "vu",
+        _ => return None,
+    })
+}
+
+fn latin(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+fn cyrillic(c: char) -> Option<&'static str> {
+    Some(match c {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' | 'э' => "e",
+        'ё' => "yo",
+        'є' => "ye",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ї' => "yi",
+        'й' | 'ы' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ў' => "w",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ю' => "yu",
+        'я' => "ya",
+        _ => return None,
+    })
+}
+
+fn greek(c: char) -> Option<&'static str> {
+    Some(match c {
+        'α' | 'ά' => "a",
+        'β' => "v",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' | 'έ' => "e",
+        'ζ' => "z",
+        'η' | 'ή' | 'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+        'θ' => "th",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' | 'ό' | 'ω' | 'ώ' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' => "s",
+        'τ' => "t",
+        'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+        'φ' => "f",
+        'χ' => "ch",
+        'ψ' => "ps",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts() {
+        let t = Transliteration::default();
+        assert_eq!(t.apply("Иван Петров"), "Ivan Petrov");
+        assert_eq!(t.apply("ЖУКОВ"), "ZHUKOV");
+        assert_eq!(t.apply("Жуков"), "Zhukov");
+        assert_eq!(t.apply("Αθήνα"), "Athina");
+        assert_eq!(t.apply("Jürgen Ågren"), "Jurgen Agren");
+        assert_eq!(t.apply("ほっかいどう"), "hokkaidou");
+        assert_eq!(t.apply("キョウト"), "kyouto");
+        assert_eq!(t.apply("マッチャ"), "matcha");
+        assert_eq!(t.apply("東京都１２３"), "???123");
+    }
+
+    #[test]
+    fn test_disabled_scripts_use_fallback() {
+        let t = Transliteration {
+            cyrillic: false,
+            fallback: None,
+            ..Default::default()
+        };
+        assert_eq!(t.apply("Kyiv Київ"), "Kyiv ");
+    }
+
+    #[test]
+    fn test_address_keeps_originals() {
+        let info = CallsignInfo {
+            call: "UA3AAA".to_string(),
+            fname: Some("Иван".to_string()),
+            name: Some("Петров".to_string()),
+            addr2: Some("Москва".to_string()),
+            country: Some("Russia".to_string()),
+            ..Default::default()
+        };
+
+        let address = info.transliterate(&Transliteration::default());
+        let name = address.name.unwrap();
+        assert_eq!(name.original, "Иван Петров");
+        assert_eq!(name.ascii, "Ivan Petrov");
+        assert_eq!(address.addr2.unwrap().ascii, "Moskva");
+        assert!(!address.country.unwrap().is_changed());
+        assert_eq!(address.addr1, None);
+    }
+}
@@ -1,7 +1,36 @@
 //! Type definitions for QRZ API responses.
 
-use serde::{Deserialize, Serialize};
+use crate::builder::{CallsignInfoBuilder, DxccInfoBuilder};
+use crate::error::{QrzXmlError, Result};
+use crate::geo::{DistanceBearing, Location};
+use crate::grid::GridSquare;
+use crate::html::decode_entities;
+use crate::license::LicenseCodes;
+use crate::subdivision::{entities, Subdivision, UsState};
+#[cfg(feature = "sun")]
+use crate::sun::SunTimes;
+#[cfg(feature = "tz")]
+use crate::timezone::StationTimeZone;
+use crate::transliterate::{TransliteratedAddress, Transliteration};
+use crate::zone::{CqZone, ItuZone};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+#[cfg(feature = "geojson")]
+use geojson::{Feature, JsonObject, JsonValue};
+#[cfg(feature = "iso3166")]
+use isocountry::CountryCode;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// Value QRZ returns in `SubExp` for users without a subscription
+pub const NON_SUBSCRIBER: &str = "non-subscriber";
+
+/// Days before expiration from which a license counts as expiring soon
+pub const LICENSE_EXPIRING_SOON_DAYS: i64 = 90;
 
 /// API version enum for specifying which version of the QRZ XML interface to use
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -31,8 +60,31 @@ impl ApiVersion {
     }
 }
 
+impl FromStr for ApiVersion {
+    type Err = QrzXmlError;
+
+    /// Parse `current`, `legacy` or a version number such as `1.34`
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("current") {
+            Ok(ApiVersion::Current)
+        } else if s.eq_ignore_ascii_case("legacy") {
+            Ok(ApiVersion::Legacy)
+        } else if !s.is_empty()
+            && s.split('.')
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        {
+            Ok(ApiVersion::Specific(s.to_string()))
+        } else {
+            Err(QrzXmlError::InvalidApiVersion {
+                version: s.to_string(),
+            })
+        }
+    }
+}
+
 /// Root response container for all QRZ XML responses
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename = "QRZDatabase")]
 pub struct QrzXmlResponse {
     /// API version
@@ -56,8 +108,30 @@ pub struct QrzXmlResponse {
     pub dxcc: Option<DxccInfo>,
 }
 
+/// Root response container for `dxcc=all` queries, which list every entity
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename = "QRZDatabase")]
+pub struct DxccListResponse {
+    /// API version
+    #[serde(rename = "@version")]
+    pub version: Option<String>,
+
+    /// XML namespace
+    #[serde(rename = "@xmlns")]
+    pub xmlns: Option<String>,
+
+    /// Session information (always present)
+    #[serde(rename = "Session")]
+    pub session: SessionInfo,
+
+    /// DXCC entities, in the order QRZ lists them
+    #[serde(rename = "DXCC", default)]
+    pub entities: Vec<DxccInfo>,
+}
+
 /// Session information and status
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SessionInfo {
     /// Session key for authenticated requests
     #[serde(rename = "Key")]
@@ -82,6 +156,10 @@ pub struct SessionInfo {
     /// Error message
     #[serde(rename = "Error")]
     pub error: Option<String>,
+
+    /// Server remark, such as the processing time (`cpu: 0.016s`)
+    #[serde(rename = "Remark")]
+    pub remark: Option<String>,
 }
 
 impl SessionInfo {
@@ -104,13 +182,351 @@ impl SessionInfo {
     pub fn info_message(&self) -> Option<&str> {
         self.message.as_deref()
     }
+
+    /// Subscription status derived from `SubExp` and the informational message
+    pub fn subscription_status(&self) -> SubscriptionStatus {
+        SubscriptionStatus::from_session(self.sub_exp.as_deref(), self.message.as_deref())
+    }
+
+    /// Check if the user has a subscription, if the session reports it
+    pub fn is_subscriber(&self) -> Option<bool> {
+        self.subscription_status().is_subscriber()
+    }
+
+    /// Subscription expiration as a timestamp
+    ///
+    /// Returns `None` for non-subscribers and for dates QRZ formats unexpectedly.
+    #[cfg(feature = "chrono")]
+    pub fn sub_exp_datetime(&self) -> Option<DateTime<Utc>> {
+        self.subscription_status().expires_datetime()
+    }
+
+    /// Server time of the response as a timestamp
+    #[cfg(feature = "chrono")]
+    pub fn gm_time_datetime(&self) -> Option<DateTime<Utc>> {
+        self.gm_time.as_deref().and_then(parse_qrz_datetime)
+    }
+}
+
+/// Whether the QRZ account has a subscription
+///
+/// Non-subscribers only get a limited callsign record, so applications can use
+/// this to explain missing fields instead of treating them as absent data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionStatus {
+    /// The account has a subscription expiring at the given date, as reported by QRZ
+    Subscriber {
+        /// Raw expiration date, e.g. "Wed Jan 1 12:34:03 2025"
+        expires: String,
+    },
+    /// The account has no subscription
+    NonSubscriber,
+    /// QRZ has not reported the subscription status yet
+    Unknown,
+}
+
+impl SubscriptionStatus {
+    pub(crate) fn from_session(sub_exp: Option<&str>, message: Option<&str>) -> Self {
+        match sub_exp.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(sub_exp) if sub_exp.eq_ignore_ascii_case(NON_SUBSCRIBER) => {
+                SubscriptionStatus::NonSubscriber
+            }
+            Some(sub_exp) => SubscriptionStatus::Subscriber {
+                expires: sub_exp.to_string(),
+            },
+            None if message
+                .is_some_and(|m| m.to_ascii_lowercase().contains("subscription is required")) =>
+            {
+                SubscriptionStatus::NonSubscriber
+            }
+            None => SubscriptionStatus::Unknown,
+        }
+    }
+
+    /// Check if the account has a subscription, or `None` if unknown
+    pub fn is_subscriber(&self) -> Option<bool> {
+        match self {
+            SubscriptionStatus::Subscriber { .. } => Some(true),
+            SubscriptionStatus::NonSubscriber => Some(false),
+            SubscriptionStatus::Unknown => None,
+        }
+    }
+
+    /// Subscription expiration as a timestamp, for subscribers
+    #[cfg(feature = "chrono")]
+    pub fn expires_datetime(&self) -> Option<DateTime<Utc>> {
+        match self {
+            SubscriptionStatus::Subscriber { expires } => parse_qrz_datetime(expires),
+            _ => None,
+        }
+    }
+}
+
+/// Oldest age [`CallsignInfo::age`] accepts; older ones come from bogus birth years
+pub const MAX_PLAUSIBLE_AGE: u32 = 120;
+
+/// Standing of a license on a given date, from its effective and expiration dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LicenseStatus {
+    /// The license is in effect and not about to expire
+    Active,
+    /// The license expires within [`LICENSE_EXPIRING_SOON_DAYS`]
+    ExpiringSoon,
+    /// The expiration date has passed
+    Expired,
+    /// The effective date has not been reached yet
+    NotYetEffective,
+    /// The record has no usable expiration date
+    Unknown,
+}
+
+/// Continent, as designated by its two-letter code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Continent {
+    /// AF
+    #[serde(rename = "AF")]
+    Africa,
+    /// AN
+    #[serde(rename = "AN")]
+    Antarctica,
+    /// AS
+    #[serde(rename = "AS")]
+    Asia,
+    /// EU
+    #[serde(rename = "EU")]
+    Europe,
+    /// NA
+    #[serde(rename = "NA")]
+    NorthAmerica,
+    /// OC
+    #[serde(rename = "OC")]
+    Oceania,
+    /// SA
+    #[serde(rename = "SA")]
+    SouthAmerica,
+}
+
+impl Continent {
+    /// All continents, in code order
+    pub const ALL: [Continent; 7] = [
+        Continent::Africa,
+        Continent::Antarctica,
+        Continent::Asia,
+        Continent::Europe,
+        Continent::NorthAmerica,
+        Continent::Oceania,
+        Continent::SouthAmerica,
+    ];
+
+    /// Two-letter code, e.g. "NA"
+    pub fn code(&self) -> &'static str {
+        match self {
+            Continent::Africa => "AF",
+            Continent::Antarctica => "AN",
+            Continent::Asia => "AS",
+            Continent::Europe => "EU",
+            Continent::NorthAmerica => "NA",
+            Continent::Oceania => "OC",
+            Continent::SouthAmerica => "SA",
+        }
+    }
+
+    /// English name, e.g. "North America"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Continent::Africa => "Africa",
+            Continent::Antarctica => "Antarctica",
+            Continent::Asia => "Asia",
+            Continent::Europe => "Europe",
+            Continent::NorthAmerica => "North America",
+            Continent::Oceania => "Oceania",
+            Continent::SouthAmerica => "South America",
+        }
+    }
+}
+
+impl FromStr for Continent {
+    type Err = QrzXmlError;
+
+    /// Parse a two-letter code, ignoring case
+    fn from_str(s: &str) -> Result<Self> {
+        let code = s.trim();
+        Continent::ALL
+            .into_iter()
+            .find(|continent| continent.code().eq_ignore_ascii_case(code))
+            .ok_or_else(|| QrzXmlError::invalid_input(format!("Unknown continent: {}", s)))
+    }
+}
+
+impl fmt::Display for Continent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// A Y/N flag in a callsign record
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QslFlag {
+    /// The field is `Y`
+    Yes,
+    /// The field is `N`
+    No,
+    /// The field is absent, empty or holds anything else
+    #[default]
+    Unknown,
+}
+
+impl QslFlag {
+    /// Interpret a `Y`/`N` field, ignoring case and surrounding whitespace
+    pub fn from_field(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(v) if v.eq_ignore_ascii_case("y") => QslFlag::Yes,
+            Some(v) if v.eq_ignore_ascii_case("n") => QslFlag::No,
+            _ => QslFlag::Unknown,
+        }
+    }
+
+    /// `Some(true)` for `Yes`, `Some(false)` for `No`, `None` when unknown
+    pub fn as_bool(self) -> Option<bool> {
+        match self {
+            QslFlag::Yes => Some(true),
+            QslFlag::No => Some(false),
+            QslFlag::Unknown => None,
+        }
+    }
+
+    /// The field is explicitly `Y`
+    pub fn is_yes(self) -> bool {
+        self == QslFlag::Yes
+    }
+}
+
+/// QSL methods a station accepts, from the `eqsl`, `mqsl` and `lotw` fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QslCapabilities {
+    /// Accepts eQSL
+    pub eqsl: QslFlag,
+    /// Returns paper QSL cards by mail
+    pub paper: QslFlag,
+    /// Uploads to Logbook of The World
+    pub lotw: QslFlag,
+}
+
+/// Where the coordinates of a callsign record came from, from most to least precise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GeoLocationSource {
+    /// Set by the operator
+    User,
+    /// Geocoded from the street address
+    Geocode,
+    /// Center of the grid square
+    Grid,
+    /// Center of the ZIP code area
+    Zip,
+    /// Center of the state
+    State,
+    /// Center of the DXCC entity
+    Dxcc,
+}
+
+impl GeoLocationSource {
+    /// The coordinates are close enough to the station for antenna pointing
+    pub fn is_precise(&self) -> bool {
+        matches!(
+            self,
+            GeoLocationSource::User | GeoLocationSource::Geocode | GeoLocationSource::Grid
+        )
+    }
+}
+
+impl FromStr for GeoLocationSource {
+    type Err = QrzXmlError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "user" => Ok(GeoLocationSource::User),
+            "geocode" => Ok(GeoLocationSource::Geocode),
+            "grid" => Ok(GeoLocationSource::Grid),
+            "zip" => Ok(GeoLocationSource::Zip),
+            "state" => Ok(GeoLocationSource::State),
+            "dxcc" => Ok(GeoLocationSource::Dxcc),
+            _ => Err(QrzXmlError::invalid_input(format!(
+                "Unknown geolocation source: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Dimensions and file size of a record's primary image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ImageInfo {
+    /// Height in pixels
+    pub height: u32,
+    /// Width in pixels
+    pub width: u32,
+    /// File size in bytes
+    pub size_bytes: u64,
+}
+
+impl FromStr for ImageInfo {
+    type Err = QrzXmlError;
+
+    /// Parse QRZ's packed `height:width:size` form, e.g. "300:400:52317"
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || QrzXmlError::invalid_input(format!("Invalid image info: {}", s));
+        let mut parts = s.trim().split(':').map(str::trim);
+        let (Some(height), Some(width), Some(size), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            height: height.parse().map_err(|_| invalid())?,
+            width: width.parse().map_err(|_| invalid())?,
+            size_bytes: size.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Parse QRZ's date formats, e.g. "Wed Jan 1 12:34:03 2025" or "2025-01-01"
+#[cfg(feature = "chrono")]
+fn parse_qrz_datetime(value: &str) -> Option<DateTime<Utc>> {
+    // Days are padded with a space or not at all, so normalize the whitespace.
+    // The weekday is redundant and not validated against the date.
+    let mut parts: Vec<&str> = value.split_whitespace().collect();
+    if parts.len() == 5 && parts[0].chars().all(|c| c.is_ascii_alphabetic()) {
+        parts.remove(0);
+    }
+    let value = parts.join(" ");
+
+    NaiveDateTime::parse_from_str(&value, "%b %d %H:%M:%S %Y")
+        .or_else(|_| NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        })
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Parse a QRZ date such as "2025-01-01"; the "0000-00-00" placeholder is `None`
+#[cfg(feature = "chrono")]
+fn parse_qrz_date(value: &str) -> Option<NaiveDate> {
+    parse_qrz_datetime(value).map(|datetime| datetime.date_naive())
 }
 
 /// Comprehensive callsign information
-#[derive(Debug, Clone, Deserialize, Serialize)]
+///
+/// Not `Eq` or `Hash` because of the floating-point coordinates; key maps and
+/// sets of records by `call` instead.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct CallsignInfo {
     /// Primary callsign
-    #[serde(rename = "call")]
+    #[serde(rename = "call", default)]
     pub call: String,
 
     /// Cross reference callsign that returned this record
@@ -122,27 +538,27 @@ pub struct CallsignInfo {
     pub aliases: Option<String>,
 
     /// DXCC entity ID (country code)
-    #[serde(rename = "dxcc")]
+    #[serde(rename = "dxcc", default, deserialize_with = "lenient_number")]
     pub dxcc: Option<u32>,
 
     /// First name
-    #[serde(rename = "fname")]
+    #[serde(rename = "fname", default, deserialize_with = "html_text")]
     pub fname: Option<String>,
 
     /// Last name
-    #[serde(rename = "name")]
+    #[serde(rename = "name", default, deserialize_with = "html_text")]
     pub name: Option<String>,
 
     /// Address line 1 (house number and street)
-    #[serde(rename = "addr1")]
+    #[serde(rename = "addr1", default, deserialize_with = "html_text")]
     pub addr1: Option<String>,
 
     /// Address line 2 (city)
-    #[serde(rename = "addr2")]
+    #[serde(rename = "addr2", default, deserialize_with = "html_text")]
     pub addr2: Option<String>,
 
     /// State (USA only)
-    #[serde(rename = "state")]
+    #[serde(rename = "state", default, deserialize_with = "html_text")]
     pub state: Option<String>,
 
     /// ZIP/postal code
@@ -150,19 +566,19 @@ pub struct CallsignInfo {
     pub zip: Option<String>,
 
     /// Country name for QSL mailing address
-    #[serde(rename = "country")]
+    #[serde(rename = "country", default, deserialize_with = "html_text")]
     pub country: Option<String>,
 
     /// DXCC entity code for mailing address country
-    #[serde(rename = "ccode")]
+    #[serde(rename = "ccode", default, deserialize_with = "lenient_number")]
     pub ccode: Option<u32>,
 
     /// Latitude (signed decimal, S < 0 > N)
-    #[serde(rename = "lat")]
+    #[serde(rename = "lat", default, deserialize_with = "lenient_decimal")]
     pub lat: Option<f64>,
 
     /// Longitude (signed decimal, W < 0 > E)
-    #[serde(rename = "lon")]
+    #[serde(rename = "lon", default, deserialize_with = "lenient_decimal")]
     pub lon: Option<f64>,
 
     /// Grid locator
@@ -170,7 +586,7 @@ pub struct CallsignInfo {
     pub grid: Option<String>,
 
     /// County name (USA)
-    #[serde(rename = "county")]
+    #[serde(rename = "county", default, deserialize_with = "html_text")]
     pub county: Option<String>,
 
     /// FIPS county identifier (USA)
@@ -178,7 +594,7 @@ pub struct CallsignInfo {
     pub fips: Option<String>,
 
     /// DXCC country name of the callsign
-    #[serde(rename = "land")]
+    #[serde(rename = "land", default, deserialize_with = "html_text")]
     pub land: Option<String>,
 
     /// License effective date (USA)
@@ -202,7 +618,7 @@ pub struct CallsignInfo {
     pub codes: Option<String>,
 
     /// QSL manager info
-    #[serde(rename = "qslmgr")]
+    #[serde(rename = "qslmgr", default, deserialize_with = "html_text")]
     pub qslmgr: Option<String>,
 
     /// Email address
@@ -214,12 +630,12 @@ pub struct CallsignInfo {
     pub url: Option<String>,
 
     /// QRZ web page views
-    #[serde(rename = "u_views")]
+    #[serde(rename = "u_views", default, deserialize_with = "lenient_number")]
     pub u_views: Option<u32>,
 
-    /// Biography size in bytes
-    #[serde(rename = "bio")]
-    pub bio: Option<String>,
+    /// Approximate biography size in bytes
+    #[serde(rename = "bio", default, deserialize_with = "leading_number")]
+    pub bio: Option<u64>,
 
     /// Biography last update date
     #[serde(rename = "biodate")]
@@ -234,7 +650,7 @@ pub struct CallsignInfo {
     pub imageinfo: Option<String>,
 
     /// QRZ database serial number
-    #[serde(rename = "serial")]
+    #[serde(rename = "serial", default, deserialize_with = "lenient_number")]
     pub serial: Option<u32>,
 
     /// Last modified date
@@ -270,15 +686,15 @@ pub struct CallsignInfo {
     pub mqsl: Option<String>,
 
     /// CQ Zone identifier
-    #[serde(rename = "cqzone")]
+    #[serde(rename = "cqzone", default, deserialize_with = "lenient_number")]
     pub cqzone: Option<u32>,
 
     /// ITU Zone identifier
-    #[serde(rename = "ituzone")]
+    #[serde(rename = "ituzone", default, deserialize_with = "lenient_number")]
     pub ituzone: Option<u32>,
 
     /// Operator's birth year
-    #[serde(rename = "born")]
+    #[serde(rename = "born", default, deserialize_with = "lenient_number")]
     pub born: Option<u32>,
 
     /// User who manages this callsign on QRZ
@@ -298,19 +714,48 @@ pub struct CallsignInfo {
     pub geoloc: Option<String>,
 
     /// Attention address line (new in v1.34)
-    #[serde(rename = "attn")]
+    #[serde(rename = "attn", default, deserialize_with = "html_text")]
     pub attn: Option<String>,
 
     /// Nickname (new in v1.34)
-    #[serde(rename = "nickname")]
+    #[serde(rename = "nickname", default, deserialize_with = "html_text")]
     pub nickname: Option<String>,
 
     /// Combined full name and nickname (new in v1.34)
-    #[serde(rename = "name_fmt")]
+    #[serde(rename = "name_fmt", default, deserialize_with = "html_text")]
     pub name_fmt: Option<String>,
+
+    /// The callsign as given to the lookup, when it was normalized to a
+    /// different one before querying QRZ
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queried_callsign: Option<String>,
+
+    /// Elements this crate does not model, by element name
+    ///
+    /// Fields added in newer API versions show up here until they get a
+    /// field of their own.
+    #[serde(flatten, deserialize_with = "extra_fields")]
+    pub extra: HashMap<String, String>,
 }
 
 impl CallsignInfo {
+    /// Start building a record, e.g. for tests and mock backends
+    pub fn builder() -> CallsignInfoBuilder {
+        CallsignInfoBuilder::default()
+    }
+
+    /// The alias this record was found by, when it differs from `call`
+    ///
+    /// Looking up `N6UFT` returns the `AA7BQ` record with `xref` set to
+    /// `N6UFT`; code that files the result under the queried callsign should
+    /// check this first.
+    pub fn resolved_via_alias(&self) -> Option<&str> {
+        self.xref
+            .as_deref()
+            .map(str::trim)
+            .filter(|xref| !xref.is_empty() && !xref.eq_ignore_ascii_case(self.call.trim()))
+    }
+
     /// Get the full name (combining first and last name)
     pub fn full_name(&self) -> Option<String> {
         match (&self.fname, &self.name) {
@@ -321,6 +766,27 @@ impl CallsignInfo {
         }
     }
 
+    /// Name to show for the operator
+    ///
+    /// Prefers `name_fmt`, then nickname and last name, then [`full_name`](Self::full_name).
+    pub fn display_name(&self) -> Option<String> {
+        fn field(value: &Option<String>) -> Option<&str> {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        }
+
+        if let Some(name_fmt) = field(&self.name_fmt) {
+            return Some(name_fmt.to_string());
+        }
+        match (field(&self.nickname), field(&self.name)) {
+            (Some(nickname), Some(last)) => Some(format!("{} {}", nickname, last)),
+            (Some(nickname), None) => Some(nickname.to_string()),
+            (None, _) => self.full_name(),
+        }
+    }
+
     /// Get coordinates as a tuple (lat, lon) if both are present
     pub fn coordinates(&self) -> Option<(f64, f64)> {
         match (self.lat, self.lon) {
@@ -329,27 +795,464 @@ impl CallsignInfo {
         }
     }
 
+    /// Grid locator as a validated [`GridSquare`], if present and well-formed
+    pub fn grid_parsed(&self) -> Option<GridSquare> {
+        self.grid.as_deref()?.parse().ok()
+    }
+
+    /// Distance and bearing to this station from a grid square or (lat, lon)
+    ///
+    /// Uses the record's coordinates, or the center of its grid square when
+    /// the operator did not set any.
+    pub fn distance_bearing_from(&self, origin: impl Into<Location>) -> Option<DistanceBearing> {
+        let to = self.position()?;
+        Some(DistanceBearing::between(origin.into().coordinates(), to))
+    }
+
+    /// Great-circle distance in kilometers to another station
+    ///
+    /// Like [`distance_bearing_from`](Self::distance_bearing_from), falls back
+    /// to the grid square center for either station without coordinates.
+    pub fn distance_to(&self, other: &CallsignInfo) -> Option<f64> {
+        Some(DistanceBearing::between(self.position()?, other.position()?).distance_km)
+    }
+
+    /// Short-path bearing in degrees from this station to another
+    pub fn bearing_to(&self, other: &CallsignInfo) -> Option<f64> {
+        Some(DistanceBearing::between(self.position()?, other.position()?).bearing)
+    }
+
+    /// Sunrise, sunset and solar noon at the station on a date
+    ///
+    /// Falls back to the grid square center without coordinates.
+    #[cfg(feature = "sun")]
+    pub fn sun_times(&self, date: NaiveDate) -> Option<SunTimes> {
+        Some(SunTimes::at(self.position()?, date))
+    }
+
+    /// Coordinates, or the center of the grid square without them
+    fn position(&self) -> Option<(f64, f64)> {
+        self.coordinates()
+            .or_else(|| self.grid_parsed().map(|grid| grid.center()))
+    }
+
+    /// CQ zone, or `None` if missing or outside 1-40
+    pub fn cq_zone_parsed(&self) -> Option<CqZone> {
+        CqZone::new(self.cqzone?)
+    }
+
+    /// ITU zone, or `None` if missing or outside 1-90
+    pub fn itu_zone_parsed(&self) -> Option<ItuZone> {
+        ItuZone::new(self.ituzone?)
+    }
+
+    /// QSL methods the station accepts
+    pub fn qsl_capabilities(&self) -> QslCapabilities {
+        QslCapabilities {
+            eqsl: QslFlag::from_field(self.eqsl.as_deref()),
+            paper: QslFlag::from_field(self.mqsl.as_deref()),
+            lotw: QslFlag::from_field(self.lotw.as_deref()),
+        }
+    }
+
     /// Check if QSL information indicates acceptance of eQSL
+    #[deprecated(note = "use `qsl_capabilities().eqsl`")]
     pub fn accepts_eqsl(&self) -> Option<bool> {
         self.eqsl.as_ref().map(|s| s.eq_ignore_ascii_case("y"))
     }
 
     /// Check if QSL information indicates will return paper QSL
+    #[deprecated(note = "use `qsl_capabilities().paper`")]
     pub fn returns_paper_qsl(&self) -> Option<bool> {
         self.mqsl.as_ref().map(|s| s.eq_ignore_ascii_case("y"))
     }
 
     /// Check if LOTW is accepted
+    #[deprecated(note = "use `qsl_capabilities().lotw`")]
     pub fn accepts_lotw(&self) -> Option<bool> {
         self.lotw.as_ref().map(|s| s.eq_ignore_ascii_case("y"))
     }
+
+    /// Parse the GMT offset as hours (may include fractions)
+    pub fn utc_offset_hours(&self) -> Option<f32> {
+        self.gmt_offset.as_deref().and_then(parse_utc_offset)
+    }
+
+    /// Time zone of the station, from `TimeZone`, `GMTOffset` and `DST`
+    #[cfg(feature = "tz")]
+    pub fn station_time_zone(&self) -> Option<StationTimeZone> {
+        StationTimeZone::from_record(self)
+    }
+
+    /// Current local time at the station, if its time zone is known
+    ///
+    /// [`Station::local_time_now`](crate::Station::local_time_now) falls back
+    /// to the DXCC entity's offset for records without one.
+    #[cfg(feature = "tz")]
+    pub fn local_time_now(&self) -> Option<DateTime<FixedOffset>> {
+        Some(self.station_time_zone()?.local_time(Utc::now()))
+    }
+
+    /// Where the record's coordinates came from
+    ///
+    /// `None` if `geoloc` is missing, `none` or not a known source.
+    pub fn geoloc_parsed(&self) -> Option<GeoLocationSource> {
+        self.geoloc.as_deref()?.parse().ok()
+    }
+
+    /// Check if daylight saving time is observed at the station's location
+    pub fn observes_dst(&self) -> Option<bool> {
+        self.dst.as_ref().map(|s| s.eq_ignore_ascii_case("y"))
+    }
+
+    /// Dimensions and size of the primary image, if present and well-formed
+    pub fn image_info(&self) -> Option<ImageInfo> {
+        self.imageinfo.as_deref()?.parse().ok()
+    }
+
+    /// License effective date, if present and valid
+    #[cfg(feature = "chrono")]
+    pub fn efdate_parsed(&self) -> Option<NaiveDate> {
+        self.efdate.as_deref().and_then(parse_qrz_date)
+    }
+
+    /// License expiration date, if present and valid
+    #[cfg(feature = "chrono")]
+    pub fn expdate_parsed(&self) -> Option<NaiveDate> {
+        self.expdate.as_deref().and_then(parse_qrz_date)
+    }
+
+    /// Standing of the license on the given date
+    ///
+    /// A license is active through its expiration date and expiring soon
+    /// during the last [`LICENSE_EXPIRING_SOON_DAYS`] of that. Dates before the
+    /// effective date give [`LicenseStatus::NotYetEffective`], matching
+    /// [`license_valid_on`](Self::license_valid_on); otherwise records without an
+    /// expiration date, which is common outside the US, give
+    /// [`LicenseStatus::Unknown`].
+    #[cfg(feature = "chrono")]
+    pub fn license_status(&self, as_of: NaiveDate) -> LicenseStatus {
+        if self.efdate_parsed().is_some_and(|efdate| as_of < efdate) {
+            return LicenseStatus::NotYetEffective;
+        }
+        let Some(expdate) = self.expdate_parsed() else {
+            return LicenseStatus::Unknown;
+        };
+        if as_of > expdate {
+            LicenseStatus::Expired
+        } else if (expdate - as_of).num_days() < LICENSE_EXPIRING_SOON_DAYS {
+            LicenseStatus::ExpiringSoon
+        } else {
+            LicenseStatus::Active
+        }
+    }
+
+    /// Check if the license expires within the given time from today (UTC)
+    ///
+    /// Licenses that have already lapsed count as expiring, so club and VE
+    /// tools can flag every member needing a renewal in one pass. Returns
+    /// `None` when the record has no usable expiration date.
+    #[cfg(feature = "chrono")]
+    pub fn license_expires_within(&self, within: Duration) -> Option<bool> {
+        self.license_expires_within_from(Utc::now().date_naive(), within)
+    }
+
+    #[cfg(feature = "chrono")]
+    fn license_expires_within_from(&self, today: NaiveDate, within: Duration) -> Option<bool> {
+        let expdate = self.expdate_parsed()?;
+        Some(expdate - today <= within)
+    }
+
+    /// Operator's age in years on the given date, from the `born` year
+    ///
+    /// Only the birth year is known, so this is the age the operator reaches
+    /// during `as_of`'s year. Birth years in the future or more than
+    /// [`MAX_PLAUSIBLE_AGE`] years back, such as the `0` some records carry,
+    /// give `None`.
+    #[cfg(feature = "chrono")]
+    pub fn age(&self, as_of: NaiveDate) -> Option<u32> {
+        let year = u32::try_from(as_of.year()).ok()?;
+        year.checked_sub(self.born?)
+            .filter(|age| *age <= MAX_PLAUSIBLE_AGE)
+    }
+
+    /// Last modification of the record as a timestamp
+    #[cfg(feature = "chrono")]
+    pub fn moddate_datetime(&self) -> Option<DateTime<Utc>> {
+        self.moddate.as_deref().and_then(parse_qrz_datetime)
+    }
+
+    /// Last modification of the biography as a timestamp
+    #[cfg(feature = "chrono")]
+    pub fn biodate_datetime(&self) -> Option<DateTime<Utc>> {
+        self.biodate.as_deref().and_then(parse_qrz_datetime)
+    }
+
+    /// Check if the record was modified after the given time
+    ///
+    /// Returns `None` if the record has no usable modification date.
+    #[cfg(feature = "chrono")]
+    pub fn modified_since(&self, since: DateTime<Utc>) -> Option<bool> {
+        self.moddate_datetime().map(|moddate| moddate > since)
+    }
+
+    /// Check if the license was valid on the given date
+    ///
+    /// The license counts as valid from its effective date through its
+    /// expiration date, both inclusive. Returns `None` when the record has no
+    /// usable expiration date and the date is not before the effective date,
+    /// which is common for licenses issued outside the US.
+    #[cfg(feature = "chrono")]
+    pub fn license_valid_on(&self, date: NaiveDate) -> Option<bool> {
+        if self.efdate_parsed().is_some_and(|efdate| date < efdate) {
+            return Some(false);
+        }
+        self.expdate_parsed().map(|expdate| date <= expdate)
+    }
+
+    /// Heuristically check if this record belongs to a club station rather than an individual
+    ///
+    /// A record is treated as a club when its license class or codes mark it as one
+    /// (class `C`/`CLUB`, a `C` code or an FCC club license such as `HAB`), or when
+    /// it has no first name and the `name` field contains a word that only club
+    /// names use, such as `CLUB`, `ARC` or `SOCIETY`. Words that also occur in
+    /// surnames or business names, like `RADIO` or `TEAM`, are not enough.
+    pub fn is_club(&self) -> bool {
+        let class_is_club = self
+            .class
+            .as_deref()
+            .map(|c| {
+                let c = c.trim();
+                c.eq_ignore_ascii_case("c") || c.eq_ignore_ascii_case("club")
+            })
+            .unwrap_or(false);
+        let codes_is_club = self
+            .codes
+            .as_deref()
+            .map(|codes| codes.trim().eq_ignore_ascii_case("c"))
+            .unwrap_or(false)
+            || self
+                .license_codes()
+                .is_some_and(|codes| codes.is_club_license());
+
+        if class_is_club || codes_is_club {
+            return true;
+        }
+
+        let has_first_name = self
+            .fname
+            .as_deref()
+            .map(|f| !f.trim().is_empty())
+            .unwrap_or(false);
+
+        !has_first_name
+            && self
+                .name
+                .as_deref()
+                .map(looks_like_organization)
+                .unwrap_or(false)
+    }
+
+    /// Decoded FCC license type codes of a US record
+    pub fn license_codes(&self) -> Option<LicenseCodes> {
+        LicenseCodes::parse(self.codes.as_deref()?)
+    }
+
+    /// Resolve the `state` field into a typed subdivision of the record's DXCC entity
+    pub fn subdivision(&self) -> Option<Subdivision> {
+        Subdivision::resolve(self.dxcc?, self.state.as_deref()?)
+    }
+
+    /// The `state` field as a US state, if it names one
+    ///
+    /// The record's DXCC entity is not considered; see
+    /// [`state_matches_dxcc`](Self::state_matches_dxcc) to catch US states
+    /// given for records outside the US.
+    pub fn state_parsed(&self) -> Option<UsState> {
+        self.state.as_deref()?.parse().ok()
+    }
+
+    /// Whether the US state in `state` belongs to the record's DXCC entity
+    ///
+    /// `None` if either is missing or `state` is not a US state.
+    pub fn state_matches_dxcc(&self) -> Option<bool> {
+        Some(self.state_parsed()?.dxcc() == self.dxcc?)
+    }
+
+    /// Multi-line postal address for QSL card labels
+    ///
+    /// Lines are the name, the attention line, the street, the city line and
+    /// the country. US addresses put state and ZIP code after the city
+    /// (`NEWINGTON, CT 06111`); elsewhere the state and postal code get a line
+    /// of their own below the city. `None` if the record has no street or city.
+    pub fn mailing_address(&self) -> Option<String> {
+        fn field(value: &Option<String>) -> Option<&str> {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        }
+
+        let (street, city) = (field(&self.addr1), field(&self.addr2));
+        if street.is_none() && city.is_none() {
+            return None;
+        }
+
+        let region = [field(&self.state), field(&self.zip)]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let region = (!region.is_empty()).then_some(region);
+        let (city, region) = match (city, region) {
+            (Some(city), Some(region)) if self.has_us_address() => {
+                (Some(format!("{}, {}", city, region)), None)
+            }
+            (city, region) => (city.map(str::to_string), region),
+        };
+
+        let lines: Vec<String> = [
+            self.full_name(),
+            field(&self.attn).map(str::to_string),
+            street.map(str::to_string),
+            city,
+            region,
+            field(&self.country).map(str::to_string),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Some(lines.join("\n"))
+    }
+
+    /// The mailing address is in the US, judged by the country or, without
+    /// one, the DXCC entity
+    fn has_us_address(&self) -> bool {
+        match self.country.as_deref().map(str::trim) {
+            Some(country) if !country.is_empty() => ["United States", "USA", "US"]
+                .iter()
+                .any(|us| country.eq_ignore_ascii_case(us)),
+            _ => matches!(
+                self.dxcc,
+                Some(entities::UNITED_STATES | entities::ALASKA | entities::HAWAII)
+            ),
+        }
+    }
+
+    /// The record as a vCard 4.0, for importing into a contacts application
+    #[cfg(feature = "vcard")]
+    pub fn to_vcard(&self) -> String {
+        crate::vcard::to_vcard(self)
+    }
+
+    /// The record as a KML placemark with the class, grid and country in its
+    /// description, or `None` without coordinates
+    #[cfg(feature = "kml")]
+    pub fn to_kml_placemark(&self) -> Option<String> {
+        crate::kml::callsign_placemark(self)
+    }
+
+    /// The record as a GeoJSON point feature with the callsign as its id
+    ///
+    /// Properties are the callsign, display name, grid, country, state,
+    /// DXCC entity, zones and `geoloc`; missing fields are left out. `None`
+    /// without coordinates.
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson_feature(&self) -> Option<Feature> {
+        let properties = [
+            ("call", Some(JsonValue::from(self.call.as_str()))),
+            ("name", self.display_name().map(JsonValue::from)),
+            ("grid", self.grid.as_deref().map(JsonValue::from)),
+            ("country", self.country.as_deref().map(JsonValue::from)),
+            ("state", self.state.as_deref().map(JsonValue::from)),
+            ("dxcc", self.dxcc.map(JsonValue::from)),
+            ("cqzone", self.cqzone.map(JsonValue::from)),
+            ("ituzone", self.ituzone.map(JsonValue::from)),
+            ("geoloc", self.geoloc.as_deref().map(JsonValue::from)),
+        ];
+        Some(point_feature(&self.call, self.coordinates()?, properties))
+    }
+
+    /// Name and address fields converted to ASCII for label printing, with the originals
+    pub fn transliterate(&self, transliteration: &Transliteration) -> TransliteratedAddress {
+        TransliteratedAddress::new(self, transliteration)
+    }
+
+    /// Get a club-oriented view of this record, if it looks like a club station
+    pub fn club_info(&self) -> Option<ClubInfo<'_>> {
+        if !self.is_club() {
+            return None;
+        }
+
+        Some(ClubInfo {
+            call: &self.call,
+            organization: self.name.as_deref().or(self.name_fmt.as_deref()),
+            attention: self.attn.as_deref(),
+            address: self.addr1.as_deref(),
+            city: self.addr2.as_deref(),
+            state: self.state.as_deref(),
+            country: self.country.as_deref(),
+            url: self.url.as_deref(),
+            email: self.email.as_deref(),
+        })
+    }
+}
+
+/// Words that, in a record without a first name, mark the name as a club's
+const ORGANIZATION_WORDS: &[&str] = &[
+    "CLUB",
+    "ARC",
+    "ARS",
+    "ARES",
+    "RACES",
+    "ASSOCIATION",
+    "ASSN",
+    "SOCIETY",
+    "LEAGUE",
+];
+
+fn looks_like_organization(name: &str) -> bool {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| {
+            ORGANIZATION_WORDS
+                .iter()
+                .any(|org| word.eq_ignore_ascii_case(org))
+        })
+}
+
+/// Club-oriented view of a callsign record
+///
+/// Club records usually have no first name and carry the organization name in
+/// `name`, so they read poorly when rendered like an individual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClubInfo<'a> {
+    /// Club callsign
+    pub call: &'a str,
+    /// Organization name
+    pub organization: Option<&'a str>,
+    /// Attention line, often the trustee or a contact person
+    pub attention: Option<&'a str>,
+    /// Street address
+    pub address: Option<&'a str>,
+    /// City
+    pub city: Option<&'a str>,
+    /// State (USA only)
+    pub state: Option<&'a str>,
+    /// Country name
+    pub country: Option<&'a str>,
+    /// Web page address
+    pub url: Option<&'a str>,
+    /// Email address
+    pub email: Option<&'a str>,
 }
 
 /// DXCC entity information
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct DxccInfo {
     /// DXCC entity number
-    #[serde(rename = "dxcc")]
+    #[serde(rename = "dxcc", default, deserialize_with = "lenient_number_required")]
     pub dxcc: u32,
 
     /// 2-letter country code (ISO-3166)
@@ -361,7 +1264,7 @@ pub struct DxccInfo {
     pub ccc: Option<String>,
 
     /// Long country name
-    #[serde(rename = "name")]
+    #[serde(rename = "name", default, deserialize_with = "html_text_required")]
     pub name: String,
 
     /// 2-letter continent designator
@@ -369,11 +1272,11 @@ pub struct DxccInfo {
     pub continent: Option<String>,
 
     /// ITU Zone
-    #[serde(rename = "ituzone")]
+    #[serde(rename = "ituzone", default, deserialize_with = "lenient_number")]
     pub ituzone: Option<u32>,
 
     /// CQ Zone
-    #[serde(rename = "cqzone")]
+    #[serde(rename = "cqzone", default, deserialize_with = "lenient_number")]
     pub cqzone: Option<u32>,
 
     /// UTC timezone offset +/-
@@ -381,19 +1284,28 @@ pub struct DxccInfo {
     pub timezone: Option<String>,
 
     /// Latitude (approximate center)
-    #[serde(rename = "lat")]
+    #[serde(rename = "lat", default, deserialize_with = "lenient_decimal")]
     pub lat: Option<f64>,
 
     /// Longitude (approximate center)
-    #[serde(rename = "lon")]
+    #[serde(rename = "lon", default, deserialize_with = "lenient_decimal")]
     pub lon: Option<f64>,
 
     /// Special notes and exceptions
-    #[serde(rename = "notes")]
+    #[serde(rename = "notes", default, deserialize_with = "html_text")]
     pub notes: Option<String>,
+
+    /// Elements this crate does not model, by element name
+    #[serde(flatten, deserialize_with = "extra_fields")]
+    pub extra: HashMap<String, String>,
 }
 
 impl DxccInfo {
+    /// Start building an entity, e.g. for tests and mock backends
+    pub fn builder() -> DxccInfoBuilder {
+        DxccInfoBuilder::default()
+    }
+
     /// Get coordinates as a tuple (lat, lon) if both are present
     pub fn coordinates(&self) -> Option<(f64, f64)> {
         match (self.lat, self.lon) {
@@ -402,27 +1314,136 @@ impl DxccInfo {
         }
     }
 
+    /// The entity as a GeoJSON point feature with the entity number as its id
+    ///
+    /// Properties are the entity number and name, country code, continent,
+    /// zones and UTC offset; missing fields are left out. `None` without
+    /// coordinates.
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson_feature(&self) -> Option<Feature> {
+        let properties = [
+            ("dxcc", Some(JsonValue::from(self.dxcc))),
+            ("name", Some(JsonValue::from(self.name.as_str()))),
+            ("cc", self.cc.as_deref().map(JsonValue::from)),
+            ("continent", self.continent.as_deref().map(JsonValue::from)),
+            ("cqzone", self.cqzone.map(JsonValue::from)),
+            ("ituzone", self.ituzone.map(JsonValue::from)),
+            ("timezone", self.timezone.as_deref().map(JsonValue::from)),
+        ];
+        Some(point_feature(
+            &self.dxcc.to_string(),
+            self.coordinates()?,
+            properties,
+        ))
+    }
+
+    /// The entity as a KML placemark with the continent and zones in its
+    /// description, or `None` without coordinates
+    #[cfg(feature = "kml")]
+    pub fn to_kml_placemark(&self) -> Option<String> {
+        crate::kml::dxcc_placemark(self)
+    }
+
+    /// CQ zone, or `None` if missing or outside 1-40
+    pub fn cq_zone_parsed(&self) -> Option<CqZone> {
+        CqZone::new(self.cqzone?)
+    }
+
+    /// ITU zone, or `None` if missing or outside 1-90
+    pub fn itu_zone_parsed(&self) -> Option<ItuZone> {
+        ItuZone::new(self.ituzone?)
+    }
+
+    /// Great-circle distance in kilometers between the centers of two entities
+    pub fn distance_to(&self, other: &DxccInfo) -> Option<f64> {
+        Some(DistanceBearing::between(self.coordinates()?, other.coordinates()?).distance_km)
+    }
+
+    /// Short-path bearing in degrees from the center of this entity to another
+    pub fn bearing_to(&self, other: &DxccInfo) -> Option<f64> {
+        Some(DistanceBearing::between(self.coordinates()?, other.coordinates()?).bearing)
+    }
+
+    /// Continent as a [`Continent`], if present and known
+    pub fn continent_parsed(&self) -> Option<Continent> {
+        self.continent.as_deref()?.parse().ok()
+    }
+
+    /// ISO 3166-1 country from the 2-letter code, if present and assigned
+    #[cfg(feature = "iso3166")]
+    pub fn country_alpha2(&self) -> Option<CountryCode> {
+        CountryCode::for_alpha2_caseless(self.cc.as_deref()?.trim()).ok()
+    }
+
+    /// ISO 3166-1 country from the 3-letter code, if present and assigned
+    #[cfg(feature = "iso3166")]
+    pub fn country_alpha3(&self) -> Option<CountryCode> {
+        CountryCode::for_alpha3_caseless(self.ccc.as_deref()?.trim()).ok()
+    }
+
     /// Parse timezone offset as hours (may include fractions)
     pub fn timezone_hours(&self) -> Option<f32> {
-        self.timezone.as_ref().and_then(|tz| {
-            // Handle formats like "+5", "-8", "545" (5 hours 45 minutes)
-            let tz = tz.trim_start_matches('+');
-            if tz.len() >= 3 {
-                // Format like "545" means 5:45
-                if let (Ok(hours), Ok(minutes)) = (
-                    tz[..tz.len() - 2].parse::<i32>(),
-                    tz[tz.len() - 2..].parse::<i32>(),
-                ) {
-                    return Some(hours as f32 + minutes as f32 / 60.0);
-                }
-            }
-            tz.parse::<f32>().ok()
-        })
+        self.timezone.as_deref().and_then(parse_utc_offset)
+    }
+
+    /// Timezone offset as a chrono offset, for arithmetic in entity-local time
+    #[cfg(feature = "chrono")]
+    pub fn utc_offset(&self) -> Option<FixedOffset> {
+        let seconds = (self.timezone_hours()? * 3600.0).round() as i32;
+        FixedOffset::east_opt(seconds)
+    }
+}
+
+/// A GeoJSON point feature, with the properties that are present
+#[cfg(feature = "geojson")]
+fn point_feature<const N: usize>(
+    id: &str,
+    (lat, lon): (f64, f64),
+    properties: [(&str, Option<JsonValue>); N],
+) -> Feature {
+    let properties: JsonObject = properties
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value?)))
+        .collect();
+
+    Feature {
+        bbox: None,
+        geometry: Some(geojson::Geometry::new(geojson::Value::Point(vec![
+            lon, lat,
+        ]))),
+        id: Some(geojson::feature::Id::String(id.to_string())),
+        properties: Some(properties),
+        foreign_members: None,
     }
 }
 
+/// Parse a UTC offset in hours
+///
+/// Handles formats like "+5", "-8", "5.5", "5:45" and "545" (5 hours 45 minutes).
+fn parse_utc_offset(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let (sign, offset) = match value.strip_prefix('-') {
+        Some(offset) => (-1.0, offset),
+        None => (1.0, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    let (hours, minutes) = match offset.split_once(':') {
+        Some(split) => split,
+        None if offset.len() >= 3 && offset.bytes().all(|b| b.is_ascii_digit()) => {
+            offset.split_at(offset.len() - 2)
+        }
+        None => (offset, "0"),
+    };
+    let minutes = minutes.parse::<u8>().ok().filter(|m| *m < 60)?;
+    let hours = hours
+        .parse::<f32>()
+        .ok()
+        .filter(|h| h.is_finite() && *h >= 0.0)?;
+    Some(sign * (hours + f32::from(minutes) / 60.0))
+}
+
 /// Biography/HTML data container
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct BiographyData {
     /// The callsign this biography belongs to
     pub callsign: String,
@@ -450,13 +1471,86 @@ impl BiographyData {
     }
 }
 
-// Implement Default for CallsignInfo to help with testing
-#[allow(clippy::derivable_impls)]
-impl Default for CallsignInfo {
-    fn default() -> Self {
-        Self {
-            call: String::new(),
-            xref: None,
+/// Classified response of the biography (`html`) endpoint
+///
+/// Besides the biography itself, the endpoint can answer with an XML error, a
+/// plain HTML error page or an empty body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BiographyOutcome {
+    /// The biography HTML
+    Html(String),
+    /// The callsign has no biography
+    NoBio,
+    /// The callsign is not in the QRZ database
+    NotFound,
+    /// Biographies require a subscription
+    SubscriptionRequired,
+    /// The session key was rejected
+    SessionExpired,
+    /// Any other error, with the message or error page title
+    Error(String),
+}
+
+impl BiographyOutcome {
+    /// Classify a raw response body from the biography endpoint
+    pub fn classify(body: &str) -> Self {
+        let trimmed = body.trim();
+        if trimmed.is_empty() {
+            return BiographyOutcome::NoBio;
+        }
+
+        if trimmed.starts_with("<?xml") || trimmed.starts_with("<QRZDatabase") {
+            return match quick_xml::de::from_str::<QrzXmlResponse>(trimmed) {
+                Ok(response) => match response.session.error {
+                    Some(error) => Self::from_error(error),
+                    None => BiographyOutcome::NoBio,
+                },
+                Err(e) => BiographyOutcome::Error(format!("Unparseable XML response: {}", e)),
+            };
+        }
+
+        if let Some(title) = html_title(trimmed) {
+            let lower = title.to_ascii_lowercase();
+            if lower.contains("error") || lower.contains("not found") {
+                return BiographyOutcome::Error(title.to_string());
+            }
+        }
+
+        BiographyOutcome::Html(body.to_string())
+    }
+
+    fn from_error(error: String) -> Self {
+        let lower = error.to_ascii_lowercase();
+        // Checked first, as subscription messages can mention the session too
+        if lower.contains("subscription") {
+            BiographyOutcome::SubscriptionRequired
+        } else if lower.contains("session") {
+            BiographyOutcome::SessionExpired
+        } else if lower.contains("no biography") {
+            BiographyOutcome::NoBio
+        } else if lower.contains("not found") {
+            BiographyOutcome::NotFound
+        } else {
+            BiographyOutcome::Error(error)
+        }
+    }
+}
+
+/// Text of the `<title>` element of an HTML document, if any
+fn html_title(html: &str) -> Option<&str> {
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title>")? + "<title>".len();
+    let end = start + lower[start..].find("</title>")?;
+    Some(html[start..end].trim())
+}
+
+// Implement Default for CallsignInfo to help with testing
+#[allow(clippy::derivable_impls)]
+impl Default for CallsignInfo {
+    fn default() -> Self {
+        Self {
+            call: String::new(),
+            xref: None,
             aliases: None,
             dxcc: None,
             fname: None,
@@ -505,6 +1599,8 @@ impl Default for CallsignInfo {
             attn: None,
             nickname: None,
             name_fmt: None,
+            queried_callsign: None,
+            extra: HashMap::new(),
         }
     }
 }
@@ -524,7 +1620,234 @@ impl Default for DxccInfo {
             lat: None,
             lon: None,
             notes: None,
+            extra: HashMap::new(),
+        }
+    }
+}
+
+/// Deserialize a text field, decoding the HTML entities QRZ leaves in it
+fn html_text<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(
+        Option::<String>::deserialize(deserializer)?.map(|text| match decode_entities(&text) {
+            Cow::Borrowed(_) => text,
+            Cow::Owned(decoded) => decoded,
+        }),
+    )
+}
+
+/// Like [`html_text`], for text fields that are always present
+fn html_text_required<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    Ok(decode_entities(&text).into_owned())
+}
+
+/// Deserialize an optional number, treating empty elements such as `<lat></lat>` as `None`
+///
+/// Surrounding whitespace and digit group separators such as `1,234` are
+/// ignored, and values that still do not parse become `None`, so one
+/// malformed counter does not fail the whole record. Numbers from
+/// self-describing formats like JSON are accepted as they are.
+fn lenient_number<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+{
+    deserializer.deserialize_option(LenientNumber {
+        group_separators: true,
+        target: PhantomData,
+    })
+}
+
+/// Like [`lenient_number`], for decimals such as coordinates
+///
+/// Separators are not stripped, as `41,714` may use a decimal comma and would
+/// otherwise read as 41714; such values become `None`.
+fn lenient_decimal<'de, D>(deserializer: D) -> std::result::Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_option(LenientNumber {
+        group_separators: false,
+        target: PhantomData,
+    })
+}
+
+/// Like [`lenient_number`], for numbers that are always present, with zero
+/// for missing or unreadable values
+fn lenient_number_required<'de, D>(deserializer: D) -> std::result::Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(lenient_number(deserializer)?.unwrap_or_default())
+}
+
+struct LenientNumber<T> {
+    /// Retry values with digit group separators removed
+    group_separators: bool,
+    target: PhantomData<T>,
+}
+
+impl<'de, T> de::Visitor<'de> for LenientNumber<T>
+where
+    T: FromStr,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a number or an empty element")
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Self::Value, E> {
+        let value = value.trim();
+        if !self.group_separators {
+            return Ok(value.parse().ok());
+        }
+        Ok(value.parse().ok().or_else(|| {
+            let digits: String = value
+                .chars()
+                .filter(|c| !matches!(c, ',' | '_' | ' '))
+                .collect();
+            digits.parse().ok()
+        }))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> std::result::Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<Self::Value, A::Error> {
+        // XML elements surface as maps, with their text under a special key
+        let mut value = None;
+        while let Some(key) = map.next_key::<String>()? {
+            let text: String = map.next_value()?;
+            if key == "$text" || key == "$value" {
+                value = LenientNumber {
+                    group_separators: self.group_separators,
+                    target: PhantomData,
+                }
+                .visit_str(&text)?;
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// Deserialize the number at the start of an element, such as the size in the
+/// `3937/2003-11-04` QRZ sends for `bio`, treating anything unreadable as `None`
+fn leading_number<'de, D>(deserializer: D) -> std::result::Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = Option::<ElementText>::deserialize(deserializer)?;
+    Ok(text.and_then(|text| {
+        let text = text.0.trim();
+        let end = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        text[..end].parse().ok()
+    }))
+}
+
+/// Deserialize unmodeled elements into their text, empty for elements without any
+fn extra_fields<'de, D>(deserializer: D) -> std::result::Result<HashMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let fields = HashMap::<String, ElementText>::deserialize(deserializer)?;
+    Ok(fields
+        .into_iter()
+        .map(|(name, text)| (name, text.0))
+        .collect())
+}
+
+/// Text content of an element of unknown type
+struct ElementText(String);
+
+impl<'de> Deserialize<'de> for ElementText {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(ElementTextVisitor)
+    }
+}
+
+struct ElementTextVisitor;
+
+impl<'de> de::Visitor<'de> for ElementTextVisitor {
+    type Value = ElementText;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an element")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+        Ok(ElementText(String::new()))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Self::Value, E> {
+        Ok(ElementText(value.to_string()))
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> std::result::Result<Self::Value, E> {
+        Ok(ElementText(value.to_string()))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<Self::Value, E> {
+        Ok(ElementText(value.to_string()))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<Self::Value, E> {
+        Ok(ElementText(value.to_string()))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> std::result::Result<Self::Value, E> {
+        Ok(ElementText(value.to_string()))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<Self::Value, A::Error> {
+        // Attributes and child elements are dropped, only the text is kept
+        let mut text = String::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "$text" || key == "$value" {
+                text = map.next_value::<ElementText>()?.0;
+            } else {
+                map.next_value::<de::IgnoredAny>()?;
+            }
         }
+        Ok(ElementText(text))
     }
 }
 
@@ -539,6 +1862,333 @@ mod tests {
         assert_eq!(ApiVersion::Legacy.to_string(), "");
     }
 
+    #[test]
+    fn test_api_version_from_str() {
+        assert_eq!(
+            "Current".parse::<ApiVersion>().unwrap(),
+            ApiVersion::Current
+        );
+        assert_eq!("legacy".parse::<ApiVersion>().unwrap(), ApiVersion::Legacy);
+        assert_eq!(
+            "1.34".parse::<ApiVersion>().unwrap(),
+            ApiVersion::version("1.34")
+        );
+        assert!("1..3".parse::<ApiVersion>().is_err());
+        assert!("latest".parse::<ApiVersion>().is_err());
+    }
+
+    #[test]
+    fn test_resolved_via_alias() {
+        let mut info = CallsignInfo {
+            call: "AA7BQ".to_string(),
+            xref: Some("N6UFT".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(info.resolved_via_alias(), Some("N6UFT"));
+
+        info.xref = Some("aa7bq".to_string());
+        assert_eq!(info.resolved_via_alias(), None);
+        info.xref = Some(" ".to_string());
+        assert_eq!(info.resolved_via_alias(), None);
+    }
+
+    #[test]
+    fn test_empty_numeric_elements() {
+        let response: QrzXmlResponse =
+            quick_xml::de::from_str(include_str!("../fixtures/callsign_empty_fields.xml")).unwrap();
+        let info = response.callsign.unwrap();
+        assert_eq!(info.dxcc, Some(291));
+        assert_eq!(info.cqzone, Some(5));
+        assert_eq!((info.lat, info.lon), (None, None));
+        assert_eq!((info.ccode, info.u_views, info.serial), (None, None, None));
+        assert_eq!((info.ituzone, info.born), (None, None));
+
+        let response: QrzXmlResponse =
+            quick_xml::de::from_str(include_str!("../fixtures/dxcc_empty_fields.xml")).unwrap();
+        let dxcc = response.dxcc.unwrap();
+        assert_eq!(dxcc.cqzone, Some(15));
+        assert_eq!((dxcc.ituzone, dxcc.lat, dxcc.lon), (None, None, None));
+
+        // Populated fields still parse, and records still round-trip through JSON
+        let response: QrzXmlResponse =
+            quick_xml::de::from_str(include_str!("../fixtures/callsign.xml")).unwrap();
+        let info = response.callsign.unwrap();
+        assert_eq!(info.u_views, Some(115336));
+        assert_eq!(info.bio, Some(3937));
+        let json = serde_json::to_string(&info).unwrap();
+        let parsed: CallsignInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.coordinates(), info.coordinates());
+        assert_eq!(parsed.bio, Some(3937));
+        for (bio, expected) in [
+            ("<bio>12</bio>", Some(12)),
+            ("<bio/>", None),
+            ("<bio>n/a</bio>", None),
+        ] {
+            let xml = format!("<Callsign><call>X</call>{}</Callsign>", bio);
+            let info: CallsignInfo = quick_xml::de::from_str(&xml).unwrap();
+            assert_eq!(info.bio, expected, "{}", bio);
+        }
+    }
+
+    #[test]
+    fn test_malformed_numeric_elements() {
+        let info: CallsignInfo = quick_xml::de::from_str(
+            "<Callsign><call>X</call><serial> 0042 </serial><u_views>1,234</u_views>\
+             <dxcc>+291</dxcc><born>19xx</born><lat>north</lat><lon>-72.7</lon></Callsign>",
+        )
+        .unwrap();
+        assert_eq!(
+            (info.serial, info.u_views, info.dxcc),
+            (Some(42), Some(1234), Some(291))
+        );
+        assert_eq!((info.born, info.lat, info.lon), (None, None, Some(-72.7)));
+
+        // A decimal comma is not mistaken for a group separator
+        let info: CallsignInfo = quick_xml::de::from_str(
+            "<Callsign><call>X</call><lat>41,714</lat><lon> -72.727 </lon></Callsign>",
+        )
+        .unwrap();
+        assert_eq!((info.lat, info.lon), (None, Some(-72.727)));
+        let dxcc: DxccInfo =
+            quick_xml::de::from_str("<DXCC><dxcc>1</dxcc><name>X</name><lat>45,0</lat></DXCC>")
+                .unwrap();
+        assert_eq!(dxcc.lat, None);
+
+        let dxcc: DxccInfo = quick_xml::de::from_str(
+            "<DXCC><dxcc> 0291</dxcc><name>X</name><cqzone>five</cqzone></DXCC>",
+        )
+        .unwrap();
+        assert_eq!((dxcc.dxcc, dxcc.cqzone), (291, None));
+        let dxcc: DxccInfo =
+            quick_xml::de::from_str("<DXCC><dxcc>n/a</dxcc><name>X</name></DXCC>").unwrap();
+        assert_eq!(dxcc.dxcc, 0);
+    }
+
+    #[test]
+    fn test_extra_fields() {
+        let response: QrzXmlResponse = quick_xml::de::from_str(
+            r#"<QRZDatabase version="1.36">
+  <Callsign>
+    <call>AA7BQ</call>
+    <fname>FRED L</fname>
+    <dxcc>291</dxcc>
+    <pronouns>he/him</pronouns>
+    <club_member></club_member>
+    <award level="2">WAS</award>
+  </Callsign>
+  <Session><Key>k</Key></Session>
+</QRZDatabase>"#,
+        )
+        .unwrap();
+        let info = response.callsign.unwrap();
+        assert_eq!(info.dxcc, Some(291));
+        assert_eq!(info.extra.len(), 3);
+        assert_eq!(info.extra["pronouns"], "he/him");
+        assert_eq!(info.extra["club_member"], "");
+        assert_eq!(info.extra["award"], "WAS");
+
+        let json = serde_json::to_string(&info).unwrap();
+        let parsed: CallsignInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.extra, info.extra);
+
+        let response: QrzXmlResponse =
+            quick_xml::de::from_str(include_str!("../fixtures/dxcc.xml")).unwrap();
+        assert!(response.dxcc.unwrap().extra.is_empty());
+    }
+
+    #[test]
+    fn test_image_info() {
+        let mut info = CallsignInfo {
+            imageinfo: Some("285:545:44207".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.image_info(),
+            Some(ImageInfo {
+                height: 285,
+                width: 545,
+                size_bytes: 44207,
+            })
+        );
+
+        for invalid in ["", "285:545", "285:545:44207:1", "a:b:c", "-1:545:44207"] {
+            info.imageinfo = Some(invalid.to_string());
+            assert_eq!(info.image_info(), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_distance_bearing_from() {
+        let mut info = CallsignInfo {
+            call: "W1AW".to_string(),
+            grid: Some("FN31pr".to_string()),
+            ..Default::default()
+        };
+        let home: GridSquare = "FN31pr".parse().unwrap();
+        let path = info.distance_bearing_from(home).unwrap();
+        assert!(path.distance_km < 0.001);
+
+        info.lat = Some(51.477928);
+        info.lon = Some(-0.001545);
+        let path = info.distance_bearing_from((41.714775, -72.727260)).unwrap();
+        assert!((path.distance_km - 5434.0).abs() < 10.0);
+
+        info.grid = Some("junk".to_string());
+        info.lat = None;
+        assert_eq!(info.distance_bearing_from((0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_distance_and_bearing_between_records() {
+        let w1aw = CallsignInfo::builder()
+            .call("W1AW")
+            .lat(41.714775)
+            .lon(-72.727260)
+            .build();
+        let g = CallsignInfo::builder().call("G0ABC").grid("IO91wl").build();
+        let distance = w1aw.distance_to(&g).unwrap();
+        assert!((distance - 5434.0).abs() < 20.0, "{}", distance);
+        assert_eq!(g.distance_to(&w1aw), Some(distance));
+        let bearing = w1aw.bearing_to(&g).unwrap();
+        assert!((bearing - 52.0).abs() < 2.0, "{}", bearing);
+        assert_eq!(w1aw.bearing_to(&CallsignInfo::default()), None);
+
+        let usa = DxccInfo::builder().dxcc(291).lat(37.0).lon(-95.0).build();
+        let japan = DxccInfo::builder().dxcc(339).lat(36.0).lon(138.0).build();
+        assert!((usa.distance_to(&japan).unwrap() - 10000.0).abs() < 500.0);
+        assert!(usa.bearing_to(&japan).unwrap() > 300.0);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_license_dates() {
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let info = CallsignInfo {
+            efdate: Some("2000-01-20".to_string()),
+            expdate: Some(" 2010-01-20 ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(info.efdate_parsed(), Some(date("2000-01-20")));
+        assert_eq!(info.expdate_parsed(), Some(date("2010-01-20")));
+        assert_eq!(info.license_valid_on(date("1999-12-31")), Some(false));
+        assert_eq!(info.license_valid_on(date("2000-01-20")), Some(true));
+        assert_eq!(info.license_valid_on(date("2010-01-20")), Some(true));
+        assert_eq!(info.license_valid_on(date("2010-01-21")), Some(false));
+
+        let unknown = CallsignInfo {
+            efdate: Some("2000-01-20".to_string()),
+            expdate: Some("0000-00-00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(unknown.expdate_parsed(), None);
+        assert_eq!(unknown.license_valid_on(date("2005-06-01")), None);
+        assert_eq!(unknown.license_valid_on(date("1990-06-01")), Some(false));
+        assert_eq!(unknown.license_expires_within(Duration::days(30)), None);
+
+        let today = date("2009-12-21");
+        let within = |days| info.license_expires_within_from(today, Duration::days(days));
+        assert_eq!(within(30), Some(true));
+        assert_eq!(within(30 - 1), Some(false));
+        assert_eq!(
+            info.license_expires_within_from(date("2011-01-01"), Duration::zero()),
+            Some(true)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_age() {
+        let as_of = NaiveDate::from_ymd_opt(2025, 6, 28).unwrap();
+        let born = |year| CallsignInfo {
+            born: year,
+            ..Default::default()
+        };
+        assert_eq!(born(Some(1950)).age(as_of), Some(75));
+        assert_eq!(born(Some(2025)).age(as_of), Some(0));
+        assert_eq!(born(Some(2030)).age(as_of), None);
+        assert_eq!(born(Some(0)).age(as_of), None);
+        assert_eq!(born(None).age(as_of), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_license_status() {
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let info = CallsignInfo {
+            efdate: Some("2015-03-01".to_string()),
+            expdate: Some("2025-03-01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.license_status(date("2024-01-01")),
+            LicenseStatus::Active
+        );
+        assert_eq!(
+            info.license_status(date("2024-12-15")),
+            LicenseStatus::ExpiringSoon
+        );
+        assert_eq!(
+            info.license_status(date("2025-03-01")),
+            LicenseStatus::ExpiringSoon
+        );
+        assert_eq!(
+            info.license_status(date("2025-03-02")),
+            LicenseStatus::Expired
+        );
+        assert_eq!(
+            info.license_status(date("2010-01-01")),
+            LicenseStatus::NotYetEffective
+        );
+        assert_eq!(
+            CallsignInfo::default().license_status(date("2024-01-01")),
+            LicenseStatus::Unknown
+        );
+
+        // Agrees with license_valid_on, with or without an expiration date
+        let no_expdate = CallsignInfo {
+            expdate: None,
+            ..info.clone()
+        };
+        for record in [&info, &no_expdate] {
+            for day in ["2010-01-01", "2024-01-01", "2025-03-02"] {
+                let valid = record.license_valid_on(date(day));
+                let status = record.license_status(date(day));
+                match status {
+                    LicenseStatus::Active | LicenseStatus::ExpiringSoon => {
+                        assert_eq!(valid, Some(true))
+                    }
+                    LicenseStatus::Expired | LicenseStatus::NotYetEffective => {
+                        assert_eq!(valid, Some(false))
+                    }
+                    LicenseStatus::Unknown => assert_eq!(valid, None),
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_record_timestamps() {
+        let response: QrzXmlResponse =
+            quick_xml::de::from_str(include_str!("../fixtures/callsign.xml")).unwrap();
+        let info = response.callsign.unwrap();
+        let moddate = info.moddate_datetime().unwrap();
+        assert_eq!(moddate.to_rfc3339(), "2003-11-04T19:37:02+00:00");
+        assert_eq!(info.biodate_datetime(), Some(moddate));
+        assert_eq!(info.modified_since(moddate), Some(false));
+        assert_eq!(
+            info.modified_since(moddate - Duration::seconds(1)),
+            Some(true)
+        );
+
+        let unset = CallsignInfo {
+            moddate: Some("0000-00-00 00:00:00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(unset.moddate_datetime(), None);
+        assert_eq!(unset.modified_since(moddate), None);
+    }
+
     #[test]
     fn test_callsign_full_name() {
         let mut info = CallsignInfo {
@@ -554,6 +2204,127 @@ mod tests {
         assert_eq!(info.full_name(), Some("John".to_string()));
     }
 
+    #[test]
+    fn test_mailing_address_layout() {
+        let mut info = CallsignInfo {
+            call: "W1AW".to_string(),
+            fname: Some("Hiram".to_string()),
+            name: Some("Maxim".to_string()),
+            attn: Some(" ".to_string()),
+            addr1: Some("225 Main St".to_string()),
+            addr2: Some("Newington".to_string()),
+            state: Some("CT".to_string()),
+            zip: Some("06111".to_string()),
+            country: Some("United States".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.mailing_address().unwrap(),
+            "Hiram Maxim\n225 Main St\nNewington, CT 06111\nUnited States"
+        );
+
+        info.country = Some("Japan".to_string());
+        info.state = None;
+        info.zip = Some("100-0001".to_string());
+        assert_eq!(
+            info.mailing_address().unwrap(),
+            "Hiram Maxim\n225 Main St\nNewington\n100-0001\nJapan"
+        );
+
+        info.addr1 = None;
+        info.addr2 = None;
+        assert_eq!(info.mailing_address(), None);
+    }
+
+    #[test]
+    fn test_response_types_compare() {
+        let info = CallsignInfo {
+            call: "W1AW".to_string(),
+            lat: Some(41.714775),
+            ..Default::default()
+        };
+        let mut moved = info.clone();
+        assert_eq!(moved, info);
+        moved.lat = Some(41.7);
+        assert_ne!(moved, info);
+
+        let session = SessionInfo {
+            key: Some("abc".to_string()),
+            count: Some(1),
+            sub_exp: None,
+            gm_time: None,
+            message: None,
+            error: None,
+            remark: None,
+        };
+        let sessions: std::collections::HashSet<_> = [session.clone(), session].into();
+        assert_eq!(sessions.len(), 1);
+
+        assert_eq!(
+            BiographyData::new("W1AW", "<p>Hi</p>"),
+            BiographyData::new("W1AW", "<p>Hi</p>")
+        );
+    }
+
+    #[test]
+    fn test_zones_parsed() {
+        let mut info = CallsignInfo::builder().cqzone(5).ituzone(8).build();
+        assert_eq!(info.cq_zone_parsed().map(|zone| zone.number()), Some(5));
+        assert_eq!(info.itu_zone_parsed().map(|zone| zone.number()), Some(8));
+
+        info.cqzone = Some(0);
+        info.ituzone = Some(95);
+        assert_eq!(
+            (info.cq_zone_parsed(), info.itu_zone_parsed()),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn test_html_entities_decoded() {
+        let info: CallsignInfo = quick_xml::de::from_str(
+            "<Callsign><call>W1AW</call><fname>Jos&amp;eacute;</fname>\
+             <name>O&amp;#39;Brien</name><addr2>Chiang Mai</addr2>\
+             <attn>AT&amp;amp;T ARC</attn></Callsign>",
+        )
+        .unwrap();
+        assert_eq!(info.full_name().as_deref(), Some("José O'Brien"));
+        assert_eq!(info.addr2.as_deref(), Some("Chiang Mai"));
+        assert_eq!(info.attn.as_deref(), Some("AT&T ARC"));
+        assert_eq!(info.nickname, None);
+
+        let dxcc: DxccInfo = quick_xml::de::from_str(
+            "<DXCC><dxcc>90</dxcc><name>Trinidad &amp;amp; Tobago</name></DXCC>",
+        )
+        .unwrap();
+        assert_eq!(dxcc.name, "Trinidad & Tobago");
+    }
+
+    #[test]
+    fn test_display_name_precedence() {
+        let mut info = CallsignInfo {
+            call: "TEST".to_string(),
+            fname: Some("Jonathan".to_string()),
+            name: Some("Doe".to_string()),
+            nickname: Some("Jack".to_string()),
+            name_fmt: Some("Jonathan \"Jack\" Doe".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.display_name().as_deref(),
+            Some("Jonathan \"Jack\" Doe")
+        );
+
+        info.name_fmt = Some(" ".to_string());
+        assert_eq!(info.display_name().as_deref(), Some("Jack Doe"));
+
+        info.nickname = None;
+        assert_eq!(info.display_name().as_deref(), Some("Jonathan Doe"));
+
+        info = CallsignInfo::default();
+        assert_eq!(info.display_name(), None);
+    }
+
     #[test]
     fn test_coordinates() {
         let info = CallsignInfo {
@@ -573,12 +2344,220 @@ mod tests {
             eqsl: Some("Y".to_string()),
             mqsl: Some("N".to_string()),
             lotw: Some("y".to_string()),
+            dst: Some("N".to_string()),
+            ..Default::default()
+        };
+
+        let qsl = info.qsl_capabilities();
+        assert_eq!(qsl.eqsl, QslFlag::Yes);
+        assert_eq!(qsl.paper, QslFlag::No);
+        assert_eq!(qsl.lotw, QslFlag::Yes);
+        assert_eq!(qsl.paper.as_bool(), Some(false));
+
+        // Absent and unrecognized values are unknown rather than "no"
+        let info = CallsignInfo {
+            eqsl: Some("".to_string()),
+            mqsl: Some("?".to_string()),
+            ..info
+        };
+        let qsl = info.qsl_capabilities();
+        assert_eq!((qsl.eqsl, qsl.paper), (QslFlag::Unknown, QslFlag::Unknown));
+        assert_eq!(
+            CallsignInfo::default().qsl_capabilities(),
+            QslCapabilities::default()
+        );
+
+        #[allow(deprecated)]
+        {
+            assert_eq!(info.accepts_lotw(), Some(true));
+            assert_eq!(info.returns_paper_qsl(), Some(false));
+        }
+        assert_eq!(info.observes_dst(), Some(false));
+        assert_eq!(CallsignInfo::default().observes_dst(), None);
+    }
+
+    #[test]
+    fn test_club_detection() {
+        let club = CallsignInfo {
+            call: "W1AW".to_string(),
+            name: Some("ARRL HQ OPERATORS CLUB".to_string()),
+            attn: Some("TRUSTEE".to_string()),
+            ..Default::default()
+        };
+        assert!(club.is_club());
+        let view = club.club_info().unwrap();
+        assert_eq!(view.organization, Some("ARRL HQ OPERATORS CLUB"));
+        assert_eq!(view.attention, Some("TRUSTEE"));
+
+        let by_class = CallsignInfo {
+            call: "K1ABC".to_string(),
+            class: Some("C".to_string()),
+            ..Default::default()
+        };
+        assert!(by_class.is_club());
+
+        let by_codes = CallsignInfo {
+            call: "K1ABC".to_string(),
+            codes: Some("HVB".to_string()),
+            ..Default::default()
+        };
+        assert!(by_codes.is_club());
+        assert!(by_codes.license_codes().unwrap().is_vanity());
+
+        let person = CallsignInfo {
+            call: "AA7BQ".to_string(),
+            fname: Some("Fred".to_string()),
+            name: Some("Lloyd".to_string()),
+            class: Some("E".to_string()),
+            ..Default::default()
+        };
+        assert!(!person.is_club());
+        assert!(person.club_info().is_none());
+
+        // Surnames and business names are not clubs, even without a first name
+        for name in ["RADIO", "TEAM", "DX ENGINEERING INC", "LLOYD", "CLUBB"] {
+            let record = CallsignInfo {
+                call: "K1XYZ".to_string(),
+                name: Some(name.to_string()),
+                ..Default::default()
+            };
+            assert!(!record.is_club(), "{}", name);
+        }
+        let member = CallsignInfo {
+            fname: Some("Fred".to_string()),
+            name: Some("Club".to_string()),
+            ..Default::default()
+        };
+        assert!(!member.is_club());
+    }
+
+    #[test]
+    fn test_subscription_status() {
+        let mut session = SessionInfo {
+            key: None,
+            count: None,
+            sub_exp: Some("non-subscriber".to_string()),
+            gm_time: Some("Sun Aug 16 03:55:47 2024".to_string()),
+            message: None,
+            error: None,
+            remark: None,
+        };
+        assert_eq!(session.is_subscriber(), Some(false));
+
+        #[cfg(feature = "chrono")]
+        {
+            assert_eq!(session.sub_exp_datetime(), None);
+            assert_eq!(
+                session.gm_time_datetime().unwrap().to_rfc3339(),
+                "2024-08-16T03:55:47+00:00"
+            );
+        }
+
+        session.sub_exp = None;
+        assert_eq!(session.subscription_status(), SubscriptionStatus::Unknown);
+        session.message = Some("A subscription is required to access the complete record.".into());
+        assert_eq!(
+            session.subscription_status(),
+            SubscriptionStatus::NonSubscriber
+        );
+
+        session.sub_exp = Some("Wed Jan  1 12:34:03 2025".to_string());
+        assert_eq!(session.is_subscriber(), Some(true));
+        assert_eq!(
+            session.subscription_status(),
+            SubscriptionStatus::Subscriber {
+                expires: "Wed Jan  1 12:34:03 2025".to_string()
+            }
+        );
+
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            session.sub_exp_datetime().unwrap().to_rfc3339(),
+            "2025-01-01T12:34:03+00:00"
+        );
+    }
+
+    #[test]
+    fn test_callsign_subdivision() {
+        let info = CallsignInfo {
+            call: "VE3XYZ".to_string(),
+            dxcc: Some(1),
+            state: Some("ON".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(info.subdivision().unwrap().name, Some("Ontario"));
+
+        let info = CallsignInfo {
+            state: None,
+            ..info
+        };
+        assert_eq!(info.subdivision(), None);
+    }
+
+    #[test]
+    fn test_callsign_us_state() {
+        let mut info = CallsignInfo {
+            call: "AA7BQ".to_string(),
+            dxcc: Some(291),
+            state: Some("az".to_string()),
             ..Default::default()
         };
+        assert_eq!(info.state_parsed(), Some(UsState::Arizona));
+        assert_eq!(info.state_matches_dxcc(), Some(true));
 
-        assert_eq!(info.accepts_eqsl(), Some(true));
-        assert_eq!(info.returns_paper_qsl(), Some(false));
-        assert_eq!(info.accepts_lotw(), Some(true));
+        // A US state on a German record is bogus
+        info.dxcc = Some(230);
+        assert_eq!(info.state_matches_dxcc(), Some(false));
+
+        info.state = Some("NRW".to_string());
+        assert_eq!(info.state_parsed(), None);
+        assert_eq!(info.state_matches_dxcc(), None);
+    }
+
+    #[test]
+    fn test_biography_classification() {
+        assert_eq!(BiographyOutcome::classify("  \n"), BiographyOutcome::NoBio);
+        assert_eq!(
+            BiographyOutcome::classify("<p>Licensed in 1970</p>"),
+            BiographyOutcome::Html("<p>Licensed in 1970</p>".to_string())
+        );
+        assert_eq!(
+            BiographyOutcome::classify(
+                "<html><head><title>500 Internal Server Error</title></head></html>"
+            ),
+            BiographyOutcome::Error("500 Internal Server Error".to_string())
+        );
+
+        let xml = |error: &str| {
+            format!(
+                "<?xml version=\"1.0\" ?><QRZDatabase><Session><Error>{}</Error></Session></QRZDatabase>",
+                error
+            )
+        };
+        assert_eq!(
+            BiographyOutcome::classify(&xml("Session Timeout")),
+            BiographyOutcome::SessionExpired
+        );
+        assert_eq!(
+            BiographyOutcome::classify(&xml("A subscription is required")),
+            BiographyOutcome::SubscriptionRequired
+        );
+        assert_eq!(
+            BiographyOutcome::classify(&xml("A subscription is required to access this session")),
+            BiographyOutcome::SubscriptionRequired
+        );
+        assert_eq!(
+            BiographyOutcome::classify(&xml("Not found: XX1XX")),
+            BiographyOutcome::NotFound
+        );
+        assert_eq!(
+            BiographyOutcome::classify(&xml("No biography for XX1XX")),
+            BiographyOutcome::NoBio
+        );
+        assert_eq!(
+            BiographyOutcome::classify(&xml("Database offline")),
+            BiographyOutcome::Error("Database offline".to_string())
+        );
     }
 
     #[test]
@@ -595,4 +2574,154 @@ mod tests {
         dxcc.timezone = Some("545".to_string());
         assert_eq!(dxcc.timezone_hours(), Some(5.75)); // 5 hours 45 minutes
     }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_dxcc_utc_offset() {
+        let mut dxcc = DxccInfo {
+            timezone: Some("545".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(dxcc.utc_offset(), FixedOffset::east_opt(5 * 3600 + 45 * 60));
+
+        dxcc.timezone = Some("-3:30".to_string());
+        assert_eq!(dxcc.utc_offset(), FixedOffset::west_opt(3 * 3600 + 30 * 60));
+
+        dxcc.timezone = None;
+        assert_eq!(dxcc.utc_offset(), None);
+    }
+
+    #[test]
+    fn test_geoloc_source() {
+        let mut info = CallsignInfo {
+            geoloc: Some("user".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(info.geoloc_parsed(), Some(GeoLocationSource::User));
+        assert!(info.geoloc_parsed().unwrap().is_precise());
+
+        info.geoloc = Some("DXCC".to_string());
+        assert_eq!(info.geoloc_parsed(), Some(GeoLocationSource::Dxcc));
+        assert!(!GeoLocationSource::Dxcc.is_precise());
+        assert!(GeoLocationSource::Geocode < GeoLocationSource::Zip);
+
+        info.geoloc = Some("none".to_string());
+        assert_eq!(info.geoloc_parsed(), None);
+    }
+
+    #[cfg(feature = "iso3166")]
+    #[test]
+    fn test_dxcc_country_codes() {
+        let mut dxcc = DxccInfo {
+            cc: Some("us".to_string()),
+            ccc: Some("USA".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(dxcc.country_alpha2(), Some(CountryCode::USA));
+        assert_eq!(dxcc.country_alpha3(), Some(CountryCode::USA));
+
+        // DXCC entities without an ISO country of their own
+        dxcc.cc = Some("XX".to_string());
+        dxcc.ccc = None;
+        assert_eq!((dxcc.country_alpha2(), dxcc.country_alpha3()), (None, None));
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_json_schemas() {
+        let schema = serde_json::to_value(schemars::schema_for!(CallsignInfo)).unwrap();
+        assert_eq!(schema["properties"]["TimeZone"]["type"][0], "string");
+        assert_eq!(schema["properties"]["lat"]["type"][0], "number");
+        assert_eq!(schema["additionalProperties"]["type"], "string");
+
+        let schema = serde_json::to_value(schemars::schema_for!(DxccInfo)).unwrap();
+        assert_eq!(schema["properties"]["dxcc"]["type"], "integer");
+        let schema = serde_json::to_value(schemars::schema_for!(SessionInfo)).unwrap();
+        assert!(schema["properties"]["Key"].is_object());
+    }
+
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn test_geojson_features() {
+        let info = CallsignInfo {
+            call: "W1AW".to_string(),
+            grid: Some("FN31pr".to_string()),
+            dxcc: Some(291),
+            lat: Some(41.714775),
+            lon: Some(-72.72726),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(info.to_geojson_feature().unwrap()).unwrap(),
+            serde_json::json!({
+                "type": "Feature",
+                "id": "W1AW",
+                "geometry": { "type": "Point", "coordinates": [-72.72726, 41.714775] },
+                "properties": { "call": "W1AW", "grid": "FN31pr", "dxcc": 291 }
+            })
+        );
+
+        let mut dxcc = DxccInfo {
+            dxcc: 291,
+            name: "United States".to_string(),
+            continent: Some("NA".to_string()),
+            lat: Some(37.0),
+            lon: Some(-95.0),
+            ..Default::default()
+        };
+        let feature = dxcc.to_geojson_feature().unwrap();
+        assert_eq!(
+            feature.property("continent"),
+            Some(&serde_json::json!("NA"))
+        );
+
+        dxcc.lat = None;
+        assert!(dxcc.to_geojson_feature().is_none());
+    }
+
+    #[test]
+    fn test_utc_offset_parsing() {
+        let mut info = CallsignInfo {
+            gmt_offset: Some("-7".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(info.utc_offset_hours(), Some(-7.0));
+
+        for (offset, hours) in [
+            ("+5", Some(5.0)),
+            ("5.5", Some(5.5)),
+            ("-3:30", Some(-3.5)),
+            ("-930", Some(-9.5)),
+            ("", None),
+            ("EST", None),
+            ("5:75", None),
+            ("--5", None),
+        ] {
+            info.gmt_offset = Some(offset.to_string());
+            assert_eq!(info.utc_offset_hours(), hours, "{}", offset);
+        }
+    }
+
+    #[test]
+    fn test_dxcc_continent() {
+        let mut dxcc = DxccInfo {
+            continent: Some("na".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(dxcc.continent_parsed(), Some(Continent::NorthAmerica));
+
+        dxcc.continent = Some("XX".to_string());
+        assert_eq!(dxcc.continent_parsed(), None);
+
+        for continent in Continent::ALL {
+            assert_eq!(
+                continent.to_string().parse::<Continent>().unwrap(),
+                continent
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&Continent::Oceania).unwrap(),
+            "\"OC\""
+        );
+    }
 }
@@ -0,0 +1,78 @@
+//! Agent string composition.
+//!
+//! QRZ asks every client program to identify itself with a distinctive `agent`
+//! value made of its name and version, so misbehaving software can be traced
+//! to its author instead of blocking a whole library. [`UserAgent`] composes the
+//! application identity with this crate's identity; the result is used both for
+//! the HTTP `User-Agent` header and the `agent` login parameter.
+
+use crate::DEFAULT_USER_AGENT;
+use std::fmt;
+
+/// Agent string sent with every request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAgent(String);
+
+impl UserAgent {
+    /// Identify an application by name and version, e.g. `UserAgent::for_app("mylogger", "2.1")`
+    ///
+    /// Produces `mylogger/2.1 qrz-xml-rs/<crate version>`. Whitespace and `/` in
+    /// the name or version are replaced with `-` so the result stays a single token
+    /// per component.
+    pub fn for_app(name: &str, version: &str) -> Self {
+        Self(format!(
+            "{}/{} {}",
+            sanitize(name),
+            sanitize(version),
+            DEFAULT_USER_AGENT
+        ))
+    }
+
+    /// Get the agent string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for UserAgent {
+    fn default() -> Self {
+        Self(DEFAULT_USER_AGENT.to_string())
+    }
+}
+
+impl fmt::Display for UserAgent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<UserAgent> for String {
+    fn from(agent: UserAgent) -> Self {
+        agent.0
+    }
+}
+
+fn sanitize(part: &str) -> String {
+    part.trim()
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c == '/' {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_app() {
+        let agent = UserAgent::for_app("My Logger", "2.1");
+        assert!(agent.as_str().starts_with("My-Logger/2.1 qrz-xml-rs/"));
+        assert_eq!(UserAgent::default().as_str(), DEFAULT_USER_AGENT);
+    }
+}
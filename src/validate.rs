@@ -0,0 +1,131 @@
+//! Local validation of lookup inputs.
+//!
+//! A lookup of something that cannot exist still counts against the daily
+//! quota. With
+//! [`validate_queries`](crate::client::QrzXmlClientConfig::validate_queries)
+//! set, the client checks callsigns and DXCC entity numbers with these
+//! functions first and fails with [`QrzXmlError::InvalidInput`] without
+//! contacting QRZ.
+
+use crate::error::{QrzXmlError, Result};
+
+/// Highest DXCC entity number assigned so far (Kosovo, Z6)
+pub const MAX_DXCC_ENTITY: u32 = 522;
+
+/// Highest CQ zone
+pub const MAX_CQ_ZONE: u32 = 40;
+
+/// Highest ITU zone
+pub const MAX_ITU_ZONE: u32 = 90;
+
+/// Longest callsign accepted, including portable prefixes and suffixes
+const MAX_CALLSIGN_LEN: usize = 20;
+
+/// Check that a callsign is plausible under the ITU callsign format
+///
+/// The callsign may carry `/`-separated prefixes and suffixes such as `VE3/`
+/// or `/P`, but one part must look like a base callsign: 3 to 10 letters and
+/// digits, with at least one digit, ending in a letter.
+pub fn check_callsign(callsign: &str) -> Result<()> {
+    let callsign = callsign.trim();
+    if callsign.is_empty() {
+        return Err(QrzXmlError::invalid_input("Callsign cannot be empty"));
+    }
+    if callsign.len() > MAX_CALLSIGN_LEN {
+        return Err(QrzXmlError::invalid_input(format!(
+            "Callsign {} is longer than {} characters",
+            callsign, MAX_CALLSIGN_LEN
+        )));
+    }
+
+    let parts: Vec<&str> = callsign.split('/').collect();
+    let well_formed = parts
+        .iter()
+        .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_alphanumeric()));
+    if !well_formed || !parts.iter().any(|part| is_base_callsign(part)) {
+        return Err(QrzXmlError::invalid_input(format!(
+            "{} is not a plausible callsign",
+            callsign
+        )));
+    }
+    Ok(())
+}
+
+/// Check that a DXCC entity number is within the assigned range
+pub fn check_dxcc_entity(entity: u32) -> Result<()> {
+    if (1..=MAX_DXCC_ENTITY).contains(&entity) {
+        Ok(())
+    } else {
+        Err(QrzXmlError::invalid_input(format!(
+            "DXCC entity {} is outside 1-{}",
+            entity, MAX_DXCC_ENTITY
+        )))
+    }
+}
+
+/// Check that a CQ zone is within 1-40
+pub fn is_valid_cq_zone(zone: u32) -> bool {
+    (1..=MAX_CQ_ZONE).contains(&zone)
+}
+
+/// Check that an ITU zone is within 1-90
+pub fn is_valid_itu_zone(zone: u32) -> bool {
+    (1..=MAX_ITU_ZONE).contains(&zone)
+}
+
+pub(crate) fn is_base_callsign(part: &str) -> bool {
+    (3..=10).contains(&part.len())
+        && part.bytes().any(|b| b.is_ascii_digit())
+        && part.bytes().last().is_some_and(|b| b.is_ascii_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_callsign() {
+        for call in [
+            "AA7BQ",
+            "w1aw",
+            "4U1UN",
+            "VE3/AA7BQ",
+            "AA7BQ/P",
+            "GB13COL",
+            "K1A",
+        ] {
+            assert!(check_callsign(call).is_ok(), "{}", call);
+        }
+        for call in [
+            "",
+            "   ",
+            "AA7BQ!",
+            "HELLO",
+            "12345",
+            "AA7BQ//P",
+            "A1",
+            "AA7BQAA7BQAA7BQAA7BQ/P",
+        ] {
+            assert!(
+                matches!(check_callsign(call), Err(QrzXmlError::InvalidInput { .. })),
+                "{}",
+                call
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_dxcc_entity() {
+        assert!(check_dxcc_entity(291).is_ok());
+        assert!(check_dxcc_entity(MAX_DXCC_ENTITY).is_ok());
+        assert!(check_dxcc_entity(0).is_err());
+        assert!(check_dxcc_entity(9999).is_err());
+    }
+
+    #[test]
+    fn test_zone_validity() {
+        assert!(is_valid_cq_zone(1) && is_valid_cq_zone(40));
+        assert!(!is_valid_cq_zone(0) && !is_valid_cq_zone(41));
+        assert!(is_valid_itu_zone(90) && !is_valid_itu_zone(91));
+    }
+}
@@ -0,0 +1,179 @@
+//! vCard export.
+//!
+//! [`CallsignInfo::to_vcard`](crate::CallsignInfo::to_vcard) renders a record
+//! as a vCard 4.0 ([RFC 6350](https://www.rfc-editor.org/rfc/rfc6350)) that
+//! contacts applications can import. The callsign goes into the `X-CALLSIGN`
+//! extension property, and club stations are marked with `KIND:org`.
+
+use crate::types::CallsignInfo;
+
+/// Longest content line in octets before it is folded
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Render a callsign record as a vCard 4.0
+pub(crate) fn to_vcard(info: &CallsignInfo) -> String {
+    let mut card = VCard::default();
+    card.property("BEGIN", "VCARD");
+    card.property("VERSION", "4.0");
+    if info.is_club() {
+        card.property("KIND", "org");
+    }
+
+    let full_name = info.display_name().unwrap_or_else(|| info.call.clone());
+    card.property("FN", &escape(&full_name));
+    card.property(
+        "N",
+        &structured(&[
+            info.name.as_deref(),
+            info.fname.as_deref(),
+            None,
+            None,
+            None,
+        ]),
+    );
+    if let Some(nickname) = non_empty(&info.nickname) {
+        card.property("NICKNAME", &escape(nickname));
+    }
+    if [
+        &info.addr1,
+        &info.addr2,
+        &info.state,
+        &info.zip,
+        &info.country,
+    ]
+    .into_iter()
+    .any(|field| non_empty(field).is_some())
+    {
+        card.property(
+            "ADR",
+            &structured(&[
+                None,
+                None,
+                non_empty(&info.addr1),
+                non_empty(&info.addr2),
+                non_empty(&info.state),
+                non_empty(&info.zip),
+                non_empty(&info.country),
+            ]),
+        );
+    }
+    if let Some(email) = non_empty(&info.email) {
+        card.property("EMAIL", &escape(email));
+    }
+    if let Some(url) = non_empty(&info.url) {
+        card.property("URL", url);
+    }
+    if let Some((lat, lon)) = info.coordinates() {
+        card.property("GEO", &format!("geo:{},{}", lat, lon));
+    }
+    card.property("X-CALLSIGN", &escape(&info.call));
+    card.property("END", "VCARD");
+    card.0
+}
+
+#[derive(Default)]
+struct VCard(String);
+
+impl VCard {
+    /// Append a content line, folded to 75 octets and terminated by CRLF
+    fn property(&mut self, name: &str, value: &str) {
+        let line = format!("{}:{}", name, value);
+        let mut octets = 0;
+        for c in line.chars() {
+            if octets + c.len_utf8() > MAX_LINE_OCTETS {
+                self.0.push_str("\r\n ");
+                octets = 1;
+            }
+            octets += c.len_utf8();
+            self.0.push(c);
+        }
+        self.0.push_str("\r\n");
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Escape a text value
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.trim().chars() {
+        match c {
+            '\\' | ',' | ';' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Join the components of a structured value such as `N` or `ADR`
+fn structured(components: &[Option<&str>]) -> String {
+    components
+        .iter()
+        .map(|component| component.map(escape).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_vcard() {
+        let info = CallsignInfo {
+            call: "AA7BQ".to_string(),
+            fname: Some("Fred L".to_string()),
+            name: Some("Lloyd".to_string()),
+            addr1: Some("8711 E Pinnacle Peak Rd 159".to_string()),
+            addr2: Some("Scottsdale".to_string()),
+            state: Some("AZ".to_string()),
+            zip: Some("85255".to_string()),
+            country: Some("United States".to_string()),
+            email: Some("flloyd@qrz.com".to_string()),
+            lat: Some(34.23),
+            lon: Some(-111.34),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            info.to_vcard(),
+            "BEGIN:VCARD\r\n\
+             VERSION:4.0\r\n\
+             FN:Fred L Lloyd\r\n\
+             N:Lloyd;Fred L;;;\r\n\
+             ADR:;;8711 E Pinnacle Peak Rd 159;Scottsdale;AZ;85255;United States\r\n\
+             EMAIL:flloyd@qrz.com\r\n\
+             GEO:geo:34.23,-111.34\r\n\
+             X-CALLSIGN:AA7BQ\r\n\
+             END:VCARD\r\n"
+        );
+    }
+
+    #[test]
+    fn test_escape_and_fold() {
+        assert_eq!(
+            escape("Radio Club; Inc, \\ HQ\n"),
+            "Radio Club\\; Inc\\, \\\\ HQ"
+        );
+
+        let mut card = VCard::default();
+        card.property("NOTE", &"ü".repeat(40));
+        let lines: Vec<&str> = card.0.trim_end().split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert_eq!(
+            lines.concat().replace(" ü", "ü"),
+            format!("NOTE:{}", "ü".repeat(40))
+        );
+    }
+}
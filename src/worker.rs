@@ -0,0 +1,150 @@
+//! Channel-driven lookup workers.
+//!
+//! Applications built as pipelines of async tasks (cluster spot feeds, log
+//! importers, contest dupe checkers) can hand callsigns to a worker over a
+//! channel and receive results on another. The worker looks callsigns up one at
+//! a time, so a full output channel holds back reading more input, backs off
+//! when QRZ rate limits it and stops on request.
+
+use crate::client::QrzXmlClient;
+use crate::error::{QrzXmlError, Result};
+use crate::types::CallsignInfo;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+/// First wait after QRZ rejects a lookup for exceeding its rate limit
+const INITIAL_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(1);
+/// Longest wait between attempts while rate limited
+const MAX_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// The outcome of a lookup performed by a [`LookupWorker`]
+#[derive(Debug)]
+pub struct LookupResult {
+    /// The callsign as received on the input channel
+    pub callsign: String,
+    /// The lookup result
+    pub result: Result<CallsignInfo>,
+}
+
+impl LookupResult {
+    /// The alias the record was found by, see [`CallsignInfo::resolved_via_alias`]
+    pub fn resolved_via_alias(&self) -> Option<&str> {
+        self.result.as_ref().ok()?.resolved_via_alias()
+    }
+}
+
+/// Handle to a running lookup worker
+///
+/// The worker stops on its own when the input channel is closed and drained,
+/// or when the output channel's receiver is dropped. Dropping the handle
+/// leaves the worker running.
+#[derive(Debug)]
+pub struct LookupWorker {
+    shutdown: watch::Sender<bool>,
+    handle: JoinHandle<()>,
+}
+
+impl LookupWorker {
+    /// Check if the worker has stopped
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Wait for the worker to stop on its own
+    pub async fn join(self) {
+        let _ = self.handle.await;
+    }
+
+    /// Stop the worker and wait for it to exit
+    ///
+    /// A lookup in progress, a rate limit backoff or a send waiting for room
+    /// in the output channel is abandoned; callsigns still queued in the input
+    /// channel are left unread.
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        let _ = self.handle.await;
+    }
+}
+
+/// Spawn a worker looking up callsigns from `rx` and sending results to `tx`
+pub(crate) fn spawn(
+    client: Arc<QrzXmlClient>,
+    rx: mpsc::Receiver<String>,
+    tx: mpsc::Sender<LookupResult>,
+) -> LookupWorker {
+    let (shutdown, signal) = watch::channel(false);
+    let handle = tokio::spawn(run(client, rx, tx, signal));
+    LookupWorker { shutdown, handle }
+}
+
+async fn run(
+    client: Arc<QrzXmlClient>,
+    mut rx: mpsc::Receiver<String>,
+    tx: mpsc::Sender<LookupResult>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        let callsign = tokio::select! {
+            _ = shutdown.changed() => break,
+            callsign = rx.recv() => match callsign {
+                Some(callsign) => callsign,
+                None => break,
+            },
+        };
+
+        let result = tokio::select! {
+            _ = shutdown.changed() => break,
+            result = lookup(&client, &callsign) => result,
+        };
+
+        tokio::select! {
+            _ = shutdown.changed() => break,
+            sent = tx.send(LookupResult { callsign, result }) => {
+                if sent.is_err() {
+                    debug!("Lookup worker output closed");
+                    break;
+                }
+            }
+        }
+    }
+
+    debug!("Lookup worker stopped");
+}
+
+/// Look up a callsign, waiting out rate limit rejections
+async fn lookup(client: &QrzXmlClient, callsign: &str) -> Result<CallsignInfo> {
+    let mut backoff = INITIAL_RATE_LIMIT_BACKOFF;
+    loop {
+        match client.lookup_callsign(callsign).await {
+            Err(QrzXmlError::RateLimitExceeded) => {
+                warn!("Rate limited, retrying {} in {:?}", callsign, backoff);
+                client.clock.sleep(backoff).await;
+                backoff = next_backoff(backoff);
+            }
+            result => return result,
+        }
+    }
+}
+
+fn next_backoff(backoff: Duration) -> Duration {
+    (backoff * 2).min(MAX_RATE_LIMIT_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_is_capped() {
+        assert_eq!(
+            next_backoff(INITIAL_RATE_LIMIT_BACKOFF),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            next_backoff(Duration::from_secs(40)),
+            MAX_RATE_LIMIT_BACKOFF
+        );
+    }
+}
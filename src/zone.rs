@@ -0,0 +1,116 @@
+//! CQ and ITU zones.
+//!
+//! QRZ stores zones as plain numbers, and the occasional record carries a
+//! zero or a number far outside the legal range. [`CqZone`] (1-40) and
+//! [`ItuZone`] (1-90) only hold valid zones, so award tracking keyed by them
+//! cannot pick up corrupt data. The `cq_zone_parsed` and `itu_zone_parsed`
+//! accessors of [`CallsignInfo`](crate::CallsignInfo) and
+//! [`DxccInfo`](crate::DxccInfo) return `None` for out-of-range values.
+
+use crate::error::{QrzXmlError, Result};
+use crate::validate::{is_valid_cq_zone, is_valid_itu_zone, MAX_CQ_ZONE, MAX_ITU_ZONE};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! zone_type {
+    ($(#[$meta:meta])* $name:ident, $label:literal, $max:ident, $valid:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(try_from = "u32", into = "u32")]
+        pub struct $name(u8);
+
+        impl $name {
+            /// The zone with this number, or `None` if it is out of range
+            pub fn new(zone: u32) -> Option<Self> {
+                $valid(zone).then(|| Self(zone as u8))
+            }
+
+            /// The zone number
+            pub fn number(&self) -> u32 {
+                u32::from(self.0)
+            }
+        }
+
+        impl TryFrom<u32> for $name {
+            type Error = QrzXmlError;
+
+            fn try_from(zone: u32) -> Result<Self> {
+                Self::new(zone).ok_or_else(|| {
+                    QrzXmlError::invalid_input(format!(
+                        "{} zone {} is outside 1-{}",
+                        $label, zone, $max
+                    ))
+                })
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(zone: $name) -> Self {
+                zone.number()
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = QrzXmlError;
+
+            fn from_str(s: &str) -> Result<Self> {
+                let zone = s.trim().parse::<u32>().map_err(|_| {
+                    QrzXmlError::invalid_input(format!("Invalid {} zone: {}", $label, s))
+                })?;
+                Self::try_from(zone)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+zone_type!(
+    /// A CQ zone, 1 to 40
+    CqZone,
+    "CQ",
+    MAX_CQ_ZONE,
+    is_valid_cq_zone
+);
+
+zone_type!(
+    /// An ITU zone, 1 to 90
+    ItuZone,
+    "ITU",
+    MAX_ITU_ZONE,
+    is_valid_itu_zone
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_ranges() {
+        assert_eq!(CqZone::new(40).map(|zone| zone.number()), Some(40));
+        assert_eq!(CqZone::new(41), None);
+        assert_eq!(CqZone::new(0), None);
+        assert_eq!(
+            ItuZone::new(90).map(|zone| zone.to_string()),
+            Some("90".to_string())
+        );
+        assert!(matches!(
+            ItuZone::try_from(91),
+            Err(QrzXmlError::InvalidInput { .. })
+        ));
+        assert_eq!(" 5 ".parse::<CqZone>().unwrap(), CqZone::new(5).unwrap());
+        assert!("five".parse::<CqZone>().is_err());
+    }
+
+    #[test]
+    fn test_serde_as_number() {
+        let zone: ItuZone = serde_json::from_str("6").unwrap();
+        assert_eq!(serde_json::to_string(&zone).unwrap(), "6");
+        assert!(serde_json::from_str::<CqZone>("99").is_err());
+    }
+}
@@ -4,8 +4,13 @@
 //! and test the complete flow without hitting the real API.
 
 use qrz_xml::client::QrzXmlClientConfig;
-use qrz_xml::{ApiVersion, QrzXmlClient, QrzXmlError};
-use wiremock::matchers::{method, query_param};
+use qrz_xml::{
+    ApiVersion, BiographyOutcome, ClientEvent, CredentialPool, IpFamily, MemorySessionStore,
+    QrzXmlClient, QrzXmlError, QueryParams, RotationStrategy, SessionSnapshot, SessionStore,
+    StoredSession, SubscriptionStatus, UserAgent,
+};
+use std::sync::Arc;
+use wiremock::matchers::{header, method, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 const SAMPLE_LOGIN_RESPONSE: &str = r#"<?xml version="1.0" ?>
@@ -104,6 +109,7 @@ async fn create_test_client(mock_server_uri: &str) -> QrzXmlClient {
         user_agent: "qrz-test/1.0".to_string(),
         timeout_seconds: 5,
         max_retries: 1,
+        ..Default::default()
     };
 
     QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap()
@@ -127,6 +133,57 @@ async fn test_successful_authentication() {
     assert!(client.is_authenticated().await);
 }
 
+#[tokio::test]
+async fn test_app_agent_string() {
+    let mock_server = MockServer::start().await;
+    let agent = UserAgent::for_app("testlogger", "2.1");
+
+    Mock::given(method("GET"))
+        .and(query_param("agent", agent.as_str()))
+        .and(header("user-agent", agent.as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        ..Default::default()
+    }
+    .with_user_agent(agent);
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+
+    client.authenticate().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_ensure_authenticated_logs_in_once() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    client.ensure_authenticated().await.unwrap();
+    client.ensure_authenticated().await.unwrap();
+    assert!(client.is_authenticated().await);
+
+    // A resumed session is kept as it is
+    let resumed = create_test_client(&mock_server.uri())
+        .await
+        .resume_session("resumed_key");
+    resumed.ensure_authenticated().await.unwrap();
+    assert_eq!(
+        resumed.current_session_key().await.as_deref(),
+        Some("resumed_key")
+    );
+}
+
 #[tokio::test]
 async fn test_authentication_failure() {
     let mock_server = MockServer::start().await;
@@ -150,6 +207,237 @@ async fn test_authentication_failure() {
     }
 }
 
+#[tokio::test]
+async fn test_authentication_failure_locks_out_logins() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_AUTH_ERROR_RESPONSE))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    assert!(matches!(
+        client.authenticate().await,
+        Err(QrzXmlError::AuthenticationFailed { .. })
+    ));
+    assert!(client.is_login_locked_out().await);
+
+    // Later logins fail fast without reaching QRZ
+    match client.lookup_callsign("AA7BQ").await {
+        Err(QrzXmlError::LoginLockedOut { reason }) => {
+            assert!(reason.contains("Username/password incorrect"));
+        }
+        other => panic!("Expected LoginLockedOut error, got {:?}", other),
+    }
+
+    client.reset_login_lockout().await;
+    assert!(matches!(
+        client.lookup_callsign("AA7BQ").await,
+        Err(QrzXmlError::AuthenticationFailed { .. })
+    ));
+
+    // An explicit login is always sent, and locks out again when rejected
+    assert!(matches!(
+        client.authenticate().await,
+        Err(QrzXmlError::AuthenticationFailed { .. })
+    ));
+    assert!(client.is_login_locked_out().await);
+}
+
+#[tokio::test]
+async fn test_set_password_clears_lockout() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("password", "testpass"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_AUTH_ERROR_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("password", "newpass"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    assert!(client.lookup_callsign("AA7BQ").await.is_err());
+    assert!(client.is_login_locked_out().await);
+
+    client.set_password("newpass").await;
+    assert!(!client.is_login_locked_out().await);
+    let info = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(info.call, "AA7BQ");
+}
+
+#[tokio::test]
+async fn test_repeated_login_failures_start_cooldown() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_AUTH_ERROR_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        lock_out_after_auth_failure: false,
+        max_failed_logins: Some(2),
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+
+    for _ in 0..2 {
+        assert!(matches!(
+            client.authenticate().await,
+            Err(QrzXmlError::AuthenticationFailed { .. })
+        ));
+    }
+
+    // The third login fails fast without reaching QRZ
+    let result = client.lookup_callsign("AA7BQ").await;
+    assert!(matches!(result, Err(QrzXmlError::LoginCoolingDown { .. })));
+    assert!(result.unwrap_err().is_retryable());
+}
+
+const SAMPLE_CONNECTION_REFUSED_RESPONSE: &str = r#"<?xml version="1.0" ?>
+<QRZDatabase version="1.34">
+  <Session>
+    <Error>Connection refused</Error>
+    <GMTime>Sun Aug 16 03:56:47 2024</GMTime>
+  </Session>
+</QRZDatabase>"#;
+
+#[tokio::test]
+async fn test_connection_refused_stops_requests() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SAMPLE_CONNECTION_REFUSED_RESPONSE),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let store = Arc::new(MemorySessionStore::new());
+    let client = create_test_client(&mock_server.uri())
+        .await
+        .with_session_store(store.clone());
+
+    let until = match client.authenticate().await {
+        Err(QrzXmlError::ConnectionRefused { until }) => until,
+        other => panic!("Expected ConnectionRefused error, got {:?}", other),
+    };
+    assert_eq!(client.connection_refused_until().await, Some(until));
+
+    // Neither this client nor another one sharing the store contacts QRZ again
+    assert!(matches!(
+        client.lookup_callsign("AA7BQ").await,
+        Err(QrzXmlError::ConnectionRefused { .. })
+    ));
+    let other = create_test_client(&mock_server.uri())
+        .await
+        .with_session_store(store.clone());
+    assert!(matches!(
+        other.lookup_callsign("AA7BQ").await,
+        Err(QrzXmlError::ConnectionRefused { .. })
+    ));
+
+    client.clear_connection_refused().await;
+    assert_eq!(client.connection_refused_until().await, None);
+    assert_eq!(store.load_refused_until("testuser").unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_extra_headers_and_query_params() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .and(query_param("tenant", "club"))
+        .and(header("x-forwarded-for", "192.0.2.10"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .and(query_param("tenant", "club"))
+        .and(query_param("route&zone", "a b"))
+        .and(header("x-forwarded-for", "192.0.2.10"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        ..Default::default()
+    }
+    .with_header("X-Forwarded-For", "192.0.2.10")
+    .with_query_param("tenant", "club")
+    .with_query_param("route&zone", "a b");
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+    client.lookup_callsign("AA7BQ").await.unwrap();
+
+    for config in [
+        QrzXmlClientConfig::default().with_query_param("password", "override"),
+        QrzXmlClientConfig::default().with_query_param("callsign", "W1AW"),
+        QrzXmlClientConfig::default().with_query_param("HTML", "W1AW"),
+        QrzXmlClientConfig::default().with_header("Bad Header", "value"),
+    ] {
+        assert!(matches!(
+            QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config),
+            Err(QrzXmlError::InvalidInput { .. })
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_ip_family_restricts_connections() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // The mock server listens on IPv4 loopback only
+    let client_for = |ip_family| {
+        let config = QrzXmlClientConfig {
+            base_url: format!("{}/xml", mock_server.uri()),
+            ip_family,
+            ..Default::default()
+        };
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap()
+    };
+
+    client_for(IpFamily::V4).authenticate().await.unwrap();
+    assert!(matches!(
+        client_for(IpFamily::V6).authenticate().await,
+        Err(QrzXmlError::Network(_))
+    ));
+}
+
 #[tokio::test]
 async fn test_successful_callsign_lookup() {
     let mock_server = MockServer::start().await;
@@ -182,9 +470,10 @@ async fn test_successful_callsign_lookup() {
     assert_eq!(callsign_info.full_name(), Some("FRED LLOYD".to_string()));
     assert_eq!(callsign_info.state, Some("AZ".to_string()));
     assert_eq!(callsign_info.grid, Some("DM32af".to_string()));
-    assert_eq!(callsign_info.accepts_eqsl(), Some(true));
-    assert_eq!(callsign_info.returns_paper_qsl(), Some(false));
-    assert_eq!(callsign_info.accepts_lotw(), Some(true));
+    let qsl = callsign_info.qsl_capabilities();
+    assert_eq!(qsl.eqsl.as_bool(), Some(true));
+    assert_eq!(qsl.paper.as_bool(), Some(false));
+    assert_eq!(qsl.lotw.as_bool(), Some(true));
 
     let coords = callsign_info.coordinates();
     assert!(coords.is_some());
@@ -265,111 +554,1874 @@ async fn test_successful_dxcc_lookup() {
 }
 
 #[tokio::test]
-async fn test_session_timeout_and_reauthentication() {
+async fn test_dxcc_lookup_latency_histogram() {
     let mock_server = MockServer::start().await;
 
-    // Mock initial login
     Mock::given(method("GET"))
         .and(query_param("username", "testuser"))
-        .and(query_param("password", "testpass"))
         .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
-        .expect(2) // Will be called twice due to re-auth
         .mount(&mock_server)
         .await;
 
-    // Mock session timeout on first callsign request
     Mock::given(method("GET"))
-        .and(query_param("s", "test_session_key_12345"))
-        .and(query_param("callsign", "AA7BQ"))
-        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
-        .expect(2)
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_DXCC_RESPONSE))
         .mount(&mock_server)
         .await;
 
     let client = create_test_client(&mock_server.uri()).await;
+    client.lookup_dxcc_entity(291).await.unwrap();
 
-    // First authenticate manually
-    client.authenticate().await.unwrap();
+    let histograms = client.latency_histograms();
+    assert_eq!(histograms["dxcc"].count(), 1);
+    assert!(!histograms.contains_key("callsign"));
+}
 
-    // Try to make a request - this should detect the session timeout and NOT retry
-    // since our current implementation only retries once and the timeout response
-    // doesn't contain a session key
-    let result = client.lookup_callsign("AA7BQ").await;
+#[tokio::test]
+async fn test_dxcc_cache_separate_from_callsigns() {
+    let mock_server = MockServer::start().await;
 
-    // This should fail with session expired, not succeed
-    assert!(result.is_err());
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    // Once for the entity, once for the callsign "291"
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_DXCC_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    // Callsign caching stays off, so both lookups reach the server
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        dxcc_cache_ttl_seconds: Some(3600),
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+    for _ in 0..2 {
+        assert_eq!(client.lookup_dxcc_entity(291).await.unwrap().dxcc, 291);
+        assert_eq!(client.lookup_callsign("AA7BQ").await.unwrap().call, "AA7BQ");
+    }
+
+    // Entity numbers and callsigns are cached apart, so this is no cache hit
+    client.lookup_dxcc_by_callsign("291").await.unwrap();
+    client.lookup_dxcc_by_callsign("291").await.unwrap();
 }
 
 #[tokio::test]
-async fn test_invalid_input_handling() {
+async fn test_cache_hits_are_validated_and_tracked() {
     let mock_server = MockServer::start().await;
-    let client = create_test_client(&mock_server.uri()).await;
 
-    // Test empty callsign
-    let result = client.lookup_callsign("").await;
-    assert!(result.is_err());
-    match result.unwrap_err() {
-        QrzXmlError::InvalidInput { .. } => {}
-        _ => panic!("Expected InvalidInput error for empty callsign"),
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_DXCC_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        validate_queries: true,
+        dxcc_cache_ttl_seconds: Some(3600),
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+    let mut events = client.subscribe_events();
+    assert_eq!(client.lookup_dxcc_entity(291).await.unwrap().dxcc, 291);
+    assert_eq!(client.lookup_dxcc_entity(291).await.unwrap().dxcc, 291);
+    assert_eq!(client.latency_histograms()["dxcc"].count(), 2);
+
+    // The entity is cached, but "291" is still no callsign
+    assert!(matches!(
+        client.lookup_dxcc_by_callsign("291").await,
+        Err(QrzXmlError::InvalidInput { .. })
+    ));
+    assert_eq!(client.latency_histograms()["dxcc"].count(), 3);
+    loop {
+        match events.try_recv().unwrap() {
+            ClientEvent::LookupFailed { query, .. } => {
+                assert_eq!(query, "291");
+                break;
+            }
+            _ => continue,
+        }
     }
 }
 
 #[tokio::test]
-async fn test_session_info_tracking() {
+async fn test_station_lookup() {
     let mock_server = MockServer::start().await;
 
-    // Mock login
     Mock::given(method("GET"))
         .and(query_param("username", "testuser"))
-        .and(query_param("password", "testpass"))
         .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
         .mount(&mock_server)
         .await;
 
-    let client = create_test_client(&mock_server.uri()).await;
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .mount(&mock_server)
+        .await;
 
-    // Before authentication
-    assert!(!client.is_authenticated().await);
-    let session_info = client.session_info().await;
-    assert!(session_info.is_some()); // session_info always returns Some, but values may be None
-    let (count, sub_exp) = session_info.unwrap();
-    assert_eq!(count, None);
-    assert_eq!(sub_exp, None);
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_DXCC_RESPONSE))
+        .mount(&mock_server)
+        .await;
 
-    // After authentication
-    client.authenticate().await.unwrap();
-    assert!(client.is_authenticated().await);
+    let client = create_test_client(&mock_server.uri()).await;
+    let station = client.station("aa7bq").await.unwrap();
 
-    let session_info = client.session_info().await;
-    assert!(session_info.is_some());
-    let (count, sub_exp) = session_info.unwrap();
-    assert_eq!(count, Some(42));
-    assert_eq!(sub_exp, Some("Wed Jan 1 12:34:03 2025".to_string()));
+    assert_eq!(station.call(), "AA7BQ");
+    assert_eq!(station.dxcc.as_ref().unwrap().name, "United States");
+    assert_eq!(station.coordinates, Some((34.12345, -112.12345)));
+    assert_eq!(station.grid.as_deref(), Some("DM32af"));
+    assert!(station.provenance.dxcc_resolved);
+    assert!(!station.provenance.grid_computed);
 }
 
-// Helper functions for testing individual components
-
 #[tokio::test]
-async fn test_error_type_properties() {
-    // Test error categorization
-    assert!(QrzXmlError::SessionExpired.should_reauthenticate());
-    assert!(QrzXmlError::NoSessionKey.should_reauthenticate());
-    assert!(!QrzXmlError::CallsignNotFound {
-        callsign: "TEST".to_string()
-    }
-    .should_reauthenticate());
+async fn test_biography_outcomes() {
+    let mock_server = MockServer::start().await;
 
-    assert!(QrzXmlError::RateLimitExceeded.is_retryable());
-    assert!(QrzXmlError::SessionExpired.is_retryable());
-    assert!(!QrzXmlError::AuthenticationFailed {
-        reason: "bad pass".to_string()
-    }
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    // The first biography request hits an expired session
+    Mock::given(method("GET"))
+        .and(query_param("html", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("html", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<p>Licensed in 1970</p>"))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("html", "W1AW"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(""))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("html", "XX1XX"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_ERROR_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+
+    let bio = client.lookup_biography("aa7bq").await.unwrap();
+    assert_eq!(bio.html(), "<p>Licensed in 1970</p>");
+
+    assert_eq!(
+        client.lookup_biography_outcome("W1AW").await.unwrap(),
+        BiographyOutcome::NoBio
+    );
+    assert!(client.lookup_biography("W1AW").await.unwrap().is_empty());
+
+    assert!(matches!(
+        client.lookup_biography("XX1XX").await,
+        Err(QrzXmlError::CallsignNotFound { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_generic_query_dispatch() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_DXCC_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+
+    let result = client
+        .query(&QueryParams::new().param("dxcc", "291"))
+        .await
+        .unwrap();
+    assert_eq!(result.as_dxcc().unwrap().name, "United States");
+    assert!(result.as_callsign().is_none());
+
+    let result = client
+        .query(&QueryParams::new().param("s", "other_key"))
+        .await;
+    assert!(matches!(result, Err(QrzXmlError::InvalidInput { .. })));
+}
+
+#[tokio::test]
+async fn test_session_timeout_and_reauthentication() {
+    let mock_server = MockServer::start().await;
+
+    // Mock initial login
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .and(query_param("password", "testpass"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(2) // Will be called twice due to re-auth
+        .mount(&mock_server)
+        .await;
+
+    // Mock session timeout on first callsign request
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+
+    // First authenticate manually
+    client.authenticate().await.unwrap();
+
+    // Try to make a request - this should detect the session timeout and NOT retry
+    // since our current implementation only retries once and the timeout response
+    // doesn't contain a session key
+    let result = client.lookup_callsign("AA7BQ").await;
+
+    // This should fail with session expired, not succeed
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_dxcc_lookup_renews_expired_session() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_DXCC_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let dxcc = client.lookup_dxcc_entity(291).await.unwrap();
+    assert_eq!(dxcc.dxcc, 291);
+}
+
+#[tokio::test]
+async fn test_hedged_request() {
+    let mock_server = MockServer::start().await;
+
+    // Without reported counts, the quota is the client's own count
+    let without_count = |body: &str| {
+        body.replace("<Count>42</Count>", "")
+            .replace("<Count>43</Count>", "")
+    };
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(without_count(SAMPLE_LOGIN_RESPONSE)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    // The first lookup hangs, the hedged one answers immediately
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(without_count(SAMPLE_CALLSIGN_RESPONSE))
+                .set_delay(std::time::Duration::from_secs(3)),
+        )
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(without_count(SAMPLE_CALLSIGN_RESPONSE)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        hedge_after_ms: Some(100),
+        retry_budget_per_minute: Some(1),
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+
+    let started = std::time::Instant::now();
+    let info = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(info.call, "AA7BQ");
+    assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    assert_eq!(client.quota().used, Some(2));
+}
+
+#[tokio::test]
+async fn test_session_store_restores_and_saves() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let store = Arc::new(MemorySessionStore::new());
+    store
+        .save(&StoredSession::new("testuser", "test_session_key_12345"))
+        .unwrap();
+
+    let client = create_test_client(&mock_server.uri())
+        .await
+        .with_session_store(store.clone());
+    let info = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(info.call, "AA7BQ");
+
+    // A fresh login is written back to the store
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let store = Arc::new(MemorySessionStore::new());
+    let client = create_test_client(&mock_server.uri())
+        .await
+        .with_session_store(store.clone());
+    client.authenticate().await.unwrap();
+
+    let stored = store.load("testuser").unwrap().unwrap();
+    assert_eq!(stored.key, "test_session_key_12345");
+    assert_eq!(stored.count, Some(42));
+}
+
+#[tokio::test]
+async fn test_resume_session_falls_back_to_login() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "stale_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri())
+        .await
+        .resume_session("stale_key");
+    assert!(client.is_authenticated().await);
+    assert_eq!(
+        client.current_session_key().await.as_deref(),
+        Some("stale_key")
+    );
+
+    let info = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(info.call, "AA7BQ");
+    assert_eq!(
+        client.current_session_key().await.as_deref(),
+        Some("test_session_key_12345")
+    );
+}
+
+#[tokio::test]
+async fn test_expired_session_adopts_key_renewed_elsewhere() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "stale_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Another process already replaced the stale session in the shared store
+    let store = Arc::new(MemorySessionStore::new());
+    store
+        .save(&StoredSession::new("testuser", "test_session_key_12345"))
+        .unwrap();
+
+    let client = create_test_client(&mock_server.uri())
+        .await
+        .with_session_store(store)
+        .resume_session("stale_key");
+
+    let info = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(info.call, "AA7BQ");
+}
+
+#[tokio::test]
+async fn test_export_and_import_session() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    assert!(client.export_session().await.is_none());
+    client.authenticate().await.unwrap();
+
+    let snapshot = client.export_session().await.unwrap();
+    assert_eq!(snapshot.key, "test_session_key_12345");
+    assert_eq!(snapshot.count, Some(42));
+
+    // A second client continues the session without logging in
+    let other = create_test_client(&mock_server.uri()).await;
+    other.import_session(snapshot.clone()).await.unwrap();
+    assert_eq!(other.export_session().await, Some(snapshot.clone()));
+    other.lookup_callsign("AA7BQ").await.unwrap();
+
+    let stale = SessionSnapshot {
+        created_at: 0,
+        ..snapshot
+    };
+    assert!(matches!(
+        other.import_session(stale).await,
+        Err(QrzXmlError::InvalidInput { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_invalid_input_handling() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server.uri()).await;
+
+    // Test empty callsign
+    let result = client.lookup_callsign("").await;
+    assert!(result.is_err());
+    match result.unwrap_err() {
+        QrzXmlError::InvalidInput { .. } => {}
+        _ => panic!("Expected InvalidInput error for empty callsign"),
+    }
+}
+
+#[tokio::test]
+async fn test_session_info_tracking() {
+    let mock_server = MockServer::start().await;
+
+    // Mock login
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .and(query_param("password", "testpass"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+
+    // Before authentication
+    assert!(!client.is_authenticated().await);
+    let session_info = client.session_info().await;
+    assert!(session_info.is_some()); // session_info always returns Some, but values may be None
+    let (count, sub_exp) = session_info.unwrap();
+    assert_eq!(count, None);
+    assert_eq!(sub_exp, None);
+
+    // After authentication
+    client.authenticate().await.unwrap();
+    assert!(client.is_authenticated().await);
+
+    let session_info = client.session_info().await;
+    assert!(session_info.is_some());
+    let (count, sub_exp) = session_info.unwrap();
+    assert_eq!(count, Some(42));
+    assert_eq!(sub_exp, Some("Wed Jan 1 12:34:03 2025".to_string()));
+}
+
+#[tokio::test]
+async fn test_subscription_status_tracking() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    assert_eq!(
+        client.subscription_status().await,
+        SubscriptionStatus::Unknown
+    );
+
+    client.authenticate().await.unwrap();
+    assert_eq!(
+        client.subscription_status().await,
+        SubscriptionStatus::Subscriber {
+            expires: "Wed Jan 1 12:34:03 2025".to_string()
+        }
+    );
+}
+
+const SAMPLE_TOO_MANY_SESSIONS_RESPONSE: &str = r#"<?xml version="1.0" ?>
+<QRZDatabase version="1.34">
+  <Session>
+    <Error>Too many sessions for this user</Error>
+    <GMTime>Sun Aug 16 03:57:47 2024</GMTime>
+  </Session>
+</QRZDatabase>"#;
+
+#[tokio::test]
+async fn test_too_many_sessions_backs_off() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_TOO_MANY_SESSIONS_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+
+    let result = client.authenticate().await;
+    assert!(matches!(result, Err(QrzXmlError::TooManySessions)));
+
+    // The second attempt must not hit the server while backing off
+    let result = client.lookup_callsign("AA7BQ").await;
+    assert!(matches!(result, Err(QrzXmlError::TooManySessions)));
+}
+
+#[cfg(feature = "iso3166")]
+#[tokio::test]
+async fn test_mailing_country_from_ccode() {
+    use qrz_xml::CountryCode;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(
+                SAMPLE_CALLSIGN_RESPONSE
+                    .replace("<dxcc>291</dxcc>", "<dxcc>291</dxcc><ccode>291</ccode>"),
+            ),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_DXCC_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let info = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(
+        client.mailing_country(&info).await.unwrap(),
+        Some(CountryCode::USA)
+    );
+
+    let no_ccode = qrz_xml::CallsignInfo::default();
+    assert_eq!(client.mailing_country(&no_ccode).await.unwrap(), None);
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn test_too_many_sessions_backoff_expires_with_mock_clock() {
+    use qrz_xml::clock::MockClock;
+    use std::time::Duration;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_TOO_MANY_SESSIONS_RESPONSE))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let clock = Arc::new(MockClock::new());
+    let client = create_test_client(&mock_server.uri())
+        .await
+        .with_clock(clock.clone());
+
+    assert!(matches!(
+        client.authenticate().await,
+        Err(QrzXmlError::TooManySessions)
+    ));
+    assert!(matches!(
+        client.authenticate().await,
+        Err(QrzXmlError::TooManySessions)
+    ));
+
+    // Stepping past the backoff allows a new login without waiting for real
+    let backoff = QrzXmlClientConfig::default().too_many_sessions_backoff_seconds;
+    clock.advance(Duration::from_secs(backoff + 1));
+    client.authenticate().await.unwrap();
+    assert!(client.is_authenticated().await);
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn test_session_expiry_with_mock_clock() {
+    use qrz_xml::clock::{Clock, MockClock};
+    use qrz_xml::session_store::SESSION_MAX_AGE;
+    use std::time::Duration;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let store = Arc::new(MemorySessionStore::new());
+    let clock = Arc::new(MockClock::new());
+    let client = || async {
+        create_test_client(&mock_server.uri())
+            .await
+            .with_session_store(store.clone())
+            .with_clock(clock.clone())
+    };
+
+    let first = client().await;
+    first.authenticate().await.unwrap();
+    let snapshot = first.export_session().await.unwrap();
+
+    // Still fresh: a second client reuses the stored session
+    clock.advance(SESSION_MAX_AGE);
+    let second = client().await;
+    second.ensure_authenticated().await.unwrap();
+    second.import_session(snapshot.clone()).await.unwrap();
+
+    // Too old by the mock clock: the stored session and the snapshot are refused
+    clock.advance(Duration::from_secs(1));
+    assert!(snapshot.is_expired_at(clock.system_now()));
+    let third = client().await;
+    assert!(third.import_session(snapshot).await.is_err());
+    third.ensure_authenticated().await.unwrap();
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn test_pacing_spaces_lookups_near_ceiling() {
+    use qrz_xml::clock::MockClock;
+    use qrz_xml::PacingConfig;
+    use std::time::Duration;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        pacing: Some(PacingConfig::new(40)),
+        ..Default::default()
+    };
+    let clock = Arc::new(MockClock::new());
+    let client = Arc::new(
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config)
+            .unwrap()
+            .with_clock(clock.clone()),
+    );
+
+    client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(client.pacing_interval(), Some(Duration::from_secs(10)));
+
+    // The count is past the ceiling, so the next lookup waits the full interval
+    let second = tokio::spawn({
+        let client = Arc::clone(&client);
+        async move { client.lookup_callsign("AA7BQ").await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!second.is_finished());
+
+    clock.advance(Duration::from_secs(10));
+    second.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_too_many_sessions_reuses_previous_key() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_TOO_MANY_SESSIONS_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    client.authenticate().await.unwrap();
+
+    // Re-authentication is rejected, so the previous key is put back into use
+    client.reauthenticate().await.unwrap();
+    assert!(client.is_authenticated().await);
+}
+
+#[tokio::test]
+async fn test_too_many_sessions_never_reuses_expired_key() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "stale_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_TOO_MANY_SESSIONS_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri())
+        .await
+        .resume_session("stale_key");
+
+    // QRZ rejected the key, so it is not retried when the new login is refused
+    let result = client.lookup_callsign("AA7BQ").await;
+    assert!(matches!(result, Err(QrzXmlError::TooManySessions)));
+    assert!(!client.is_authenticated().await);
+}
+
+#[tokio::test]
+async fn test_concurrent_expired_lookups_log_in_once() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "stale_key"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
+        .expect(4)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(4)
+        .mount(&mock_server)
+        .await;
+
+    let client = Arc::new(
+        create_test_client(&mock_server.uri())
+            .await
+            .resume_session("stale_key"),
+    );
+
+    // All four fail on the same key before any of them renews it
+    let lookups: Vec<_> = (0..4)
+        .map(|_| {
+            let client = Arc::clone(&client);
+            tokio::spawn(async move { client.lookup_callsign("AA7BQ").await })
+        })
+        .collect();
+    for lookup in lookups {
+        assert_eq!(lookup.await.unwrap().unwrap().call, "AA7BQ");
+    }
+}
+
+#[tokio::test]
+async fn test_client_events() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("callsign", "INVALIDCALL"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_ERROR_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let mut events = client.subscribe_events();
+
+    let _ = client.lookup_callsign("INVALIDCALL").await;
+
+    assert_eq!(
+        events.recv().await.unwrap(),
+        ClientEvent::SessionEstablished {
+            count: Some(42),
+            sub_exp: Some("Wed Jan 1 12:34:03 2025".to_string()),
+        }
+    );
+    match events.recv().await.unwrap() {
+        ClientEvent::LookupFailed { kind, query, .. } => {
+            assert_eq!(kind, "callsign");
+            assert_eq!(query, "INVALIDCALL");
+        }
+        other => panic!("Expected LookupFailed event, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_quota_warnings() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        daily_lookup_limit: Some(50),
+        quota_warning_percentages: vec![80, 100],
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+    let mut events = client.subscribe_events();
+
+    // Login reports 42 lookups, the callsign response 43
+    client.lookup_callsign("AA7BQ").await.unwrap();
+    client.lookup_callsign("AA7BQ").await.unwrap();
+
+    let quota = client.quota();
+    assert_eq!(quota.used, Some(43));
+    assert_eq!(quota.remaining(), Some(7));
+
+    let warnings: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+        .filter(|e| matches!(e, ClientEvent::QuotaWarning { .. }))
+        .collect();
+    assert_eq!(
+        warnings,
+        vec![ClientEvent::QuotaWarning {
+            count: 42,
+            threshold: 40
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_daily_lookup_limit_enforced() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        daily_lookup_limit: Some(43),
+        enforce_daily_lookup_limit: true,
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+
+    // The callsign response reports 43 lookups, which uses up the budget
+    client.lookup_callsign("AA7BQ").await.unwrap();
+    let result = client.lookup_callsign("AA7BQ").await;
+    assert!(matches!(
+        result,
+        Err(QrzXmlError::QuotaExceeded {
+            used: 43,
+            limit: 43
+        })
+    ));
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn test_daily_lookup_limit_frees_up_after_window() {
+    use qrz_xml::clock::MockClock;
+    use std::time::Duration;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        daily_lookup_limit: Some(43),
+        enforce_daily_lookup_limit: true,
+        ..Default::default()
+    };
+    let clock = Arc::new(MockClock::new());
+    let client = QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config)
+        .unwrap()
+        .with_clock(clock.clone());
+
+    client.lookup_callsign("AA7BQ").await.unwrap();
+    assert!(matches!(
+        client.lookup_callsign("AA7BQ").await,
+        Err(QrzXmlError::QuotaExceeded { .. })
+    ));
+
+    // No new count arrives while blocked, yet the budget frees up with the window
+    clock.advance(Duration::from_secs(24 * 3600));
+    assert_eq!(client.quota().used, None);
+    client.lookup_callsign("AA7BQ").await.unwrap();
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn test_governor_recovers_after_window() {
+    use qrz_xml::clock::MockClock;
+    use qrz_xml::{GovernorConfig, GovernorPhase};
+    use std::time::Duration;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        concurrency_governor: Some(GovernorConfig::new(43)),
+        ..Default::default()
+    };
+    let clock = Arc::new(MockClock::new());
+    let client = QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config)
+        .unwrap()
+        .with_clock(clock.clone());
+
+    // The callsign response reports 43 lookups, the governor's ceiling
+    client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(client.governor_phase(), Some(GovernorPhase::Stopped));
+    assert!(matches!(
+        client.lookup_callsign("AA7BQ").await,
+        Err(QrzXmlError::QuotaExceeded { .. })
+    ));
+
+    clock.advance(Duration::from_secs(24 * 3600));
+    assert_eq!(client.governor_phase(), Some(GovernorPhase::FullSpeed));
+    client.lookup_callsign("AA7BQ").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_rate_limited_response() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(429))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let mut events = client.subscribe_events();
+
+    let result = client.authenticate().await;
+    assert!(matches!(result, Err(QrzXmlError::RateLimitExceeded)));
+    assert_eq!(events.recv().await.unwrap(), ClientEvent::RateLimited);
+}
+
+#[cfg(feature = "request-signing")]
+#[tokio::test]
+async fn test_signed_requests() {
+    use qrz_xml::signing::{RequestSigner, SigningKey};
+    use wiremock::matchers::header_exists;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(header_exists("X-Proxy-Auth"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        request_signer: Some(
+            RequestSigner::new(SigningKey::Static(b"secret".to_vec()))
+                .with_header_name("X-Proxy-Auth"),
+        ),
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+
+    client.authenticate().await.unwrap();
+}
+
+#[cfg(feature = "strict")]
+#[tokio::test]
+async fn test_probe_api_reports_unknown_fields() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let drifted = SAMPLE_CALLSIGN_RESPONSE.replace(
+        "<call>AA7BQ</call>",
+        "<call>AA7BQ</call><newfield>x</newfield>",
+    );
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(drifted))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let report = client.probe_api().await.unwrap();
+
+    assert_eq!(report.api_version.as_deref(), Some("1.34"));
+    assert!(report.has_drift());
+    assert_eq!(report.unknown_fields, vec!["Callsign.newfield"]);
+}
+
+// Helper functions for testing individual components
+
+#[tokio::test]
+async fn test_error_type_properties() {
+    // Test error categorization
+    assert!(QrzXmlError::SessionExpired.should_reauthenticate());
+    assert!(QrzXmlError::NoSessionKey.should_reauthenticate());
+    assert!(!QrzXmlError::CallsignNotFound {
+        callsign: "TEST".to_string()
+    }
+    .should_reauthenticate());
+
+    assert!(QrzXmlError::RateLimitExceeded.is_retryable());
+    assert!(QrzXmlError::SessionExpired.is_retryable());
+    assert!(!QrzXmlError::AuthenticationFailed {
+        reason: "bad pass".to_string()
+    }
     .is_retryable());
 
-    assert!(QrzXmlError::SubscriptionRequired.is_permission_error());
-    assert!(QrzXmlError::ConnectionRefused.is_permission_error());
-    assert!(!QrzXmlError::CallsignNotFound {
-        callsign: "TEST".to_string()
+    assert!(QrzXmlError::SubscriptionRequired.is_permission_error());
+    assert!(QrzXmlError::ConnectionRefused { until: 0 }.is_permission_error());
+    assert!(!QrzXmlError::CallsignNotFound {
+        callsign: "TEST".to_string()
+    }
+    .is_permission_error());
+}
+
+#[tokio::test]
+async fn test_credential_pool_fails_over_to_next_account() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "first"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_AUTH_ERROR_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "second"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("s", "test_session_key_12345"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        max_retries: 1,
+        ..Default::default()
+    };
+    let pool = CredentialPool::new(
+        [("first", "pass1"), ("second", "pass2")],
+        ApiVersion::Current,
+        config,
+        RotationStrategy::Failover,
+    )
+    .unwrap();
+
+    let info = pool.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(info.call, "AA7BQ");
+
+    // The failed account is skipped from now on
+    pool.lookup_callsign("AA7BQ").await.unwrap();
+    let quotas = pool.quotas();
+    assert_eq!(quotas[0].0, "first");
+    assert_eq!(quotas[1].1.used, Some(43));
+}
+
+#[tokio::test]
+async fn test_lookup_worker_pipeline() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "INVALIDCALL"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_ERROR_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = Arc::new(create_test_client(&mock_server.uri()).await);
+    let (input, rx) = tokio::sync::mpsc::channel(4);
+    let (tx, mut output) = tokio::sync::mpsc::channel(1);
+    let worker = client.spawn_lookup_worker(rx, tx);
+
+    input.send("AA7BQ".to_string()).await.unwrap();
+    input.send("INVALIDCALL".to_string()).await.unwrap();
+    drop(input);
+
+    let first = output.recv().await.unwrap();
+    assert_eq!(first.callsign, "AA7BQ");
+    assert_eq!(first.result.unwrap().call, "AA7BQ");
+
+    let second = output.recv().await.unwrap();
+    assert_eq!(second.callsign, "INVALIDCALL");
+    assert!(matches!(second.result, Err(QrzXmlError::ApiError { .. })));
+
+    // The worker stops once the input is closed and drained
+    assert!(output.recv().await.is_none());
+    worker.join().await;
+}
+
+#[tokio::test]
+async fn test_lookup_worker_shutdown() {
+    let mock_server = MockServer::start().await;
+    let client = Arc::new(create_test_client(&mock_server.uri()).await);
+
+    let (_input, rx) = tokio::sync::mpsc::channel::<String>(1);
+    let (tx, mut output) = tokio::sync::mpsc::channel(1);
+    let worker = client.spawn_lookup_worker(rx, tx);
+
+    assert!(!worker.is_finished());
+    worker.shutdown().await;
+    assert!(output.recv().await.is_none());
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn test_field_level_merge_keeps_cached_fields() {
+    use qrz_xml::clock::MockClock;
+    use qrz_xml::CacheMergePolicy;
+    use std::time::Duration;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    // A lapsed subscription only returns the basic fields
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<?xml version="1.0" ?>
+<QRZDatabase version="1.34">
+  <Callsign>
+    <call>AA7BQ</call>
+    <fname>FRED L</fname>
+    <name>LLOYD</name>
+    <country>United States</country>
+  </Callsign>
+  <Session>
+    <Key>test_session_key_12345</Key>
+    <Count>44</Count>
+  </Session>
+</QRZDatabase>"#,
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        callsign_cache_ttl_seconds: Some(60),
+        callsign_merge_policy: CacheMergePolicy::FieldLevel,
+        ..Default::default()
+    };
+    let clock = Arc::new(MockClock::new());
+    let client = QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config)
+        .unwrap()
+        .with_clock(clock.clone());
+
+    let full = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(full.grid.as_deref(), Some("DM32af"));
+
+    clock.advance(Duration::from_secs(61));
+    let merged = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(merged.fname.as_deref(), Some("FRED L"));
+    assert_eq!(merged.grid.as_deref(), Some("DM32af"));
+    assert_eq!(merged.email, full.email);
+}
+
+#[tokio::test]
+async fn test_validate_queries_rejects_locally() {
+    let mock_server = MockServer::start().await;
+
+    // Neither a login nor a lookup is sent for implausible input
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        validate_queries: true,
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+
+    assert!(matches!(
+        client.lookup_callsign("HELLO").await,
+        Err(QrzXmlError::InvalidInput { .. })
+    ));
+    assert!(matches!(
+        client.lookup_dxcc_by_callsign("AA7BQ!").await,
+        Err(QrzXmlError::InvalidInput { .. })
+    ));
+    assert!(matches!(
+        client.lookup_dxcc_entity(9999).await,
+        Err(QrzXmlError::InvalidInput { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_lookup_with_callsign_type() {
+    use qrz_xml::Callsign;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let callsign = Callsign::parse("aa7bq").unwrap();
+    let info = client.lookup_callsign(&callsign).await.unwrap();
+    assert_eq!(info.call, "AA7BQ");
+
+    // Owned strings work as well
+    let owned = String::from("AA7BQ");
+    assert_eq!(client.lookup_callsign(&owned).await.unwrap().call, "AA7BQ");
+}
+
+#[tokio::test]
+async fn test_callsign_normalization() {
+    use qrz_xml::{CallsignNormalization, LookupOptions};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ/P"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_ERROR_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        callsign_normalization: CallsignNormalization::StripOperatingSuffix,
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+
+    let info = client.lookup_callsign("aa7bq/p").await.unwrap();
+    assert_eq!(info.call, "AA7BQ");
+    assert_eq!(info.queried_callsign.as_deref(), Some("aa7bq/p"));
+
+    let info = client
+        .lookup_callsign_with(
+            "VE3/AA7BQ",
+            &LookupOptions::default().with_normalization(CallsignNormalization::BaseCall),
+        )
+        .await
+        .unwrap();
+    assert_eq!(info.queried_callsign.as_deref(), Some("VE3/AA7BQ"));
+
+    // Normalization can be turned off per request
+    let options = LookupOptions::default().with_normalization(CallsignNormalization::None);
+    assert!(client
+        .lookup_callsign_with("AA7BQ/P", &options)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_parse_modes() {
+    use qrz_xml::parse::ParseWarningKind;
+    use qrz_xml::ParseMode;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE.replace(
+                "<fname>FRED</fname>",
+                "<fname>FRED</fname><fname>FREDDY</fname>",
+            )),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let mut events = client.subscribe_events();
+    let info = client.lookup_callsign("AA7BQ").await.unwrap();
+    assert_eq!(info.fname.as_deref(), Some("FRED"));
+    let (query, warnings) = loop {
+        if let ClientEvent::ParseWarnings { query, warnings } = events.recv().await.unwrap() {
+            break (query, warnings);
+        }
+    };
+    assert_eq!(query, "callsign=AA7BQ");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, ParseWarningKind::Duplicate);
+    assert_eq!(warnings[0].path, "Callsign.fname");
+    assert_eq!(client.diagnostics().await.parse_warnings, 1);
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        parse_mode: ParseMode::Strict,
+        ..Default::default()
+    };
+    let strict =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+    let err = strict.lookup_callsign("AA7BQ").await.unwrap_err();
+    assert!(matches!(err, QrzXmlError::XmlParsing(_)));
+    assert!(err.to_string().contains("Callsign.fname"));
+}
+
+#[tokio::test]
+async fn test_quota_reset_on_count_rollover() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    // QRZ's next response comes from a new 24-hour window
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            SAMPLE_CALLSIGN_RESPONSE.replace("<Count>43</Count>", "<Count>2</Count>"),
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let mut events = client.subscribe_events();
+
+    client.lookup_callsign("AA7BQ").await.unwrap();
+    client.lookup_callsign("AA7BQ").await.unwrap();
+
+    let resets: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+        .filter(|e| matches!(e, ClientEvent::QuotaReset { .. }))
+        .collect();
+    assert_eq!(
+        resets,
+        vec![ClientEvent::QuotaReset {
+            previous: 43,
+            count: 2
+        }]
+    );
+    assert_eq!(client.quota().used, Some(2));
+}
+
+#[tokio::test]
+async fn test_lookup_all_dxcc_entities() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "all"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(include_str!("../fixtures/dxcc_all.xml")),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        dxcc_cache_ttl_seconds: Some(3600),
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+    let entities = client.lookup_all_dxcc_entities().await.unwrap();
+    assert_eq!(entities.len(), 340);
+    assert_eq!(entities[0].name, "Canada");
+
+    // Every entity is cached, so single lookups need no request
+    let japan = client.lookup_dxcc_entity(339).await.unwrap();
+    assert_eq!(japan.name, "Japan");
+    assert_eq!(japan.cqzone, Some(25));
+}
+
+#[tokio::test]
+async fn test_dxcc_entities_stream() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "all"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(include_str!("../fixtures/dxcc_all.xml")),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let mut stream = client.dxcc_entities_stream().await.unwrap();
+    let mut names = Vec::new();
+    while let Some(dxcc) = stream.next().await {
+        names.push(dxcc.unwrap().name);
     }
-    .is_permission_error());
+    assert_eq!(names.len(), 340);
+    assert_eq!(stream.entities_read(), 340);
+    assert_eq!(names.last().map(String::as_str), Some("Republic of Kosovo"));
+
+    // Callers can stop early by dropping the stream
+    let mut stream = client.dxcc_entities_stream().await.unwrap();
+    let first = stream.next().await.unwrap().unwrap();
+    assert_eq!(first.dxcc, 1);
+    drop(stream);
+    assert_eq!(client.lookup_all_dxcc_entities().await.unwrap().len(), 340);
+}
+
+#[tokio::test]
+async fn test_dxcc_entities_stream_session_timeout() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "all"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_SESSION_TIMEOUT_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    let mut stream = client.dxcc_entities_stream().await.unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(QrzXmlError::SessionExpired))
+    ));
+    assert!(stream.next().await.is_none());
+}
+
+#[cfg(feature = "offline-dxcc")]
+#[tokio::test]
+async fn test_dxcc_entity_offline_prefers_live_cache() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "291"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            SAMPLE_DXCC_RESPONSE.replace("<cqzone>3</cqzone>", "<cqzone>5</cqzone>"),
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        dxcc_cache_ttl_seconds: Some(3600),
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+    // Served from the bundled snapshot without logging in
+    assert_eq!(client.dxcc_entity_offline(339).unwrap().name, "Japan");
+    assert_eq!(client.dxcc_entity_offline(291).unwrap().cqzone, Some(3));
+    assert!(client.dxcc_entity_offline(9999).is_none());
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+    client.lookup_dxcc_entity(291).await.unwrap();
+    assert_eq!(client.dxcc_entity_offline(291).unwrap().cqzone, Some(5));
+}
+
+#[cfg(feature = "offline-dxcc")]
+#[tokio::test]
+async fn test_dxcc_for_callsign_offline() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("dxcc", "KG4ABC"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_DXCC_RESPONSE))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    assert_eq!(
+        client
+            .dxcc_for_callsign_offline("JA1ABC")
+            .await
+            .unwrap()
+            .name,
+        "Japan"
+    );
+    assert_eq!(
+        client
+            .dxcc_for_callsign_offline("W1AW/KH6")
+            .await
+            .unwrap()
+            .dxcc,
+        110
+    );
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+    // KG4 is shared by Guantanamo Bay and the United States
+    let dxcc = client.dxcc_for_callsign_offline("KG4ABC").await.unwrap();
+    assert_eq!(dxcc.dxcc, 291);
+}
+
+#[tokio::test]
+async fn test_latin1_response_body() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    // ISO-8859-1 bytes, declared only in the XML declaration
+    let declared: &[u8] = include_bytes!("../fixtures/callsign_latin1.xml");
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "F1ABC"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(declared, "text/xml"))
+        .mount(&mock_server)
+        .await;
+
+    // The same bytes without any declaration
+    let declaration_end = declared.iter().position(|&b| b == b'\n').unwrap() + 1;
+    let undeclared = declared[declaration_end..].to_vec();
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "F2ABC"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(undeclared, "text/xml"))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_test_client(&mock_server.uri()).await;
+    for callsign in ["F1ABC", "F2ABC"] {
+        let info = client.lookup_callsign(callsign).await.unwrap();
+        assert_eq!(info.full_name(), Some("José Müller".to_string()));
+        assert_eq!(info.addr2.as_deref(), Some("Saint-Étienne"));
+    }
+}
+
+#[tokio::test]
+async fn test_diagnostics_are_redacted() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("username", "testuser"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_LOGIN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "AA7BQ"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_CALLSIGN_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("callsign", "INVALIDCALL"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(SAMPLE_ERROR_RESPONSE))
+        .mount(&mock_server)
+        .await;
+
+    let config = QrzXmlClientConfig {
+        base_url: format!("{}/xml", mock_server.uri()),
+        ..Default::default()
+    }
+    .with_header("X-Api-Token", "proxy-secret");
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+
+    client.lookup_callsign("AA7BQ").await.unwrap();
+    assert!(client.lookup_callsign("INVALIDCALL").await.is_err());
+
+    let diagnostics = client.diagnostics().await;
+    assert_eq!(diagnostics.server_version.as_deref(), Some("1.34"));
+    assert!(diagnostics.session.authenticated);
+    assert_eq!(diagnostics.session.count, Some(43));
+    assert_eq!(diagnostics.recent_errors.len(), 1);
+    assert_eq!(diagnostics.recent_errors[0].query, "INVALIDCALL");
+    assert_eq!(
+        diagnostics.config["extra_headers"],
+        "X-Api-Token=<redacted>"
+    );
+
+    let json = serde_json::to_string(&diagnostics).unwrap();
+    for secret in ["testpass", "test_session_key_12345", "proxy-secret"] {
+        assert!(!json.contains(secret), "{} leaked", secret);
+    }
+}
+
+#[tokio::test]
+async fn test_diagnostics_strip_urls_from_network_errors() {
+    // Nothing listens on this port, so the login fails with a network error
+    let config = QrzXmlClientConfig {
+        base_url: "http://127.0.0.1:9/xml".to_string(),
+        timeout_seconds: 2,
+        ..Default::default()
+    };
+    let client =
+        QrzXmlClient::with_config("testuser", "testpass", ApiVersion::Current, config).unwrap();
+    assert!(client.lookup_callsign("AA7BQ").await.is_err());
+
+    let diagnostics = client.diagnostics().await;
+    assert_eq!(diagnostics.recent_errors.len(), 1);
+    assert!(!diagnostics.recent_errors[0].error.contains("testpass"));
 }